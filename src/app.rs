@@ -1,25 +1,39 @@
 use crate::command::FlowCommand;
+use crate::command::TransportCommand;
 use crate::command_queue::COMMAND_QUEUE;
+use crate::engine::BuiltinUniforms;
 use crate::flow_window::FlowWindow;
 use crate::mc_guffin_container::McGuffinContainer;
 use crate::mc_guffin_window::McGuffinWindow;
 use crate::performance_window::PerformanceWindow;
+use crate::presets_window::PresetsWindow;
 use crate::project::GridPos;
 use crate::project::Resource;
 use crate::project::Step;
 use crate::project_window::ProjectWindow;
 use crate::properties_window::PropertiesWindow;
+use crate::remote_window::RemoteWindow;
 use crate::resources_window::ResourcesWindow;
+use crate::rocket_client::RocketEvent;
+use crate::script_window::ScriptWindow;
 use crate::shaders_window::ShadersWindow;
 use crate::state::State;
+use crate::sync_window::SyncWindow;
+use crate::transport_window::TransportWindow;
+use crate::CliMode;
+use crate::CliOptions;
 use crate::Command;
+use crate::FrameExporter;
 use crate::WindowManager;
 use color_eyre::Result;
 use egui::Color32;
 use egui::RichText;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Default, serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct TemplateApp {
     //    #[serde(skip)]
@@ -29,27 +43,26 @@ pub struct TemplateApp {
 
     state: State,
 
-    #[serde(skip)]
-    start_time: std::time::Instant,
     //#[serde(skip)]
     // windows_menu: Option<WindowsMenu>,
-}
-
-impl Default for TemplateApp {
-    fn default() -> Self {
-        Self {
-            //windows: Default::default(),
-            window_manager: Default::default(),
-            state: Default::default(),
-            start_time: std::time::Instant::now(),
-            // windows_menu: Default::default(),
-        }
-    }
+    #[serde(skip)]
+    frame_exporter: Option<FrameExporter>,
+    #[serde(skip)]
+    exit_code: Arc<AtomicI32>,
 }
 
 impl TemplateApp {
     /// Called once before the first frame.
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        Self::new_with_cli_options(cc, &CliOptions::default(), Default::default())
+    }
+
+    /// Called once before the first frame, `exit_code` is set when a batch mode (e.g. frame export) finishes.
+    pub fn new_with_cli_options(
+        cc: &eframe::CreationContext<'_>,
+        cli_options: &CliOptions,
+        exit_code: Arc<AtomicI32>,
+    ) -> Self {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
@@ -67,6 +80,11 @@ impl TemplateApp {
             s.window_manager.add(Box::new(PropertiesWindow::default()));
             s.window_manager.add(Box::new(ProjectWindow::default()));
             s.window_manager.add(Box::new(ResourcesWindow::default()));
+            s.window_manager.add(Box::new(TransportWindow::default()));
+            s.window_manager.add(Box::new(SyncWindow::default()));
+            s.window_manager.add(Box::new(RemoteWindow::default()));
+            s.window_manager.add(Box::new(ScriptWindow::default()));
+            s.window_manager.add(Box::new(PresetsWindow::default()));
 
             let app_save: AppSave =
                 eframe::get_value(storage, &format!("{}-custom", eframe::APP_KEY))
@@ -77,20 +95,55 @@ impl TemplateApp {
             Default::default()
         };
 
+        s.exit_code = exit_code;
+        if let Some(project_path) = cli_options.project_path() {
+            s.state.set_project_path(project_path.to_path_buf());
+        }
+        if let Some(port) = cli_options.osc_port() {
+            if let Err(e) = s.state.start_osc_server(port) {
+                eprintln!("Failed starting OSC server on port {port} -> {e:?}");
+            }
+        }
+        if cli_options.fullscreen() {
+            s.state.mc_guffin_is_fullscreen = true;
+            s.window_manager.open("McGuffin");
+        }
+        if let CliMode::ExportFrames {
+            folder,
+            frame_count,
+            fps,
+        } = cli_options.mode()
+        {
+            s.state.mc_guffin_is_fullscreen = true;
+            s.window_manager.open("McGuffin");
+            s.frame_exporter = Some(FrameExporter::new(folder.clone(), *frame_count, *fps));
+        }
+
         s.state.reload_project();
-        if let Some(get_proc_address) = cc.get_proc_address {
-            let mgc = McGuffinContainer::default();
-            //let mgc = s.mc_guffin.clone();
-            let mgc2 = mgc.clone();
-            match mgc.lock().setup(get_proc_address) {
-                Ok(()) => {
-                    s.state.set_mc_guffin(mgc2);
-                }
-                Err(e) => {
-                    eprintln!("McGuffin setup error -> {e:#?}");
-                }
-            };
+        #[cfg(target_arch = "wasm32")]
+        if let Some(storage) = cc.storage {
+            s.state.load_project_from_storage(storage);
         }
+        let mgc = McGuffinContainer::default();
+        //let mgc = s.mc_guffin.clone();
+        #[cfg(not(target_arch = "wasm32"))]
+        let setup_result = cc
+            .get_proc_address
+            .map(|get_proc_address| mgc.lock().setup(get_proc_address));
+        #[cfg(target_arch = "wasm32")]
+        let setup_result = cc.gl.clone().map(|gl| mgc.lock().setup_glow(gl));
+        match setup_result {
+            Some(Ok(())) => {
+                if let Some(start_label) = cli_options.start_label() {
+                    mgc.lock().set_start_label(start_label);
+                }
+                s.state.set_mc_guffin(mgc);
+            }
+            Some(Err(e)) => {
+                eprintln!("McGuffin setup error -> {e:#?}");
+            }
+            None => {}
+        };
         /*
         // not program load at this point, so no uniforms to create properties from
         if let Some( mgc ) = s.state.mc_guffin_cloned() {
@@ -102,8 +155,6 @@ impl TemplateApp {
         }
         */
 
-        s.start_time = std::time::Instant::now();
-
         s
     }
 }
@@ -128,6 +179,45 @@ impl TemplateApp {
         Ok(app_save)
     }
 
+    fn update_frame_exporter(&mut self, ctx: &egui::Context) {
+        let Some(frame_exporter) = &mut self.frame_exporter else {
+            return;
+        };
+
+        let result = if let Some(mgc) = self.state.mc_guffin_cloned() {
+            let mut mg = mgc.lock();
+            let saved = if let Some(captured_frame) = mg.take_captured_frame() {
+                frame_exporter.save_frame(&captured_frame)
+            } else {
+                Ok(())
+            };
+            match saved {
+                Ok(()) if frame_exporter.is_done() => {
+                    mg.set_fixed_time(None);
+                    Some(0)
+                }
+                Ok(()) => {
+                    mg.set_fixed_time(Some(frame_exporter.time()));
+                    mg.request_capture();
+                    None
+                }
+                Err(e) => {
+                    eprintln!("Failed exporting frame {e:?}");
+                    Some(1)
+                }
+            }
+        } else {
+            eprintln!("Can not export frames without McGuffin");
+            Some(1)
+        };
+
+        if let Some(exit_code) = result {
+            self.exit_code.store(exit_code, Ordering::Relaxed);
+            self.frame_exporter = None;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+
     fn handle_flow_command(&mut self, flow_command: FlowCommand) {
         match flow_command {
             FlowCommand::RemoveSteps { grid_rect } => {
@@ -256,12 +346,121 @@ impl TemplateApp {
             }
         }
     }
+
+    fn update_midi(&mut self) {
+        let Some(midi_input) = &mut self.state.midi_input else {
+            return;
+        };
+        for event in midi_input.poll() {
+            if let Some(property) = self.state.midi_learn.take() {
+                self.state
+                    .project
+                    .midi_bindings_mut()
+                    .bind(event.source, &property);
+            }
+            self.state.project.apply_midi(&event.source, event.value);
+        }
+    }
+
+    fn update_preset_transition(&mut self) {
+        let Some(transition) = &self.state.preset_transition else {
+            return;
+        };
+        self.state
+            .project
+            .morph_presets(&transition.from, &transition.to, transition.t());
+        if transition.is_done() {
+            self.state.preset_transition = None;
+        }
+    }
+
+    fn update_rocket(&mut self) {
+        let Some(rocket) = &mut self.state.rocket else {
+            return;
+        };
+        let events = match rocket.poll() {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("Sync tracker connection lost -> {e:?}");
+                self.state.rocket = None;
+                return;
+            }
+        };
+        for event in events {
+            match event {
+                RocketEvent::SetKey { track, key } => {
+                    self.state
+                        .project
+                        .with_sync_tracks_mut(|st| st.set_key(&track, key));
+                }
+                RocketEvent::DeleteKey { track, row } => {
+                    self.state
+                        .project
+                        .with_sync_tracks_mut(|st| st.delete_key(&track, row));
+                }
+                RocketEvent::SetRow { row } => {
+                    let time = self.state.project.sync_tracks().row_to_time(row);
+                    self.handle_transport_command(TransportCommand::Seek { time });
+                }
+                RocketEvent::Pause { paused } => {
+                    self.handle_transport_command(if paused {
+                        TransportCommand::Pause
+                    } else {
+                        TransportCommand::Play
+                    });
+                }
+                RocketEvent::SaveTracks => {
+                    if let Err(e) = self.state.export_sync_tracks() {
+                        eprintln!("Failed exporting sync tracks {e:?}");
+                    }
+                    self.state.save_project();
+                }
+            }
+        }
+
+        let Some(mgc) = self.state.mc_guffin_cloned() else {
+            return;
+        };
+        let time = mgc.lock().clock().time();
+        let row = self.state.project.sync_tracks().nearest_row(time);
+        let names = self.state.project.sync_track_names();
+        if let Some(rocket) = &mut self.state.rocket {
+            let result = names
+                .iter()
+                .try_for_each(|name| rocket.request_track(name))
+                .and_then(|_| rocket.send_row(row));
+            if let Err(e) = result {
+                eprintln!("Sync tracker connection lost -> {e:?}");
+                self.state.rocket = None;
+            }
+        }
+    }
+
+    fn handle_transport_command(&mut self, transport_command: TransportCommand) {
+        let Some(mgc) = self.state.mc_guffin_cloned() else {
+            return;
+        };
+        let mut mg = mgc.lock();
+        let clock = mg.clock_mut();
+        match transport_command {
+            TransportCommand::Play => clock.play(),
+            TransportCommand::Pause => clock.pause(),
+            TransportCommand::TogglePlay => clock.toggle_play(),
+            TransportCommand::StepFrames { frames } => clock.step(frames),
+            TransportCommand::SetSpeed { speed } => clock.set_speed(speed),
+            TransportCommand::Seek { time } => clock.seek(time),
+            TransportCommand::Nop => {}
+        }
+    }
 }
 impl eframe::App for TemplateApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         self.state.save_project();
         let _todo_handle = self.state.save_all_resources();
+        // there is no project folder on web, so keep the project in the browser storage
+        #[cfg(target_arch = "wasm32")]
+        self.state.save_project_to_storage(storage);
 
         eframe::set_value(storage, eframe::APP_KEY, self);
 
@@ -282,7 +481,7 @@ impl eframe::App for TemplateApp {
     */
 
     /// Called each time the UI needs repainting, which may be many times per second.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         /* :TODO: :( doesn't work
         // quit handling
         ctx.input(|i|{
@@ -299,21 +498,59 @@ impl eframe::App for TemplateApp {
                 eprintln!("ALT-Enter");
                 let _ = COMMAND_QUEUE.send(Command::ToggleFullscreen);
             }
+            if i.consume_key(egui::Modifiers::ALT, egui::Key::Space) {
+                let _ = COMMAND_QUEUE.send(Command::Transport {
+                    transport_command: TransportCommand::TogglePlay,
+                });
+            }
         });
 
+        self.update_frame_exporter(ctx);
+        self.update_rocket();
+        self.update_midi();
+        self.update_preset_transition();
+
         // McGuffin
         {
             if let Some(mgc) = self.state.mc_guffin_cloned() {
                 let mut mg = mgc.lock();
                 mg.update_from_project(&self.state.project);
+                let linked_programs = mg.take_linked_programs();
+                if !linked_programs.is_empty() {
+                    // uniforms set by the flow, or built in, need no property
+                    let flow_uniforms: Vec<String> = self
+                        .state
+                        .project
+                        .flow()
+                        .steps()
+                        .iter()
+                        .filter_map(|(s, _)| match s {
+                            Step::SetUniformF32 { name, .. }
+                            | Step::SetUniformF64 { name, .. }
+                            | Step::SetUniformVec3F32 { name, .. } => Some(name.clone()),
+                            _ => None,
+                        })
+                        .chain(BuiltinUniforms::NAMES.iter().map(|n| n.to_string()))
+                        .collect();
+                    self.state.project.with_property_manager_mut(|pm| {
+                        for lp in linked_programs.iter() {
+                            // annotations first, they can choose the kind of property
+                            pm.apply_shader_annotations(&lp.resource_id, &lp.shader_annotations);
+                            pm.ensure_program_properties(
+                                &lp.resource_id,
+                                &lp.uniforms,
+                                &flow_uniforms,
+                            );
+                            pm.set_program_usage(&lp.resource_id, &lp.uniforms);
+                        }
+                    });
+                }
 
                 /*
                 self.state.project.with_property_manager_mut(|pm| {
                     pm.ensure_all_properties_from_uniforms(mg.uniform_manager());
                 });
                 */
-                let t = self.start_time.elapsed().as_secs_f64();
-                mg.set_time(t);
             }
         }
         if !self.state.mc_guffin_is_fullscreen {
@@ -409,6 +646,30 @@ impl eframe::App for TemplateApp {
                     });
                     // self.state.project.property_manager.delete_entry(&name);
                 }
+                Command::EnterFullscreen => {
+                    self.state.mc_guffin_is_fullscreen = true;
+                }
+                Command::SetProperty { name, values } => {
+                    let mut found = false;
+                    self.state.project.with_property_manager_mut(|pm| {
+                        if let Some(p) = pm.get_mut(&name) {
+                            found = p.value_mut().set_from_f64s(&values);
+                        }
+                    });
+                    if !found {
+                        eprintln!("Can not set property {name} to {values:?}");
+                    }
+                }
+                Command::SetStartLabel { label } => {
+                    if let Some(mgc) = self.state.mc_guffin_cloned() {
+                        mgc.lock().set_start_label(&label);
+                    }
+                }
+                Command::ResetFeedback => {
+                    if let Some(mgc) = self.state.mc_guffin_cloned() {
+                        mgc.lock().reset_feedback();
+                    }
+                }
                 Command::LeaveFullscreen => {
                     // :TODO: side effects
                     self.state.mc_guffin_is_fullscreen = false;
@@ -559,7 +820,7 @@ impl eframe::App for TemplateApp {
                     self.state.project.with_flow_mut(|f| {
                         f.with_step_at_mut(&grid_pos, |s| {
                             let new_name = &name;
-                            let new_values: &[String;3] = &values;
+                            let new_values: &[String; 3] = &values;
                             match s {
                                 Step::SetUniformVec3F32 {
                                     name,
@@ -568,9 +829,9 @@ impl eframe::App for TemplateApp {
                                 } => {
                                     *name = new_name.to_string();
                                     *values = [
-                                        new_values[ 0 ].to_string(),
-                                        new_values[ 1 ].to_string(),
-                                        new_values[ 2 ].to_string(),
+                                        new_values[0].to_string(),
+                                        new_values[1].to_string(),
+                                        new_values[2].to_string(),
                                     ];
                                     *version += 1;
                                 }
@@ -593,9 +854,134 @@ impl eframe::App for TemplateApp {
                         })
                     });
                 }
+                Command::HackStepDispatchSetResourceIdAndGroups {
+                    grid_pos,
+                    resource_id,
+                    groups,
+                } => {
+                    self.state.project.with_flow_mut(|f| {
+                        f.with_step_at_mut(&grid_pos, |s| {
+                            let new_resource_id = &resource_id;
+                            let new_groups = groups;
+                            if let Step::Dispatch {
+                                resource_id,
+                                groups,
+                                version,
+                            } = s
+                            {
+                                *resource_id = new_resource_id.to_string();
+                                *groups = new_groups;
+                                *version += 1;
+                            }
+                        })
+                    });
+                }
+                Command::HackStepDrawMeshSetResourceIdAndInstances {
+                    grid_pos,
+                    resource_id,
+                    instances,
+                } => {
+                    self.state.project.with_flow_mut(|f| {
+                        f.with_step_at_mut(&grid_pos, |s| {
+                            let new_resource_id = &resource_id;
+                            let new_instances = instances;
+                            if let Step::DrawMesh {
+                                resource_id,
+                                instances,
+                                version,
+                            } = s
+                            {
+                                *resource_id = new_resource_id.to_string();
+                                *instances = new_instances;
+                                *version += 1;
+                            }
+                        })
+                    });
+                }
+                Command::HackStepGeometrySetParameters {
+                    grid_pos,
+                    detail,
+                    instances,
+                    quads,
+                } => {
+                    self.state.project.with_flow_mut(|f| {
+                        f.with_step_at_mut(&grid_pos, |s| {
+                            let (new_detail, new_instances, new_quads) = (detail, instances, quads);
+                            match s {
+                                Step::Plane {
+                                    subdivisions: detail,
+                                    instances,
+                                    version,
+                                }
+                                | Step::Sphere {
+                                    subdivisions: detail,
+                                    instances,
+                                    version,
+                                }
+                                | Step::LineStrip {
+                                    points: detail,
+                                    instances,
+                                    version,
+                                } => {
+                                    *detail = new_detail;
+                                    *instances = new_instances;
+                                    *version += 1;
+                                }
+                                Step::Particles {
+                                    count,
+                                    quads,
+                                    version,
+                                } => {
+                                    *count = new_instances;
+                                    *quads = new_quads;
+                                    *version += 1;
+                                }
+                                _ => {}
+                            }
+                        })
+                    });
+                }
+                Command::HackStepRenderStateSet { grid_pos, step } => {
+                    self.state.project.with_flow_mut(|f| {
+                        f.with_step_at_mut(&grid_pos, |s| {
+                            if core::mem::discriminant(s) != core::mem::discriminant(&step) {
+                                return;
+                            }
+                            let new_version = s.version() + 1;
+                            *s = step.clone();
+                            match s {
+                                Step::Clear { version, .. }
+                                | Step::Blend { version, .. }
+                                | Step::Depth { version, .. }
+                                | Step::Cull { version, .. }
+                                | Step::Viewport { version, .. } => *version = new_version,
+                                _ => {}
+                            }
+                        })
+                    });
+                }
+                Command::LoadProjectBundle { data } => {
+                    self.state.load_project_bundle(&data);
+                }
+                Command::ImportShadertoy { data } => {
+                    self.state.import_shadertoy(&data);
+                }
+                Command::SaveProjectToStorage => {
+                    if let Some(storage) = frame.storage_mut() {
+                        self.state.save_project_to_storage(storage);
+                    }
+                }
+                Command::LoadProjectFromStorage => {
+                    if let Some(storage) = frame.storage() {
+                        self.state.load_project_from_storage(storage);
+                    }
+                }
                 Command::ChangeFlow { flow_command } => {
                     self.handle_flow_command(flow_command);
                 }
+                Command::Transport { transport_command } => {
+                    self.handle_transport_command(transport_command);
+                }
                 o => {
                     eprintln!("Unhandled command {o:?}");
                 }
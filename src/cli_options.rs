@@ -0,0 +1,246 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: sixtyfour-rs [OPTIONS] [PROJECT_PATH]
+
+Options:
+  --project <PATH>         Open the project in PATH (same as PROJECT_PATH)
+  --fullscreen             Start with the McGuffin in fullscreen
+  --size <WIDTHxHEIGHT>    Set the initial window size, e.g. 1280x720
+  --start <LABEL>          Start the flow at LABEL instead of `start`
+  --osc <PORT>             Listen for OSC remote control on UDP PORT
+  --validate               Validate the project and exit
+  --script <FILE>          Run the Rhai script FILE against the project and exit
+  --save                   Save the project after --script succeeded
+  --export-frames <PATH>   Render frames into PATH and exit
+  --frames <COUNT>         Number of frames to export (default: 60)
+  --fps <FPS>              Frames per second for export (default: 60)
+  --help                   Print this help
+";
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum CliMode {
+    #[default]
+    Editor,
+    Validate,
+    Script {
+        file: PathBuf,
+        save: bool,
+    },
+    ExportFrames {
+        folder: PathBuf,
+        frame_count: u32,
+        fps: f64,
+    },
+    Help,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CliOptions {
+    project_path: Option<PathBuf>,
+    fullscreen: bool,
+    window_size: Option<[f32; 2]>,
+    start_label: Option<String>,
+    osc_port: Option<u16>,
+    mode: CliMode,
+}
+
+impl CliOptions {
+    pub fn from_env() -> Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut options = Self::default();
+        let mut export_folder = None;
+        let mut frame_count = 60;
+        let mut fps = 60.0;
+        let mut validate = false;
+        let mut script = None;
+        let mut save = false;
+
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            let mut value_for =
+                |name: &str| args.next().ok_or_else(|| eyre!("Missing value for {name}"));
+            match arg.as_str() {
+                "--help" | "-h" => {
+                    options.mode = CliMode::Help;
+                    return Ok(options);
+                }
+                "--project" => {
+                    options.project_path = Some(PathBuf::from(value_for("--project")?));
+                }
+                "--fullscreen" => options.fullscreen = true,
+                "--size" => {
+                    let size = value_for("--size")?;
+                    options.window_size = Some(Self::parse_size(&size)?);
+                }
+                "--start" => options.start_label = Some(value_for("--start")?),
+                "--osc" => {
+                    let v = value_for("--osc")?;
+                    options.osc_port =
+                        Some(v.parse().map_err(|_| eyre!("Invalid OSC port '{v}'"))?);
+                }
+                "--validate" => validate = true,
+                "--script" => script = Some(PathBuf::from(value_for("--script")?)),
+                "--save" => save = true,
+                "--export-frames" => {
+                    export_folder = Some(PathBuf::from(value_for("--export-frames")?));
+                }
+                "--frames" => {
+                    let v = value_for("--frames")?;
+                    frame_count = v
+                        .parse::<u32>()
+                        .map_err(|_| eyre!("Invalid frame count '{v}'"))?;
+                }
+                "--fps" => {
+                    let v = value_for("--fps")?;
+                    fps = v
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|fps| *fps > 0.0)
+                        .ok_or_else(|| eyre!("Invalid fps '{v}'"))?;
+                }
+                o if o.starts_with('-') => {
+                    return Err(eyre!("Unknown option '{o}'"));
+                }
+                o => {
+                    if options.project_path.is_some() {
+                        return Err(eyre!("Unexpected argument '{o}'"));
+                    }
+                    options.project_path = Some(PathBuf::from(o));
+                }
+            }
+        }
+
+        if save && script.is_none() {
+            return Err(eyre!("--save needs --script"));
+        }
+        options.mode = match (validate, script, export_folder) {
+            (true, None, None) => CliMode::Validate,
+            (false, Some(file), None) => CliMode::Script { file, save },
+            (false, None, Some(folder)) => CliMode::ExportFrames {
+                folder,
+                frame_count,
+                fps,
+            },
+            (false, None, None) => CliMode::Editor,
+            _ => {
+                return Err(eyre!(
+                    "--validate, --script and --export-frames can not be combined"
+                ));
+            }
+        };
+
+        Ok(options)
+    }
+
+    fn parse_size(size: &str) -> Result<[f32; 2]> {
+        let (w, h) = size
+            .split_once('x')
+            .ok_or_else(|| eyre!("Invalid size '{size}', expected WIDTHxHEIGHT"))?;
+        let valid = |v: &str| v.parse::<f32>().ok().filter(|v| v.is_finite() && *v > 0.0);
+        let w = valid(w).ok_or_else(|| eyre!("Invalid width in '{size}'"))?;
+        let h = valid(h).ok_or_else(|| eyre!("Invalid height in '{size}'"))?;
+        Ok([w, h])
+    }
+
+    pub fn project_path(&self) -> Option<&Path> {
+        self.project_path.as_deref()
+    }
+    pub fn fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+    pub fn window_size(&self) -> Option<[f32; 2]> {
+        self.window_size
+    }
+    pub fn start_label(&self) -> Option<&str> {
+        self.start_label.as_deref()
+    }
+    pub fn osc_port(&self) -> Option<u16> {
+        self.osc_port
+    }
+    pub fn mode(&self) -> &CliMode {
+        &self.mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_defaults_to_editor() {
+        let o = CliOptions::parse(Vec::<String>::new()).unwrap();
+        assert_eq!(CliOptions::default(), o);
+        assert_eq!(&CliMode::Editor, o.mode());
+    }
+
+    #[test]
+    fn parse_works() {
+        let o = CliOptions::parse([
+            "--fullscreen",
+            "--size",
+            "1280x720",
+            "--start",
+            "intro",
+            "--osc",
+            "9000",
+            "projects/demo",
+        ])
+        .unwrap();
+        assert_eq!(Some(Path::new("projects/demo")), o.project_path());
+        assert!(o.fullscreen());
+        assert_eq!(Some([1280.0, 720.0]), o.window_size());
+        assert_eq!(Some("intro"), o.start_label());
+        assert_eq!(Some(9000), o.osc_port());
+        assert_eq!(&CliMode::Editor, o.mode());
+
+        let o = CliOptions::parse(["--project", "demo", "--validate"]).unwrap();
+        assert_eq!(Some(Path::new("demo")), o.project_path());
+        assert_eq!(&CliMode::Validate, o.mode());
+
+        let o = CliOptions::parse(["demo", "--script", "check.rhai", "--save"]).unwrap();
+        assert_eq!(
+            &CliMode::Script {
+                file: PathBuf::from("check.rhai"),
+                save: true,
+            },
+            o.mode()
+        );
+
+        let o = CliOptions::parse(["demo", "--export-frames", "out", "--frames", "10"]).unwrap();
+        assert_eq!(
+            &CliMode::ExportFrames {
+                folder: PathBuf::from("out"),
+                frame_count: 10,
+                fps: 60.0,
+            },
+            o.mode()
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid() {
+        assert!(CliOptions::parse(["--size", "1280"]).is_err());
+        for size in ["0x0", "-1280x720", "1280x-1", "NaNx720", "1280xinf"] {
+            assert!(CliOptions::parse(["--size", size]).is_err(), "{size}");
+        }
+        assert!(CliOptions::parse(["--size"]).is_err());
+        assert!(CliOptions::parse(["--fps", "0"]).is_err());
+        assert!(CliOptions::parse(["--osc", "70000"]).is_err());
+        assert!(CliOptions::parse(["--bogus"]).is_err());
+        assert!(CliOptions::parse(["a", "b"]).is_err());
+        assert!(CliOptions::parse(["--validate", "--export-frames", "out"]).is_err());
+        assert!(CliOptions::parse(["--validate", "--script", "a.rhai"]).is_err());
+        assert!(CliOptions::parse(["--save"]).is_err());
+    }
+}
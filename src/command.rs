@@ -2,6 +2,7 @@ use crate::project::GridPos;
 use crate::project::GridRect;
 use crate::project::ResourceId;
 use crate::project::ShaderType;
+use crate::project::Step;
 
 #[derive(Debug, Default)]
 pub enum FlowCommand {
@@ -21,13 +22,41 @@ pub enum FlowCommand {
     Nop,
 }
 
+#[derive(Debug, Default)]
+pub enum TransportCommand {
+    Play,
+    Pause,
+    TogglePlay,
+    StepFrames {
+        frames: i32,
+    },
+    SetSpeed {
+        speed: f64,
+    },
+    Seek {
+        time: f64,
+    },
+
+    #[default]
+    Nop,
+}
+
 #[derive(Debug, Default)]
 pub enum Command {
     DeleteProperty {
         name: String,
     },
     ToggleFullscreen,
+    EnterFullscreen,
     LeaveFullscreen,
+    SetProperty {
+        name: String,
+        values: Vec<f64>,
+    },
+    SetStartLabel {
+        label: String,
+    },
+    ResetFeedback,
     ProgramAddShader {
         resource_id: ResourceId,
         shader_type: ShaderType,
@@ -71,9 +100,42 @@ pub enum Command {
         grid_pos: GridPos,
         name: String,
     },
+    HackStepDrawMeshSetResourceIdAndInstances {
+        grid_pos: GridPos,
+        resource_id: ResourceId,
+        instances: u32,
+    },
+    HackStepDispatchSetResourceIdAndGroups {
+        grid_pos: GridPos,
+        resource_id: ResourceId,
+        groups: [u32; 3],
+    },
+    /// `detail` is the subdivisions, or points, `quads` is only used by particles.
+    HackStepGeometrySetParameters {
+        grid_pos: GridPos,
+        detail: u32,
+        instances: u32,
+        quads: bool,
+    },
+    /// Replaces a `Clear`, `Blend`, `Depth`, `Cull`, or `Viewport` step with the same kind.
+    HackStepRenderStateSet {
+        grid_pos: GridPos,
+        step: Step,
+    },
     ChangeFlow {
         flow_command: FlowCommand,
     },
+    LoadProjectBundle {
+        data: String,
+    },
+    ImportShadertoy {
+        data: String,
+    },
+    SaveProjectToStorage,
+    LoadProjectFromStorage,
+    Transport {
+        transport_command: TransportCommand,
+    },
     #[default]
     Nop,
 }
@@ -0,0 +1,151 @@
+use crate::engine::AudioClip;
+use rustfft::num_complex::Complex;
+use rustfft::Fft;
+use rustfft::FftPlanner;
+use std::sync::Arc;
+
+/// The music driven values, available as `${...}` values:
+///
+/// - `${BEAT}` the number of beats since the start, fractional
+/// - `${BAR}` the number of bars since the start, fractional
+/// - `${BEAT_PHASE}` the position inside the current beat, `0..1`
+/// - `${FFT_0}`..`${FFT_7}` the energy of the frequency bands, from low to high
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AudioValues {
+    pub beat: f32,
+    pub bar: f32,
+    pub beat_phase: f32,
+    pub bands: [f32; AudioValues::BAND_COUNT],
+}
+
+impl AudioValues {
+    pub const BAND_COUNT: usize = 8;
+
+    /// Derives the beat values from the tempo, `offset` is the time of the first beat.
+    pub fn with_tempo(mut self, time: f64, bpm: f64, beats_per_bar: u32, offset: f64) -> Self {
+        if bpm > 0.0 {
+            let beat = (time - offset) * bpm / 60.0;
+            self.beat = beat as f32;
+            self.bar = (beat / beats_per_bar.max(1) as f64) as f32;
+            self.beat_phase = beat.rem_euclid(1.0) as f32;
+        }
+        self
+    }
+
+    pub fn resolve(&self, value: &str) -> Option<f32> {
+        match value {
+            "${BEAT}" => Some(self.beat),
+            "${BAR}" => Some(self.bar),
+            "${BEAT_PHASE}" => Some(self.beat_phase),
+            o => o
+                .strip_prefix("${FFT_")
+                .and_then(|b| b.strip_suffix('}'))
+                .and_then(|b| b.parse::<usize>().ok())
+                .and_then(|b| self.bands.get(b).copied()),
+        }
+    }
+}
+
+/// Calculates the band energies of an [`AudioClip`] around a given time.
+pub struct AudioAnalyzer {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+}
+
+impl core::fmt::Debug for AudioAnalyzer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AudioAnalyzer")
+            .field("size", &self.window.len())
+            .finish()
+    }
+}
+
+impl Default for AudioAnalyzer {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SIZE)
+    }
+}
+
+impl AudioAnalyzer {
+    pub const DEFAULT_SIZE: usize = 1024;
+
+    pub fn new(size: usize) -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(size);
+        // hann window
+        let window = (0..size)
+            .map(|i| {
+                let x = i as f32 / size as f32;
+                0.5 - 0.5 * (core::f32::consts::TAU * x).cos()
+            })
+            .collect();
+        Self { fft, window }
+    }
+
+    /// Returns the energy of [`AudioValues::BAND_COUNT`] logarithmically spaced bands,
+    /// for the window ending at `time`, roughly normalized to `0..1`.
+    pub fn band_energies(&self, clip: &AudioClip, time: f64) -> [f32; AudioValues::BAND_COUNT] {
+        let size = self.window.len();
+        let start = time - size as f64 / clip.sample_rate().max(1) as f64;
+        let mut buffer: Vec<_> = clip
+            .mono_window(start, size)
+            .into_iter()
+            .zip(self.window.iter())
+            .map(|(s, w)| Complex::new(s * w, 0.0))
+            .collect();
+        self.fft.process(&mut buffer);
+
+        // only the first half holds the positive frequencies, bin 0 is DC
+        let bins = size / 2;
+        let mut bands = [0.0; AudioValues::BAND_COUNT];
+        for (b, band) in bands.iter_mut().enumerate() {
+            let from = Self::band_edge(b, bins);
+            let to = Self::band_edge(b + 1, bins).max(from + 1);
+            let energy: f32 = buffer[from..to].iter().map(|c| c.norm()).sum();
+            *band = (energy / (to - from) as f32 / size as f32 * 4.0).min(1.0);
+        }
+        bands
+    }
+
+    fn band_edge(band: usize, bins: usize) -> usize {
+        let t = band as f32 / AudioValues::BAND_COUNT as f32;
+        (bins as f32).powf(t).round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tempo_values_work() {
+        let v = AudioValues::default().with_tempo(10.25, 120.0, 4, 0.0);
+        assert_eq!(20.5, v.beat);
+        assert_eq!(5.125, v.bar);
+        assert_eq!(0.5, v.beat_phase);
+
+        assert_eq!(Some(20.5), v.resolve("${BEAT}"));
+        assert_eq!(Some(0.0), v.resolve("${FFT_7}"));
+        assert_eq!(None, v.resolve("${FFT_8}"));
+        assert_eq!(None, v.resolve("${TIME}"));
+    }
+
+    #[test]
+    fn band_energies_find_the_tone() {
+        let sample_rate = 44100;
+        let samples = (0..sample_rate)
+            .map(|i| (core::f32::consts::TAU * 100.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+        let clip = AudioClip::new(sample_rate, 1, samples);
+
+        let bands = AudioAnalyzer::default().band_energies(&clip, 0.5);
+        let loudest = bands
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i);
+        assert_eq!(Some(1), loudest);
+
+        let silence = AudioAnalyzer::default().band_energies(&clip, 10.0);
+        assert_eq!([0.0; AudioValues::BAND_COUNT], silence);
+    }
+}
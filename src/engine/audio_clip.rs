@@ -0,0 +1,145 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::io::Cursor;
+
+/// Decoded audio, as interleaved `f32` samples.
+#[derive(Default, Clone, PartialEq)]
+pub struct AudioClip {
+    sample_rate: u32,
+    channels: u16,
+    samples: Vec<f32>,
+}
+
+impl core::fmt::Debug for AudioClip {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AudioClip")
+            .field("sample_rate", &self.sample_rate)
+            .field("channels", &self.channels)
+            .field("duration", &self.duration())
+            .finish()
+    }
+}
+
+impl AudioClip {
+    pub fn new(sample_rate: u32, channels: u16, samples: Vec<f32>) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            samples,
+        }
+    }
+
+    /// Decodes WAV or OGG/Vorbis data, the format is detected from the content.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        match data.get(0..4) {
+            Some(b"RIFF") => Self::from_wav(data),
+            Some(b"OggS") => Self::from_ogg(data),
+            _ => Err(eyre!("Unsupported audio format, expected WAV or OGG")),
+        }
+    }
+
+    pub fn from_wav(data: &[u8]) -> Result<Self> {
+        let reader = hound::WavReader::new(Cursor::new(data))?;
+        let spec = reader.spec();
+        let samples = match spec.sample_format {
+            hound::SampleFormat::Float => reader
+                .into_samples::<f32>()
+                .collect::<core::result::Result<Vec<_>, _>>()?,
+            hound::SampleFormat::Int => {
+                let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|s| s.map(|s| s as f32 * scale))
+                    .collect::<core::result::Result<Vec<_>, _>>()?
+            }
+        };
+        Ok(Self::new(spec.sample_rate, spec.channels, samples))
+    }
+
+    pub fn from_ogg(data: &[u8]) -> Result<Self> {
+        let mut reader = lewton::inside_ogg::OggStreamReader::new(Cursor::new(data))
+            .map_err(|e| eyre!("Failed decoding OGG: {e:?}"))?;
+        let sample_rate = reader.ident_hdr.audio_sample_rate;
+        let channels = reader.ident_hdr.audio_channels as u16;
+        let mut samples = Vec::new();
+        while let Some(packet) = reader
+            .read_dec_packet_itl()
+            .map_err(|e| eyre!("Failed decoding OGG: {e:?}"))?
+        {
+            samples.extend(packet.into_iter().map(|s| s as f32 / 32768.0));
+        }
+        Ok(Self::new(sample_rate, channels, samples))
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+    /// The interleaved samples.
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+    pub fn frame_count(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+    pub fn duration(&self) -> f64 {
+        if self.sample_rate == 0 {
+            0.0
+        } else {
+            self.frame_count() as f64 / self.sample_rate as f64
+        }
+    }
+
+    /// Returns `len` frames mixed down to mono, starting at `time`, padded with silence.
+    pub fn mono_window(&self, time: f64, len: usize) -> Vec<f32> {
+        let channels = self.channels as usize;
+        let start = (time * self.sample_rate as f64).floor() as i64;
+        (0..len as i64)
+            .map(|i| {
+                let frame = start + i;
+                if frame < 0 || frame as usize >= self.frame_count() {
+                    return 0.0;
+                }
+                let offset = frame as usize * channels;
+                self.samples[offset..offset + channels].iter().sum::<f32>() / channels as f32
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_wav_works() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut data = Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut data, spec).unwrap();
+            for _ in 0..50 {
+                writer.write_sample(16384i16).unwrap();
+                writer.write_sample(0i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let clip = AudioClip::from_bytes(data.get_ref()).unwrap();
+        assert_eq!(100, clip.sample_rate());
+        assert_eq!(2, clip.channels());
+        assert_eq!(0.5, clip.duration());
+
+        let w = clip.mono_window(0.45, 10);
+        assert_eq!(0.25, w[0]);
+        assert_eq!(0.0, w[9]);
+
+        assert!(AudioClip::from_bytes(b"nope").is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::engine::AudioAnalyzer;
+use crate::engine::AudioClip;
+use crate::engine::AudioSink;
+use crate::engine::AudioValues;
+use crate::engine::Clock;
+use crate::engine::NullAudioSink;
+use std::sync::Arc;
+
+/// The tempo of the soundtrack, `offset` is the time of the first beat in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tempo {
+    pub bpm: f64,
+    pub beats_per_bar: u32,
+    pub offset: f64,
+}
+
+impl Default for Tempo {
+    fn default() -> Self {
+        Self {
+            bpm: 120.0,
+            beats_per_bar: 4,
+            offset: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SyncState {
+    time: f64,
+    playing: bool,
+    speed: f64,
+}
+
+/// Keeps the soundtrack locked to the [`Clock`], and analyses it.
+#[derive(Debug)]
+pub struct AudioPlayer {
+    sink: Box<dyn AudioSink>,
+    clip: Option<Arc<AudioClip>>,
+    tempo: Tempo,
+    analyzer: AudioAnalyzer,
+    last_sync: Option<SyncState>,
+}
+
+impl Default for AudioPlayer {
+    fn default() -> Self {
+        Self::new(Box::<NullAudioSink>::default())
+    }
+}
+
+impl AudioPlayer {
+    /// Time jumps bigger than this are treated as seeks.
+    pub const SEEK_THRESHOLD: f64 = 0.25;
+
+    pub fn new(sink: Box<dyn AudioSink>) -> Self {
+        Self {
+            sink,
+            clip: None,
+            tempo: Tempo::default(),
+            analyzer: AudioAnalyzer::default(),
+            last_sync: None,
+        }
+    }
+
+    #[cfg(feature = "audio")]
+    pub fn set_sink(&mut self, mut sink: Box<dyn AudioSink>) {
+        sink.set_clip(self.clip.clone());
+        self.sink = sink;
+        self.last_sync = None;
+    }
+
+    pub fn set_clip(&mut self, clip: Option<Arc<AudioClip>>) {
+        let same = match (&self.clip, &clip) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same {
+            self.sink.set_clip(clip.clone());
+            self.clip = clip;
+            self.last_sync = None;
+        }
+    }
+
+    pub fn set_tempo(&mut self, tempo: Tempo) {
+        self.tempo = tempo;
+    }
+
+    /// Follows play/pause, speed changes, and seeks of the clock.
+    pub fn sync(&mut self, clock: &Clock) {
+        let state = SyncState {
+            time: clock.time(),
+            playing: clock.is_playing(),
+            speed: clock.speed(),
+        };
+        let Some(last) = self.last_sync.replace(state) else {
+            self.sink.set_speed(state.speed);
+            self.sink.seek(state.time);
+            if state.playing {
+                self.sink.play();
+            } else {
+                self.sink.pause();
+            }
+            return;
+        };
+
+        if state.speed != last.speed {
+            self.sink.set_speed(state.speed);
+        }
+        let moved = state.time != last.time;
+        if moved && (!state.playing || (state.time - last.time).abs() > Self::SEEK_THRESHOLD) {
+            self.sink.seek(state.time);
+        }
+        if state.playing != last.playing {
+            if state.playing {
+                self.sink.play();
+            } else {
+                self.sink.pause();
+            }
+        }
+    }
+
+    /// The music driven values at `time`.
+    pub fn values(&self, time: f64) -> AudioValues {
+        let mut values = AudioValues::default();
+        if let Some(clip) = &self.clip {
+            values.bands = self.analyzer.band_energies(clip, time);
+            values = values.with_tempo(
+                time,
+                self.tempo.bpm,
+                self.tempo.beats_per_bar,
+                self.tempo.offset,
+            );
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, Clone)]
+    struct SharedSink(Arc<std::sync::Mutex<NullAudioSink>>);
+
+    impl AudioSink for SharedSink {
+        fn set_clip(&mut self, clip: Option<Arc<AudioClip>>) {
+            self.0.lock().unwrap().set_clip(clip);
+        }
+        fn play(&mut self) {
+            self.0.lock().unwrap().play();
+        }
+        fn pause(&mut self) {
+            self.0.lock().unwrap().pause();
+        }
+        fn seek(&mut self, time: f64) {
+            self.0.lock().unwrap().seek(time);
+        }
+        fn set_speed(&mut self, speed: f64) {
+            self.0.lock().unwrap().set_speed(speed);
+        }
+    }
+
+    #[test]
+    fn sync_follows_the_clock() {
+        let sink = SharedSink::default();
+        let mut player = AudioPlayer::new(Box::new(sink.clone()));
+        let mut clock = Clock::default();
+
+        player.sync(&clock);
+        assert!(sink.0.lock().unwrap().playing);
+        assert_eq!(vec![0.0], sink.0.lock().unwrap().seeks);
+
+        // normal playback doesn't seek
+        clock.advance(1.0 / 60.0);
+        player.sync(&clock);
+        assert_eq!(1, sink.0.lock().unwrap().seeks.len());
+
+        clock.seek(10.0);
+        player.sync(&clock);
+        assert_eq!(Some(&10.0), sink.0.lock().unwrap().seeks.last());
+
+        clock.step(1);
+        player.sync(&clock);
+        assert!(!sink.0.lock().unwrap().playing);
+        assert_eq!(3, sink.0.lock().unwrap().seeks.len());
+
+        clock.set_speed(2.0);
+        clock.play();
+        player.sync(&clock);
+        assert!(sink.0.lock().unwrap().playing);
+        assert_eq!(2.0, sink.0.lock().unwrap().speed);
+    }
+
+    #[test]
+    fn values_need_a_clip() {
+        let mut player = AudioPlayer::default();
+        assert_eq!(AudioValues::default(), player.values(1.0));
+
+        player.set_clip(Some(Arc::new(AudioClip::new(100, 1, vec![0.0; 100]))));
+        assert_eq!(Some(2.0), player.values(1.0).resolve("${BEAT}"));
+    }
+}
@@ -0,0 +1,38 @@
+use crate::engine::AudioClip;
+use std::sync::Arc;
+
+/// Plays the soundtrack, driven by the [`crate::engine::AudioPlayer`].
+pub trait AudioSink: core::fmt::Debug + Send {
+    fn set_clip(&mut self, clip: Option<Arc<AudioClip>>);
+    fn play(&mut self);
+    fn pause(&mut self);
+    fn seek(&mut self, time: f64);
+    fn set_speed(&mut self, speed: f64);
+}
+
+/// Plays nothing, but keeps track of what it was told, e.g. for headless runs and tests.
+#[derive(Debug, Default)]
+pub struct NullAudioSink {
+    pub clip: Option<Arc<AudioClip>>,
+    pub playing: bool,
+    pub speed: f64,
+    pub seeks: Vec<f64>,
+}
+
+impl AudioSink for NullAudioSink {
+    fn set_clip(&mut self, clip: Option<Arc<AudioClip>>) {
+        self.clip = clip;
+    }
+    fn play(&mut self) {
+        self.playing = true;
+    }
+    fn pause(&mut self) {
+        self.playing = false;
+    }
+    fn seek(&mut self, time: f64) {
+        self.seeks.push(time);
+    }
+    fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+}
@@ -0,0 +1,125 @@
+use crate::engine::AudioClip;
+use crate::engine::AudioSink;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use cpal::traits::DeviceTrait;
+use cpal::traits::HostTrait;
+use cpal::traits::StreamTrait;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+struct Playback {
+    clip: Option<Arc<AudioClip>>,
+    /// in frames of the clip
+    position: f64,
+    playing: bool,
+    speed: f64,
+}
+
+/// Plays the soundtrack on the default output device.
+pub struct CpalAudioSink {
+    playback: Arc<Mutex<Playback>>,
+    _stream: cpal::Stream,
+}
+
+impl core::fmt::Debug for CpalAudioSink {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CpalAudioSink")
+            .field("playback", &self.playback)
+            .finish()
+    }
+}
+
+// :TODO: the stream is not Send on all platforms, but we only touch it from the main thread
+unsafe impl Send for CpalAudioSink {}
+
+impl CpalAudioSink {
+    pub fn new() -> Result<Self> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| eyre!("No audio output device"))?;
+        let config: cpal::StreamConfig = device.default_output_config()?.into();
+        let channels = config.channels as usize;
+        let output_rate = config.sample_rate.0 as f64;
+
+        let playback = Arc::new(Mutex::new(Playback {
+            speed: 1.0,
+            ..Default::default()
+        }));
+        let pb = playback.clone();
+        let stream = device.build_output_stream(
+            &config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                let Ok(mut pb) = pb.lock() else {
+                    return;
+                };
+                Self::fill(&mut pb, data, channels, output_rate);
+            },
+            |e| eprintln!("Audio stream error {e:?}"),
+            None,
+        )?;
+        stream.play()?;
+
+        Ok(Self {
+            playback,
+            _stream: stream,
+        })
+    }
+
+    fn fill(pb: &mut Playback, data: &mut [f32], channels: usize, output_rate: f64) {
+        data.fill(0.0);
+        let Some(clip) = pb.clip.clone() else {
+            return;
+        };
+        if !pb.playing || pb.speed <= 0.0 {
+            return;
+        }
+        let clip_channels = clip.channels() as usize;
+        let step = clip.sample_rate() as f64 / output_rate * pb.speed;
+        for frame in data.chunks_exact_mut(channels) {
+            let p = pb.position as usize;
+            if p >= clip.frame_count() {
+                break;
+            }
+            let source = &clip.samples()[p * clip_channels..(p + 1) * clip_channels];
+            for (c, s) in frame.iter_mut().enumerate() {
+                *s = source[c % clip_channels];
+            }
+            pb.position += step;
+        }
+    }
+
+    fn with_playback<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Playback),
+    {
+        if let Ok(mut pb) = self.playback.lock() {
+            f(&mut pb);
+        }
+    }
+}
+
+impl AudioSink for CpalAudioSink {
+    fn set_clip(&mut self, clip: Option<Arc<AudioClip>>) {
+        self.with_playback(|pb| {
+            pb.clip = clip;
+            pb.position = 0.0;
+        });
+    }
+    fn play(&mut self) {
+        self.with_playback(|pb| pb.playing = true);
+    }
+    fn pause(&mut self) {
+        self.with_playback(|pb| pb.playing = false);
+    }
+    fn seek(&mut self, time: f64) {
+        self.with_playback(|pb| {
+            let sample_rate = pb.clip.as_ref().map(|c| c.sample_rate()).unwrap_or(0);
+            pb.position = time.max(0.0) * sample_rate as f64;
+        });
+    }
+    fn set_speed(&mut self, speed: f64) {
+        self.with_playback(|pb| pb.speed = speed);
+    }
+}
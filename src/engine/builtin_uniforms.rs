@@ -0,0 +1,374 @@
+use crate::engine::gl::*;
+use crate::engine::Pipeline;
+use crate::project::ChannelInput;
+
+/// The uniforms every program gets without a property, set when the program is bound.
+///
+/// Native names:
+///
+/// - `vResolution` the viewport size in pixels, `fAspect` its width over height
+/// - `fFrame` the frame index, `fDeltaTime` the seconds since the last frame
+/// - `vDate` year, month (from 0), day, and seconds of the day, in UTC
+/// - `vMouse` like Shadertoy's `iMouse`, in pixels from the bottom left:
+///   `xy` the position while a button is down, `zw` where it went down,
+///   `z` negative when released, `w` negative after the first frame
+/// - `tKeyboard` a 256x3 texture of JavaScript key codes,
+///   row 0 held down, row 1 pressed this frame, row 2 toggled by each press
+/// - `vViewportOrigin` the bottom left of the viewport in the framebuffer
+/// - `tFeedback` the flow's previous frame, if the flow declares [`crate::project::Feedback`]
+///
+/// Shadertoy names: `iResolution`, `iTime`, `iTimeDelta`, `iFrame`, `iFrameRate`,
+/// `iMouse`, `iDate`, `iChannel0..3`, and `iChannelResolution`.
+#[derive(Debug, Default)]
+pub struct BuiltinUniforms {
+    resolution: [f32; 2],
+    viewport_origin: [f32; 2],
+    time: f32,
+    delta_time: f32,
+    last_time: Option<f64>,
+    frame: u64,
+    date: [f32; 4],
+    mouse: [f32; 4],
+    mouse_down: bool,
+    /// One row each for down, pressed, and toggled.
+    keys: Vec<u8>,
+    keys_dirty: bool,
+    keyboard_texture: GLuint,
+    /// Owned by the [`crate::engine::FeedbackBuffer`], `0` without feedback.
+    feedback_texture: GLuint,
+    feedback_resolution: [f32; 2],
+}
+
+/// The pointer and keyboard state of the preview for one frame.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PreviewInput {
+    /// In pixels from the bottom left of the viewport, `None` when outside.
+    pub pointer: Option<[f32; 2]>,
+    pub pointer_down: bool,
+    /// JavaScript key codes, see [`BuiltinUniforms::key_code`].
+    pub keys_down: Vec<u8>,
+}
+
+impl BuiltinUniforms {
+    /// The uniforms, to keep them from turning into properties.
+    pub const NAMES: &'static [&'static str] = &[
+        "vResolution",
+        "fAspect",
+        "fFrame",
+        "fDeltaTime",
+        "vDate",
+        "vMouse",
+        "tKeyboard",
+        "vViewportOrigin",
+        "tFeedback",
+        "iResolution",
+        "iTime",
+        "iTimeDelta",
+        "iFrame",
+        "iFrameRate",
+        "iMouse",
+        "iDate",
+        "iChannel0",
+        "iChannel1",
+        "iChannel2",
+        "iChannel3",
+        "iChannelResolution[0]",
+    ];
+    const KEYBOARD_SIZE: [GLsizei; 2] = [256, 3];
+    /// The keyboard is bound after the channels.
+    const KEYBOARD_UNIT: u32 = 4;
+    const FEEDBACK_UNIT: u32 = 5;
+
+    /// Moves to the next frame, `time` is the wrapped time shaders see.
+    pub fn advance(&mut self, time: f64, wrapped_time: f32, frame: u64) {
+        self.delta_time = self
+            .last_time
+            .map(|t| (time - t).max(0.0) as f32)
+            .unwrap_or_default();
+        self.last_time = Some(time);
+        self.time = wrapped_time;
+        self.frame = frame;
+        let now = web_time::SystemTime::now()
+            .duration_since(web_time::SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or_default();
+        self.date = Self::date(now);
+    }
+
+    /// Sets the viewport, as `[x, y, width, height]` in pixels.
+    pub fn set_viewport(&mut self, [x, y, w, h]: [GLint; 4]) {
+        self.viewport_origin = [x as f32, y as f32];
+        self.resolution = [w as f32, h as f32];
+    }
+
+    /// Sets last frame's output, and its size in pixels.
+    pub fn set_feedback(&mut self, texture: GLuint, [w, h]: [GLsizei; 2]) {
+        self.feedback_texture = texture;
+        self.feedback_resolution = [w as f32, h as f32];
+    }
+
+    /// The viewport's width over height, `1.0` before it is known.
+    pub fn aspect(&self) -> f32 {
+        let [w, h] = self.resolution;
+        if h > 0.0 {
+            w / h
+        } else {
+            1.0
+        }
+    }
+
+    pub fn set_input(&mut self, input: &PreviewInput) {
+        let [x, y, z, w] = self.mouse;
+        let click = [z.abs(), w.abs()];
+        if input.pointer_down {
+            let [px, py] = input.pointer.unwrap_or([x, y]);
+            self.mouse = if self.mouse_down {
+                [px, py, click[0], -click[1]]
+            } else {
+                [px, py, px, py]
+            };
+        } else {
+            self.mouse = [x, y, -click[0], -click[1]];
+        }
+        self.mouse_down = input.pointer_down;
+
+        let [width, _] = Self::KEYBOARD_SIZE;
+        let width = width as usize;
+        self.keys.resize(3 * width, 0);
+        let (down, rest) = self.keys.split_at_mut(width);
+        let (pressed, toggled) = rest.split_at_mut(width);
+        for code in 0..width {
+            let is_down = input.keys_down.contains(&(code as u8));
+            let was_down = down[code] != 0;
+            pressed[code] = if is_down && !was_down { 255 } else { 0 };
+            if pressed[code] != 0 {
+                toggled[code] ^= 255;
+            }
+            down[code] = if is_down { 255 } else { 0 };
+        }
+        self.keys_dirty = true;
+    }
+
+    /// The JavaScript key code Shadertoy uses for `key`.
+    pub fn key_code(key: egui::Key) -> Option<u8> {
+        use egui::Key;
+        let code = match key {
+            Key::Backspace => 8,
+            Key::Tab => 9,
+            Key::Enter => 13,
+            Key::Escape => 27,
+            Key::Space => 32,
+            Key::PageUp => 33,
+            Key::PageDown => 34,
+            Key::End => 35,
+            Key::Home => 36,
+            Key::ArrowLeft => 37,
+            Key::ArrowUp => 38,
+            Key::ArrowRight => 39,
+            Key::ArrowDown => 40,
+            Key::Insert => 45,
+            Key::Delete => 46,
+            Key::Semicolon => 186,
+            Key::Equals | Key::Plus => 187,
+            Key::Comma => 188,
+            Key::Minus => 189,
+            Key::Period => 190,
+            Key::Slash => 191,
+            Key::Backtick => 192,
+            Key::OpenBracket => 219,
+            Key::Backslash => 220,
+            Key::CloseBracket => 221,
+            key => {
+                let name = key.name();
+                match name.as_bytes() {
+                    [c] if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+                    [b'F', n @ ..] => {
+                        let n = std::str::from_utf8(n).ok()?.parse::<u8>().ok()?;
+                        if (1..=24).contains(&n) {
+                            111 + n
+                        } else {
+                            return None;
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+        };
+        Some(code)
+    }
+
+    /// The key codes held down in `input`, including the modifiers.
+    pub fn keys_down(input: &egui::InputState) -> Vec<u8> {
+        let mut keys: Vec<u8> = input
+            .keys_down
+            .iter()
+            .filter_map(|k| Self::key_code(*k))
+            .collect();
+        for (down, code) in [
+            (input.modifiers.shift, 16),
+            (input.modifiers.ctrl, 17),
+            (input.modifiers.alt, 18),
+        ] {
+            if down {
+                keys.push(code);
+            }
+        }
+        keys
+    }
+
+    /// `[year, month from 0, day, seconds of the day]` for seconds since the epoch, in UTC.
+    pub fn date(unix_seconds: f64) -> [f32; 4] {
+        let days = (unix_seconds / 86_400.0).floor() as i64;
+        let seconds = unix_seconds - days as f64 * 86_400.0;
+        // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        [year as f32, (month - 1) as f32, day as f32, seconds as f32]
+    }
+
+    /// Updates the keyboard texture, once per frame before the programs run.
+    pub fn prepare(&mut self, gl: &Gl) {
+        if self.keyboard_texture == 0 {
+            gl.glGenTextures(1, &mut self.keyboard_texture);
+            gl.glBindTexture(GL_TEXTURE_2D, self.keyboard_texture);
+            for (pname, param) in [
+                (GL_TEXTURE_MIN_FILTER, GL_NEAREST),
+                (GL_TEXTURE_MAG_FILTER, GL_NEAREST),
+                (GL_TEXTURE_WRAP_S, GL_CLAMP_TO_EDGE),
+                (GL_TEXTURE_WRAP_T, GL_CLAMP_TO_EDGE),
+            ] {
+                gl.glTexParameteri(GL_TEXTURE_2D, pname, param as GLint);
+            }
+            self.keys_dirty = true;
+        } else if !self.keys_dirty {
+            return;
+        }
+        let [w, h] = Self::KEYBOARD_SIZE;
+        self.keys.resize((w * h) as usize, 0);
+        gl.glBindTexture(GL_TEXTURE_2D, self.keyboard_texture);
+        gl.glTexImage2D(
+            GL_TEXTURE_2D,
+            0,
+            GL_R8 as GLint,
+            w,
+            h,
+            0,
+            GL_RED,
+            GL_UNSIGNED_BYTE,
+            self.keys.as_ptr() as *const _,
+        );
+        gl.glBindTexture(GL_TEXTURE_2D, 0);
+        gl.check_gl_error(std::file!(), std::line!());
+        self.keys_dirty = false;
+    }
+
+    /// Sets the uniforms the bound `pipeline` uses, and binds the textures of its `channels`.
+    pub fn upload(&self, gl: &Gl, pipeline: &Pipeline, channels: &[ChannelInput; 4]) {
+        let [w, h] = self.resolution;
+        let aspect = self.aspect();
+        let frame_rate = if self.delta_time > 0.0 {
+            1.0 / self.delta_time
+        } else {
+            0.0
+        };
+
+        pipeline.set_property_vec2_f32(gl, "vResolution", &self.resolution);
+        pipeline.set_property(gl, "fAspect", aspect);
+        pipeline.set_property(gl, "fFrame", self.frame as f32);
+        pipeline.set_property(gl, "fDeltaTime", self.delta_time);
+        pipeline.set_property_vec4_f32(gl, "vDate", &self.date);
+        pipeline.set_property_vec4_f32(gl, "vMouse", &self.mouse);
+        pipeline.set_property_vec2_f32(gl, "vViewportOrigin", &self.viewport_origin);
+
+        pipeline.set_property_vec3_f32(gl, "iResolution", &[w, h, 1.0]);
+        pipeline.set_property(gl, "iTime", self.time);
+        pipeline.set_property(gl, "iTimeDelta", self.delta_time);
+        pipeline.set_property_i32(gl, "iFrame", self.frame as i32);
+        pipeline.set_property(gl, "iFrameRate", frame_rate);
+        pipeline.set_property_vec4_f32(gl, "iMouse", &self.mouse);
+        pipeline.set_property_vec4_f32(gl, "iDate", &self.date);
+
+        let [kw, kh] = Self::KEYBOARD_SIZE;
+        let keyboard_resolution = [kw as f32, kh as f32, 1.0];
+        let mut channel_resolutions = [[0.0; 3]; 4];
+        for (unit, input) in channels.iter().enumerate() {
+            let texture = match input {
+                ChannelInput::None => 0,
+                ChannelInput::Keyboard => {
+                    channel_resolutions[unit] = keyboard_resolution;
+                    self.keyboard_texture
+                }
+                ChannelInput::Feedback => {
+                    let [fw, fh] = self.feedback_resolution;
+                    channel_resolutions[unit] = [fw, fh, 1.0];
+                    self.feedback_texture
+                }
+            };
+            gl.glActiveTexture(GL_TEXTURE0 + unit as u32);
+            gl.glBindTexture(GL_TEXTURE_2D, texture);
+            pipeline.set_sampler(gl, &format!("iChannel{unit}"), unit as u32);
+        }
+        pipeline.set_property_vec3_f32_array(gl, "iChannelResolution[0]", &channel_resolutions);
+
+        gl.glActiveTexture(GL_TEXTURE0 + Self::KEYBOARD_UNIT);
+        gl.glBindTexture(GL_TEXTURE_2D, self.keyboard_texture);
+        pipeline.set_sampler(gl, "tKeyboard", Self::KEYBOARD_UNIT);
+        gl.glActiveTexture(GL_TEXTURE0 + Self::FEEDBACK_UNIT);
+        gl.glBindTexture(GL_TEXTURE_2D, self.feedback_texture);
+        pipeline.set_sampler(gl, "tFeedback", Self::FEEDBACK_UNIT);
+        gl.glActiveTexture(GL_TEXTURE0);
+        gl.check_gl_error(std::file!(), std::line!());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_works() {
+        assert_eq!([1970.0, 0.0, 1.0, 0.0], BuiltinUniforms::date(0.0));
+        // 2024-02-29 12:00:30
+        assert_eq!(
+            [2024.0, 1.0, 29.0, 43_230.0],
+            BuiltinUniforms::date(1_709_208_030.0)
+        );
+    }
+
+    #[test]
+    fn mouse_and_keys_follow_shadertoy() {
+        let mut b = BuiltinUniforms::default();
+        let mut input = PreviewInput {
+            pointer: Some([10.0, 20.0]),
+            pointer_down: true,
+            keys_down: vec![b'A'],
+        };
+        b.set_input(&input);
+        assert_eq!([10.0, 20.0, 10.0, 20.0], b.mouse);
+        assert_eq!(255, b.keys[256 + b'A' as usize]);
+
+        input.pointer = Some([30.0, 40.0]);
+        b.set_input(&input);
+        assert_eq!([30.0, 40.0, 10.0, -20.0], b.mouse);
+        assert_eq!(0, b.keys[256 + b'A' as usize]);
+        assert_eq!(255, b.keys[2 * 256 + b'A' as usize]);
+
+        input.pointer_down = false;
+        input.keys_down.clear();
+        b.set_input(&input);
+        assert_eq!([30.0, 40.0, -10.0, -20.0], b.mouse);
+        assert_eq!(0, b.keys[b'A' as usize]);
+
+        assert_eq!(Some(b'Q'), BuiltinUniforms::key_code(egui::Key::Q));
+        assert_eq!(Some(b'7'), BuiltinUniforms::key_code(egui::Key::Num7));
+        assert_eq!(Some(112), BuiltinUniforms::key_code(egui::Key::F1));
+        assert_eq!(Some(37), BuiltinUniforms::key_code(egui::Key::ArrowLeft));
+    }
+}
@@ -0,0 +1,110 @@
+/// The clock driving the flow time, with play/pause, single steps, speed and seeking.
+#[derive(Debug, Clone, Copy)]
+pub struct Clock {
+    time: f64,
+    playing: bool,
+    speed: f64,
+    last_tick: Option<web_time::Instant>,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            playing: true,
+            speed: 1.0,
+            last_tick: None,
+        }
+    }
+}
+
+impl Clock {
+    /// The duration of a single step when stepping frames.
+    pub const STEP_DURATION: f64 = 1.0 / 60.0;
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Advances the clock by the wall clock time since the last tick.
+    pub fn tick(&mut self) -> f64 {
+        let now = web_time::Instant::now();
+        let dt = self
+            .last_tick
+            .map(|last_tick| (now - last_tick).as_secs_f64())
+            .unwrap_or_default();
+        self.last_tick = Some(now);
+        self.advance(dt)
+    }
+
+    /// Advances the clock by `dt` seconds of wall clock time, scaled by the speed.
+    pub fn advance(&mut self, dt: f64) -> f64 {
+        if self.playing {
+            self.time = (self.time + dt * self.speed).max(0.0);
+        }
+        self.time
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+    pub fn toggle_play(&mut self) {
+        self.playing = !self.playing;
+    }
+    /// Pauses, and moves the given number of steps, negative steps go backwards.
+    pub fn step(&mut self, steps: i32) {
+        self.playing = false;
+        self.seek(self.time + steps as f64 * Self::STEP_DURATION);
+    }
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+    pub fn seek(&mut self, time: f64) {
+        self.time = time.max(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_respects_speed_and_pause() {
+        let mut c = Clock::default();
+        assert_eq!(1.0, c.advance(1.0));
+
+        c.set_speed(0.5);
+        assert_eq!(1.5, c.advance(1.0));
+
+        c.pause();
+        assert_eq!(1.5, c.advance(1.0));
+
+        c.toggle_play();
+        c.set_speed(-2.0);
+        assert_eq!(0.0, c.advance(1.0));
+    }
+
+    #[test]
+    fn step_and_seek_work() {
+        let mut c = Clock::default();
+        c.seek(10.0);
+        c.step(60);
+        assert!(!c.is_playing());
+        assert!((11.0 - c.time()).abs() < 1e-9);
+
+        c.step(-2 * 60);
+        assert!((9.0 - c.time()).abs() < 1e-9);
+
+        c.seek(-1.0);
+        assert_eq!(0.0, c.time());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::engine::gl::*;
+use crate::engine::RenderTarget;
+use crate::project::Feedback;
+use crate::project::FeedbackFilter;
+use crate::project::FeedbackWrap;
+
+/// The two halves of the flow's [`Feedback`], rendered into in turns.
+///
+/// Lives in the [`crate::engine::FlowVm`], so it survives flow rebuilds, it is only cleared on resize, or by [`FeedbackBuffer::reset`], e.g. when another project is loaded.
+#[derive(Debug, Default)]
+pub struct FeedbackBuffer {
+    target: RenderTarget<2>,
+    /// The half rendered into this frame, the other one is sampled.
+    write: usize,
+    /// The sampling the textures are set up for.
+    feedback: Option<Feedback>,
+    reset_requested: bool,
+    active: bool,
+}
+
+impl FeedbackBuffer {
+    /// Clears both halves before the next frame.
+    pub fn reset(&mut self) {
+        self.reset_requested = true;
+    }
+
+    /// Last frame's output, `0` before there is one.
+    pub fn read_texture(&self) -> GLuint {
+        if self.active {
+            self.target.textures()[1 - self.write]
+        } else {
+            0
+        }
+    }
+
+    /// Starts rendering into the current half, sized to `viewport`, `[x, y, width, height]` in pixels.
+    ///
+    /// Returns the viewport inside the half, or `None` if there is no usable target, rendering then goes straight to the output.
+    pub fn begin(
+        &mut self,
+        gl: &Gl,
+        feedback: &Feedback,
+        viewport: &[GLint; 4],
+    ) -> Option<[GLint; 4]> {
+        let [_, _, w, h] = *viewport;
+        if w <= 0 || h <= 0 {
+            return None;
+        }
+        self.target.save(gl, *viewport);
+        if !self.ensure_target(gl, [w, h]) {
+            return None;
+        }
+        if self.feedback.as_ref() != Some(feedback) {
+            self.apply_sampling(gl, feedback);
+        }
+        if self.reset_requested {
+            self.reset_requested = false;
+            self.clear(gl);
+        }
+        self.target.bind(gl, self.write);
+        gl.check_gl_error(std::file!(), std::line!());
+        self.active = true;
+        Some([0, 0, w, h])
+    }
+
+    /// Copies the current half to the output, and swaps the halves.
+    pub fn end(&mut self, gl: &Gl) {
+        if !self.active {
+            return;
+        }
+        self.active = false;
+        let [w, h] = self.target.size();
+        let [x, y, vw, vh] = self.target.previous_viewport();
+        self.target.restore(gl);
+        gl.glBindFramebuffer(GL_READ_FRAMEBUFFER, self.target.framebuffer(self.write));
+        gl.glBindFramebuffer(GL_DRAW_FRAMEBUFFER, self.target.previous_framebuffer());
+        gl.glBlitFramebuffer(
+            0,
+            0,
+            w,
+            h,
+            x,
+            y,
+            x + vw,
+            y + vh,
+            GL_COLOR_BUFFER_BIT,
+            GL_NEAREST,
+        );
+        gl.glBindFramebuffer(GL_FRAMEBUFFER, self.target.previous_framebuffer());
+        gl.check_gl_error(std::file!(), std::line!());
+        self.write = 1 - self.write;
+    }
+
+    /// Frees both halves, e.g. when the flow no longer declares feedback.
+    pub fn release(&mut self, gl: &Gl) {
+        self.target.release(gl);
+        self.feedback = None;
+    }
+
+    fn clear(&self, gl: &Gl) {
+        gl.glClearColor(0.0, 0.0, 0.0, 0.0);
+        for i in 0..2 {
+            gl.glBindFramebuffer(GL_FRAMEBUFFER, self.target.framebuffer(i));
+            gl.glClear(GL_COLOR_BUFFER_BIT);
+        }
+        gl.glBindFramebuffer(GL_FRAMEBUFFER, self.target.previous_framebuffer());
+    }
+
+    fn apply_sampling(&mut self, gl: &Gl, feedback: &Feedback) {
+        let filter = match feedback.filter {
+            FeedbackFilter::Nearest => GL_NEAREST,
+            FeedbackFilter::Linear => GL_LINEAR,
+        };
+        let wrap = match feedback.wrap {
+            FeedbackWrap::Clamp => GL_CLAMP_TO_EDGE,
+            FeedbackWrap::Repeat => GL_REPEAT,
+        };
+        for texture in *self.target.textures() {
+            gl.glBindTexture(GL_TEXTURE_2D, texture);
+            for (pname, param) in [
+                (GL_TEXTURE_MIN_FILTER, filter),
+                (GL_TEXTURE_MAG_FILTER, filter),
+                (GL_TEXTURE_WRAP_S, wrap),
+                (GL_TEXTURE_WRAP_T, wrap),
+            ] {
+                gl.glTexParameteri(GL_TEXTURE_2D, pname, param as GLint);
+            }
+        }
+        gl.glBindTexture(GL_TEXTURE_2D, 0);
+        self.feedback = Some(*feedback);
+    }
+
+    fn ensure_target(&mut self, gl: &Gl, size: [GLsizei; 2]) -> bool {
+        if self.target.size() != size {
+            // new textures, with the default sampling, and undefined contents
+            self.feedback = None;
+            self.write = 0;
+            self.reset_requested = true;
+        }
+        self.target.ensure(gl, size, "feedback")
+    }
+}
@@ -0,0 +1,136 @@
+/// The time model of the flow.
+///
+/// The time is kept as `f64` seconds and never reset.
+/// Shaders only get `f32`, so the time is offered in a few forms via `${...}` values:
+///
+/// - `${TIME}` the time wrapped to the wrap period, for `f32` uniforms
+/// - `${TIME_HI}`/`${TIME_LO}` the time split into two floats, `hi + lo` is the full time
+/// - `${TIME_WRAP}` the wrap period, so shaders can e.g. keep periodic functions seamless
+/// - `${FRAME}` the number of frames rendered while the time moved, so it stands still while paused
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlowTime {
+    time: f64,
+    frame: u64,
+    wrap_period: f64,
+}
+
+impl Default for FlowTime {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            frame: 0,
+            wrap_period: Self::DEFAULT_WRAP_PERIOD,
+        }
+    }
+}
+
+impl FlowTime {
+    pub const DEFAULT_WRAP_PERIOD: f64 = 2048.0;
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+    pub fn wrap_period(&self) -> f64 {
+        self.wrap_period
+    }
+    pub fn set_wrap_period(&mut self, wrap_period: f64) {
+        if wrap_period > 0.0 {
+            self.wrap_period = wrap_period;
+        }
+    }
+
+    /// Advances to the next frame at the given time, the same time again, e.g. while paused, is not a new frame.
+    pub fn advance(&mut self, time: f64) {
+        if time != self.time {
+            self.time = time;
+            self.frame += 1;
+        }
+    }
+
+    pub fn wrapped(&self) -> f32 {
+        self.time.rem_euclid(self.wrap_period) as f32
+    }
+
+    pub fn hi_lo(&self) -> (f32, f32) {
+        let hi = self.time as f32;
+        let lo = (self.time - hi as f64) as f32;
+        (hi, lo)
+    }
+
+    /// Parses `value` as a number, or resolves it as one of the `${...}` time values.
+    pub fn resolve_f32(&self, value: &str) -> f32 {
+        value.parse::<f32>().unwrap_or_else(|_| match value {
+            "${TIME}" => self.wrapped(),
+            "${TIME_HI}" => self.hi_lo().0,
+            "${TIME_LO}" => self.hi_lo().1,
+            "${TIME_WRAP}" => self.wrap_period as f32,
+            "${FRAME}" => self.frame as f32,
+            _ => 0.0,
+        })
+    }
+
+    /// Like [`FlowTime::resolve_f32`], but `${TIME}` is the full, unwrapped time.
+    pub fn resolve_f64(&self, value: &str) -> f64 {
+        value.parse::<f64>().unwrap_or_else(|_| match value {
+            "${TIME}" => self.time,
+            "${TIME_WRAP}" => self.wrap_period,
+            "${FRAME}" => self.frame as f64,
+            o => self.resolve_f32(o) as f64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_works() {
+        let mut t = FlowTime::default();
+        t.set_wrap_period(10.0);
+        t.advance(12.5);
+        t.advance(23.5);
+
+        assert_eq!(1.5, t.resolve_f32("1.5"));
+        assert_eq!(3.5, t.resolve_f32("${TIME}"));
+        assert_eq!(10.0, t.resolve_f32("${TIME_WRAP}"));
+        assert_eq!(2.0, t.resolve_f32("${FRAME}"));
+        assert_eq!(0.0, t.resolve_f32("${UNKNOWN}"));
+
+        assert_eq!(23.5, t.resolve_f64("${TIME}"));
+        assert_eq!(2.0, t.resolve_f64("${FRAME}"));
+    }
+
+    #[test]
+    fn frame_stops_while_paused() {
+        let mut t = FlowTime::default();
+        t.advance(1.0);
+        t.advance(1.0);
+        assert_eq!(1, t.frame());
+
+        // stepping back is a frame too
+        t.advance(0.5);
+        assert_eq!(2, t.frame());
+    }
+
+    #[test]
+    fn hi_lo_keeps_precision() {
+        let mut t = FlowTime::default();
+        let time = 3.0 * 24.0 * 60.0 * 60.0 + 0.123_456_789;
+        t.advance(time);
+
+        let (hi, lo) = t.hi_lo();
+        assert_ne!(time, hi as f64);
+        assert!((time - (hi as f64 + lo as f64)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wrap_period_must_be_positive() {
+        let mut t = FlowTime::default();
+        t.set_wrap_period(0.0);
+        assert_eq!(FlowTime::DEFAULT_WRAP_PERIOD, t.wrap_period());
+    }
+}
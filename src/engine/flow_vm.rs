@@ -1,14 +1,27 @@
 use crate::engine::gl::Gl;
+use crate::engine::AudioValues;
+use crate::engine::BuiltinUniforms;
+use crate::engine::Clock;
+use crate::engine::FeedbackBuffer;
+use crate::engine::FlowTime;
+use crate::engine::PreviewInput;
 use crate::engine::ResourceLogManager;
 use crate::engine::StepRunnerData;
+use crate::engine::StepRunnerDispatch;
+use crate::engine::StepRunnerDrawMesh;
 use crate::engine::StepRunnerFullscreenQuad;
 use crate::engine::StepRunnerProgram;
+use crate::engine::StepRunnerRenderState;
 use crate::engine::StepRunnerSetUniformF32;
 use crate::engine::StepRunnerSetUniformF64;
 use crate::engine::StepRunnerSetUniformVec3F32;
+use crate::engine::StorageBuffers;
 use crate::project::Flow;
 use crate::project::Project;
+use crate::project::PropertyManager;
+use crate::project::PropertyValue;
 use crate::project::Step;
+use crate::project::SyncTracks;
 use color_eyre::eyre::eyre;
 use color_eyre::Result;
 use std::collections::HashMap;
@@ -17,8 +30,21 @@ use std::collections::HashMap;
 pub struct FlowVm {
     flow: Flow,
     step_runner_data: HashMap<String, Vec<Option<Box<dyn StepRunnerData>>>>,
-    start_time: std::time::Instant,
-    time: f64,
+    clock: Clock,
+    flow_time: FlowTime,
+    audio_values: AudioValues,
+    sync_tracks: SyncTracks,
+    /// The current property values, uploaded when programs are bound.
+    property_values: HashMap<String, PropertyValue>,
+    fixed_time: Option<f64>,
+    start_label: String,
+    builtin_uniforms: BuiltinUniforms,
+    /// The viewport the flow renders to, `Step::Viewport` is relative to it.
+    viewport: [i32; 4],
+    storage_buffers: StorageBuffers,
+    feedback_buffer: FeedbackBuffer,
+    /// The [`Project::load_generation`] of the last setup.
+    load_generation: u32,
 }
 
 impl Default for FlowVm {
@@ -26,23 +52,96 @@ impl Default for FlowVm {
         Self {
             flow: Flow::default(),
             step_runner_data: HashMap::default(),
-            start_time: std::time::Instant::now(),
-            time: f64::default(),
+            clock: Clock::default(),
+            flow_time: FlowTime::default(),
+            audio_values: AudioValues::default(),
+            sync_tracks: SyncTracks::default(),
+            property_values: HashMap::default(),
+            fixed_time: None,
+            start_label: String::from("start"),
+            builtin_uniforms: BuiltinUniforms::default(),
+            viewport: [0; 4],
+            storage_buffers: StorageBuffers::default(),
+            feedback_buffer: FeedbackBuffer::default(),
+            load_generation: 0,
         }
     }
 }
 
 impl FlowVm {
-    pub fn time(&self) -> f64 {
-        self.time
+    pub fn flow_time(&self) -> &FlowTime {
+        &self.flow_time
+    }
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+    pub fn clock_mut(&mut self) -> &mut Clock {
+        &mut self.clock
+    }
+    pub fn set_audio_values(&mut self, audio_values: AudioValues) {
+        self.audio_values = audio_values;
+    }
+    pub fn set_sync_tracks(&mut self, sync_tracks: SyncTracks) {
+        self.sync_tracks = sync_tracks;
+    }
+    /// Sets the viewport the flow renders to, as `[x, y, width, height]` in pixels.
+    pub fn set_viewport(&mut self, viewport: [i32; 4]) {
+        self.viewport = viewport;
+        self.builtin_uniforms.set_viewport(viewport);
+    }
+    pub fn set_input(&mut self, input: &PreviewInput) {
+        self.builtin_uniforms.set_input(input);
+    }
+    pub fn set_property_values(&mut self, property_manager: &PropertyManager) {
+        self.property_values.clear();
+        for (k, p) in property_manager.entries().iter() {
+            self.property_values.insert(k.clone(), p.shader_value());
+        }
+    }
+    /// Resolves `value` as a number, or one of the `${...}` time, audio, and sync track values.
+    pub fn resolve_f32(&self, value: &str) -> f32 {
+        self.audio_values
+            .resolve(value)
+            .or_else(|| self.sync_tracks.resolve(value, self.flow_time.time()))
+            .unwrap_or_else(|| self.flow_time.resolve_f32(value))
+    }
+    /// Like [`FlowVm::resolve_f32`], but keeps the full precision of the time.
+    pub fn resolve_f64(&self, value: &str) -> f64 {
+        self.audio_values
+            .resolve(value)
+            .or_else(|| self.sync_tracks.resolve(value, self.flow_time.time()))
+            .map(|v| v as f64)
+            .unwrap_or_else(|| self.flow_time.resolve_f64(value))
+    }
+    pub fn set_time_wrap_period(&mut self, wrap_period: f64) {
+        self.flow_time.set_wrap_period(wrap_period);
+    }
+    pub fn set_fixed_time(&mut self, fixed_time: Option<f64>) {
+        self.fixed_time = fixed_time;
+    }
+    pub fn set_start_label(&mut self, start_label: &str) {
+        // the data is torn down by the next `run_setup`
+        self.start_label = start_label.to_string();
+    }
+    /// Clears the feedback before the next frame.
+    pub fn reset_feedback(&mut self) {
+        self.feedback_buffer.reset();
     }
     pub fn load(&mut self, flow: &Flow) -> Result<()> {
         //    	eprintln!("Load!!!!!!!!!!!!!!!!!");
         self.flow = flow.clone();
-        self.step_runner_data = HashMap::default();
         Ok(())
     }
 
+    /// Frees the GPU objects of the last setup, the data might be from an older flow, so it is not matched by step.
+    fn run_teardown(&mut self, gl: &Gl) {
+        for (_label, srd_block) in self.step_runner_data.drain() {
+            for mut srd in srd_block {
+                StepRunnerDrawMesh::default().run_teardown(gl, &mut srd);
+            }
+        }
+    }
+
     pub fn run_setup(
         &mut self,
         gl: &Gl,
@@ -50,9 +149,16 @@ impl FlowVm {
         resource_log_manager: &mut ResourceLogManager,
     ) -> Result<()> {
         // !!! should only run once when project/flow is changed !!!
+        self.run_teardown(gl);
+        self.storage_buffers.sync(gl, project);
+        // another project's last frame
+        if self.load_generation != project.load_generation() {
+            self.load_generation = project.load_generation();
+            self.feedback_buffer.reset();
+        }
         if let Some(start_step) = self.flow.steps().iter().find(|(s, _gp)| {
             if let Step::Label { name, .. } = s {
-                name == "start"
+                *name == self.start_label
             } else {
                 false
             }
@@ -73,6 +179,12 @@ impl FlowVm {
                         sr.run_setup(gl, project, resource_log_manager, step, &mut srd);
                         srd_block[s_idx] = srd;
                     }
+                    Step::Dispatch { .. } => {
+                        let sr = StepRunnerDispatch::default();
+                        let mut srd = sr.create_data();
+                        sr.run_setup(gl, project, resource_log_manager, step, &mut srd);
+                        srd_block[s_idx] = srd;
+                    }
                     Step::SetUniformF32 { .. } => {
                         let sr = StepRunnerSetUniformF32::default();
                         let mut srd = sr.create_data();
@@ -97,6 +209,21 @@ impl FlowVm {
                         sr.run_setup(gl, &mut srd);
                         srd_block[s_idx] = srd;
                     }
+                    Step::DrawMesh { .. }
+                    | Step::Plane { .. }
+                    | Step::Sphere { .. }
+                    | Step::LineStrip { .. }
+                    | Step::Particles { .. } => {
+                        let sr = StepRunnerDrawMesh::default();
+                        let mut srd = sr.create_data();
+                        sr.run_setup(gl, project, step, &mut srd);
+                        srd_block[s_idx] = srd;
+                    }
+                    Step::Clear { .. }
+                    | Step::Blend { .. }
+                    | Step::Depth { .. }
+                    | Step::Cull { .. }
+                    | Step::Viewport { .. } => {}
                     Step::Label { .. } => {}
                     Step::Nop => {}
                 }
@@ -104,24 +231,46 @@ impl FlowVm {
                 s_idx += 1;
             }
             self.step_runner_data
-                .insert(String::from("start"), srd_block); // :TODO: "start" is not the right name
+                .insert(self.start_label.clone(), srd_block);
         }
         Ok(())
     }
+    /// Moves to the next frame, and returns its time.
+    pub fn advance_time(&mut self) -> f64 {
+        let time = match self.fixed_time {
+            Some(fixed_time) => fixed_time,
+            None => self.clock.tick(),
+        };
+        self.flow_time.advance(time);
+        self.builtin_uniforms
+            .advance(time, self.flow_time.wrapped(), self.flow_time.frame());
+        time
+    }
     pub fn run_update(&mut self, gl: &Gl) -> Result<()> {
-        // update time
-        let now = std::time::Instant::now();
-        let t = now - self.start_time;
-
-        self.time = t.as_secs_f64();
-
-        if self.time > 2048.0 {
-            self.start_time = now;
-        }
-
+        self.builtin_uniforms.prepare(gl);
+        self.storage_buffers.bind(gl);
+        let feedback_viewport = match self.flow.feedback() {
+            Some(feedback) => self.feedback_buffer.begin(gl, feedback, &self.viewport),
+            None => {
+                self.feedback_buffer.release(gl);
+                None
+            }
+        };
+        let viewport = feedback_viewport.unwrap_or(self.viewport);
+        let [_, _, w, h] = viewport;
+        self.builtin_uniforms
+            .set_feedback(self.feedback_buffer.read_texture(), [w, h]);
+        let result = self.run_steps(gl, &viewport);
+        self.feedback_buffer.end(gl);
+        result
+    }
+    /// Runs the steps from the start label, `viewport` is the one the flow renders to this frame.
+    fn run_steps(&mut self, gl: &Gl, viewport: &[i32; 4]) -> Result<()> {
+        // undo the `Step::Viewport`s of the last frame
+        self.builtin_uniforms.set_viewport(*viewport);
         if let Some(start_step) = self.flow.steps().iter().find(|(s, _gp)| {
             if let Step::Label { name, .. } = s {
-                name == "start"
+                *name == self.start_label
             } else {
                 false
             }
@@ -132,8 +281,8 @@ impl FlowVm {
             let mut s_idx = 0;
             let srd_block = self
                 .step_runner_data
-                .get("start")
-                .ok_or(eyre!("Data for block `start` not found"))?;
+                .get(&self.start_label)
+                .ok_or(eyre!("Data for block `{}` not found", self.start_label))?;
 
             while let Some(step) = self.flow.get_step_at(&pos) {
                 // eprintln!("Update {s_idx} {step:?}");
@@ -142,7 +291,13 @@ impl FlowVm {
                         let sr = StepRunnerProgram::default();
 
                         let srd = &srd_block[s_idx];
-                        sr.run_render(gl, srd);
+                        sr.run_render(gl, &self.property_values, &self.builtin_uniforms, srd);
+                    }
+                    Step::Dispatch { .. } => {
+                        let sr = StepRunnerDispatch::default();
+
+                        let srd = &srd_block[s_idx];
+                        sr.run_render(gl, &self.property_values, &self.builtin_uniforms, step, srd);
                     }
                     Step::SetUniformF32 { .. } => {
                         let sr = StepRunnerSetUniformF32::default();
@@ -168,6 +323,27 @@ impl FlowVm {
                         let srd = &srd_block[s_idx];
                         sr.run_render(gl, srd);
                     }
+                    Step::DrawMesh { .. }
+                    | Step::Plane { .. }
+                    | Step::Sphere { .. }
+                    | Step::LineStrip { .. }
+                    | Step::Particles { .. } => {
+                        let sr = StepRunnerDrawMesh::default();
+
+                        let srd = &srd_block[s_idx];
+                        sr.run_render(gl, srd);
+                    }
+                    Step::Clear { .. }
+                    | Step::Blend { .. }
+                    | Step::Depth { .. }
+                    | Step::Cull { .. }
+                    | Step::Viewport { .. } => {
+                        let sr = StepRunnerRenderState::default();
+
+                        if let Some(viewport) = sr.run_render(gl, step, viewport) {
+                            self.builtin_uniforms.set_viewport(viewport);
+                        }
+                    }
                     Step::Label { .. } => {}
                     Step::Nop => {}
                 }
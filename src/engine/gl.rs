@@ -1,5 +1,8 @@
 #![allow(dead_code)]
+#[cfg(not(target_arch = "wasm32"))]
 use super::gl_wrapper_macro::*;
+#[cfg(not(target_arch = "wasm32"))]
+use super::GlslProfile;
 use color_eyre::eyre::eyre;
 use color_eyre::Result;
 use core::ffi::*;
@@ -12,24 +15,102 @@ pub type GLsizei = core::ffi::c_int;
 pub type GLchar = core::ffi::c_char;
 pub type GLfloat = core::ffi::c_float;
 pub type GLdouble = core::ffi::c_double;
+pub type GLvoid = core::ffi::c_void;
+pub type GLubyte = core::ffi::c_uchar;
+pub type GLboolean = core::ffi::c_uchar;
+pub type GLbitfield = core::ffi::c_uint;
+pub type GLstring = *const GLubyte;
 
 pub const GL_CURRENT_PROGRAM: GLenum = 0x8B8D;
 pub const GL_ARRAY_BUFFER: GLenum = 0x8892;
+pub const GL_ELEMENT_ARRAY_BUFFER: GLenum = 0x8893;
 pub const GL_STATIC_DRAW: GLenum = 0x88E4;
 pub const GL_DYNAMIC_DRAW: GLenum = 0x88E8;
+pub const GL_DYNAMIC_COPY: GLenum = 0x88EA;
+pub const GL_SHADER_STORAGE_BUFFER: GLenum = 0x90D2;
+pub const GL_ALL_BARRIER_BITS: GLbitfield = 0xFFFFFFFF;
 pub const GL_FLOAT: GLenum = 0x1406;
+pub const GL_DOUBLE: GLenum = 0x140A;
+pub const GL_UNSIGNED_BYTE: GLenum = 0x1401;
+pub const GL_UNSIGNED_INT: GLenum = 0x1405;
+pub const GL_RGBA: GLenum = 0x1908;
 pub const GL_FLOAT_VEC2: GLenum = 0x8B50;
 pub const GL_FLOAT_VEC3: GLenum = 0x8B51;
+pub const GL_FLOAT_VEC4: GLenum = 0x8B52;
+pub const GL_FLOAT_MAT4: GLenum = 0x8B5C;
+pub const GL_INT: GLenum = 0x1404;
+pub const GL_SAMPLER_2D: GLenum = 0x8B5E;
 pub const GL_FALSE: GLenum = 0x0000;
 pub const GL_TRUE: GLenum = 0x0001;
+pub const GL_POINTS: GLenum = 0x0000;
+pub const GL_LINE_STRIP: GLenum = 0x0003;
+pub const GL_TRIANGLES: GLenum = 0x0004;
 pub const GL_TRIANGLE_STRIP: GLenum = 0x0005;
 pub const GL_CULL_FACE: GLenum = 0x0B44;
+pub const GL_BACK: GLenum = 0x0405;
+pub const GL_FRONT: GLenum = 0x0404;
+pub const GL_BLEND: GLenum = 0x0BE2;
+pub const GL_BLEND_SRC_RGB: GLenum = 0x80C9;
+pub const GL_BLEND_DST_RGB: GLenum = 0x80C8;
+pub const GL_BLEND_SRC_ALPHA: GLenum = 0x80CB;
+pub const GL_BLEND_DST_ALPHA: GLenum = 0x80CA;
+pub const GL_BLEND_EQUATION_RGB: GLenum = 0x8009;
+pub const GL_BLEND_EQUATION_ALPHA: GLenum = 0x883D;
+pub const GL_FUNC_ADD: GLenum = 0x8006;
+pub const GL_ZERO: GLenum = 0x0000;
+pub const GL_ONE: GLenum = 0x0001;
+pub const GL_SRC_COLOR: GLenum = 0x0300;
+pub const GL_SRC_ALPHA: GLenum = 0x0302;
+pub const GL_ONE_MINUS_SRC_ALPHA: GLenum = 0x0303;
+pub const GL_DST_COLOR: GLenum = 0x0306;
+pub const GL_DEPTH_TEST: GLenum = 0x0B71;
+pub const GL_LESS: GLenum = 0x0201;
+pub const GL_EQUAL: GLenum = 0x0202;
+pub const GL_LEQUAL: GLenum = 0x0203;
+pub const GL_GREATER: GLenum = 0x0204;
+pub const GL_GEQUAL: GLenum = 0x0206;
+pub const GL_ALWAYS: GLenum = 0x0207;
+pub const GL_COLOR_BUFFER_BIT: GLbitfield = 0x4000;
+pub const GL_DEPTH_BUFFER_BIT: GLbitfield = 0x0100;
 pub const GL_VERTEX_SHADER: GLenum = 0x8B31;
 pub const GL_FRAGMENT_SHADER: GLenum = 0x8B30;
+pub const GL_GEOMETRY_SHADER: GLenum = 0x8DD9;
+pub const GL_COMPUTE_SHADER: GLenum = 0x91B9;
 pub const GL_COMPILE_STATUS: GLenum = 0x8B81;
 pub const GL_LINK_STATUS: GLenum = 0x8B82;
 pub const GL_INFO_LOG_LENGTH: GLenum = 0x8B84;
 pub const GL_ACTIVE_UNIFORMS: GLenum = 0x8B86;
+pub const GL_VERSION: GLenum = 0x1F02;
+pub const GL_MAJOR_VERSION: GLenum = 0x821B;
+pub const GL_MINOR_VERSION: GLenum = 0x821C;
+pub const GL_VIEWPORT: GLenum = 0x0BA2;
+pub const GL_SCISSOR_TEST: GLenum = 0x0C11;
+pub const GL_SCISSOR_BOX: GLenum = 0x0C10;
+pub const GL_PROGRAM_POINT_SIZE: GLenum = 0x8642;
+pub const GL_FRAMEBUFFER: GLenum = 0x8D40;
+pub const GL_FRAMEBUFFER_BINDING: GLenum = 0x8CA6;
+pub const GL_READ_FRAMEBUFFER: GLenum = 0x8CA8;
+pub const GL_DRAW_FRAMEBUFFER: GLenum = 0x8CA9;
+pub const GL_FRAMEBUFFER_COMPLETE: GLenum = 0x8CD5;
+pub const GL_COLOR_ATTACHMENT0: GLenum = 0x8CE0;
+pub const GL_DEPTH_ATTACHMENT: GLenum = 0x8D00;
+pub const GL_TEXTURE_2D: GLenum = 0x0DE1;
+pub const GL_TEXTURE0: GLenum = 0x84C0;
+pub const GL_TEXTURE_MIN_FILTER: GLenum = 0x2801;
+pub const GL_TEXTURE_MAG_FILTER: GLenum = 0x2800;
+pub const GL_TEXTURE_WRAP_S: GLenum = 0x2802;
+pub const GL_TEXTURE_WRAP_T: GLenum = 0x2803;
+pub const GL_NEAREST: GLenum = 0x2600;
+pub const GL_LINEAR: GLenum = 0x2601;
+pub const GL_REPEAT: GLenum = 0x2901;
+pub const GL_CLAMP_TO_EDGE: GLenum = 0x812F;
+pub const GL_RGBA8: GLenum = 0x8058;
+pub const GL_RGBA16F: GLenum = 0x881A;
+pub const GL_RED: GLenum = 0x1903;
+pub const GL_R8: GLenum = 0x8229;
+pub const GL_HALF_FLOAT: GLenum = 0x140B;
+pub const GL_DEPTH_COMPONENT: GLenum = 0x1902;
+pub const GL_DEPTH_COMPONENT24: GLenum = 0x81A6;
 
 #[derive(Debug)]
 pub struct GlFunctionPointer {
@@ -47,6 +128,9 @@ impl GlFunctionPointer {
             f: core::ptr::null(),
         }
     }
+    pub fn is_loaded(&self) -> bool {
+        !self.f.is_null()
+    }
 }
 
 impl GlFunctionPointer {
@@ -79,6 +163,10 @@ impl From<GlFunctionPointer> for extern "system" fn(i16, i16, i16, i16) -> c_voi
 //unsafe impl Send for GlFunctionPointer {}
 unsafe impl Sync for GlFunctionPointer {}
 
+#[cfg(target_arch = "wasm32")]
+pub use super::gl_glow::Gl;
+
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, Default)]
 pub struct Gl {
     glfp_rects: GlFunctionPointer,
@@ -93,15 +181,37 @@ pub struct Gl {
     glfp_get_programiv: GlFunctionPointer,
     glfp_get_program_info_log: GlFunctionPointer,
     glfps: Glfps,
+    glsl_profile: GlslProfile,
+    /// `[major, minor]`, compute shaders need 4.3.
+    version: [GLint; 2],
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, Default)]
 #[allow(non_snake_case)]
 struct Glfps {
     glGetError: GlFunctionPointer,
     glDisable: GlFunctionPointer,
+    glEnable: GlFunctionPointer,
+    glIsEnabled: GlFunctionPointer,
+    glViewport: GlFunctionPointer,
+    glScissor: GlFunctionPointer,
+    glClearColor: GlFunctionPointer,
+    glClearDepth: GlFunctionPointer,
+    glClear: GlFunctionPointer,
+    glBlendFuncSeparate: GlFunctionPointer,
+    glBlendEquationSeparate: GlFunctionPointer,
+    glDepthFunc: GlFunctionPointer,
+    glDepthMask: GlFunctionPointer,
+    glCullFace: GlFunctionPointer,
     glGenVertexArrays: GlFunctionPointer,
+    glDeleteVertexArrays: GlFunctionPointer,
     glBindVertexArray: GlFunctionPointer,
+    glDrawElementsInstanced: GlFunctionPointer,
+    glDeleteBuffers: GlFunctionPointer,
+    glBindBufferBase: GlFunctionPointer,
+    glDispatchCompute: GlFunctionPointer,
+    glMemoryBarrier: GlFunctionPointer,
     glCreateShader: GlFunctionPointer,
     glShaderSource: GlFunctionPointer,
     glCompileShader: GlFunctionPointer,
@@ -117,13 +227,32 @@ struct Glfps {
     glProgramUniform1f: GlFunctionPointer,
     glProgramUniform2fv: GlFunctionPointer,
     glProgramUniform3fv: GlFunctionPointer,
+    glProgramUniform4fv: GlFunctionPointer,
+    glProgramUniform1i: GlFunctionPointer,
     glProgramUniform1d: GlFunctionPointer,
+    glProgramUniformMatrix4fv: GlFunctionPointer,
 
     glGetActiveUniform: GlFunctionPointer,
     glFinish: GlFunctionPointer,
     glGetIntegerv: GlFunctionPointer,
+    glReadPixels: GlFunctionPointer,
+    glGetString: GlFunctionPointer,
+
+    glGenFramebuffers: GlFunctionPointer,
+    glDeleteFramebuffers: GlFunctionPointer,
+    glBindFramebuffer: GlFunctionPointer,
+    glFramebufferTexture2D: GlFunctionPointer,
+    glCheckFramebufferStatus: GlFunctionPointer,
+    glBlitFramebuffer: GlFunctionPointer,
+    glGenTextures: GlFunctionPointer,
+    glDeleteTextures: GlFunctionPointer,
+    glBindTexture: GlFunctionPointer,
+    glActiveTexture: GlFunctionPointer,
+    glTexImage2D: GlFunctionPointer,
+    glTexParameteri: GlFunctionPointer,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl Gl {
     pub fn check_gl_error(&self, file: &str, line: u32) -> bool {
         let error = self.glGetError();
@@ -151,10 +280,43 @@ impl Gl {
             .glGetError
             .load(get_proc_address, c"glGetError")?;
         self.glfps.glDisable.load(get_proc_address, c"glDisable")?;
+        self.glfps.glEnable.load(get_proc_address, c"glEnable")?;
+        self.glfps
+            .glIsEnabled
+            .load(get_proc_address, c"glIsEnabled")?;
+        self.glfps
+            .glViewport
+            .load(get_proc_address, c"glViewport")?;
+        self.glfps.glScissor.load(get_proc_address, c"glScissor")?;
+        self.glfps
+            .glClearColor
+            .load(get_proc_address, c"glClearColor")?;
+        self.glfps
+            .glClearDepth
+            .load(get_proc_address, c"glClearDepth")?;
+        self.glfps.glClear.load(get_proc_address, c"glClear")?;
+        self.glfps
+            .glBlendFuncSeparate
+            .load(get_proc_address, c"glBlendFuncSeparate")?;
+        self.glfps
+            .glBlendEquationSeparate
+            .load(get_proc_address, c"glBlendEquationSeparate")?;
+        self.glfps
+            .glDepthFunc
+            .load(get_proc_address, c"glDepthFunc")?;
+        self.glfps
+            .glDepthMask
+            .load(get_proc_address, c"glDepthMask")?;
+        self.glfps
+            .glCullFace
+            .load(get_proc_address, c"glCullFace")?;
         self.glfp_rects.load(get_proc_address, c"glRects")?;
         self.glfps
             .glGenVertexArrays
             .load(get_proc_address, c"glGenVertexArrays")?;
+        self.glfps
+            .glDeleteVertexArrays
+            .load(get_proc_address, c"glDeleteVertexArrays")?;
         self.glfps
             .glBindVertexArray
             .load(get_proc_address, c"glBindVertexArray")?;
@@ -170,6 +332,24 @@ impl Gl {
             .load(get_proc_address, c"glVertexAttribPointer")?;
         self.glfp_draw_arrays
             .load(get_proc_address, c"glDrawArrays")?;
+        self.glfps
+            .glDrawElementsInstanced
+            .load(get_proc_address, c"glDrawElementsInstanced")?;
+        self.glfps
+            .glDeleteBuffers
+            .load(get_proc_address, c"glDeleteBuffers")?;
+        self.glfps
+            .glBindBufferBase
+            .load(get_proc_address, c"glBindBufferBase")?;
+        // optional, GL 4.3, not on macOS
+        let _ = self
+            .glfps
+            .glDispatchCompute
+            .load(get_proc_address, c"glDispatchCompute");
+        let _ = self
+            .glfps
+            .glMemoryBarrier
+            .load(get_proc_address, c"glMemoryBarrier");
 
         self.glfps
             .glCreateShader
@@ -223,8 +403,19 @@ impl Gl {
             .glProgramUniform3fv
             .load(get_proc_address, c"glProgramUniform3fv")?;
         self.glfps
+            .glProgramUniform4fv
+            .load(get_proc_address, c"glProgramUniform4fv")?;
+        self.glfps
+            .glProgramUniform1i
+            .load(get_proc_address, c"glProgramUniform1i")?;
+        self.glfps
+            .glProgramUniformMatrix4fv
+            .load(get_proc_address, c"glProgramUniformMatrix4fv")?;
+        // desktop only, GLES/WebGL2 have no double precision uniforms
+        let _ = self
+            .glfps
             .glProgramUniform1d
-            .load(get_proc_address, c"glProgramUniform1d")?;
+            .load(get_proc_address, c"glProgramUniform1d");
 
         self.glfps
             .glGetActiveUniform
@@ -234,14 +425,118 @@ impl Gl {
         self.glfps
             .glGetIntegerv
             .load(get_proc_address, c"glGetIntegerv")?;
+        self.glfps
+            .glReadPixels
+            .load(get_proc_address, c"glReadPixels")?;
+        self.glfps
+            .glGetString
+            .load(get_proc_address, c"glGetString")?;
+
+        self.glfps
+            .glGenFramebuffers
+            .load(get_proc_address, c"glGenFramebuffers")?;
+        self.glfps
+            .glDeleteFramebuffers
+            .load(get_proc_address, c"glDeleteFramebuffers")?;
+        self.glfps
+            .glBindFramebuffer
+            .load(get_proc_address, c"glBindFramebuffer")?;
+        self.glfps
+            .glFramebufferTexture2D
+            .load(get_proc_address, c"glFramebufferTexture2D")?;
+        self.glfps
+            .glCheckFramebufferStatus
+            .load(get_proc_address, c"glCheckFramebufferStatus")?;
+        self.glfps
+            .glBlitFramebuffer
+            .load(get_proc_address, c"glBlitFramebuffer")?;
+        self.glfps
+            .glGenTextures
+            .load(get_proc_address, c"glGenTextures")?;
+        self.glfps
+            .glDeleteTextures
+            .load(get_proc_address, c"glDeleteTextures")?;
+        self.glfps
+            .glBindTexture
+            .load(get_proc_address, c"glBindTexture")?;
+        self.glfps
+            .glActiveTexture
+            .load(get_proc_address, c"glActiveTexture")?;
+        self.glfps
+            .glTexImage2D
+            .load(get_proc_address, c"glTexImage2D")?;
+        self.glfps
+            .glTexParameteri
+            .load(get_proc_address, c"glTexParameteri")?;
+
+        self.glsl_profile = self.detect_glsl_profile();
+        let mut version = [0; 2];
+        self.glGetIntegerv(GL_MAJOR_VERSION, &mut version[0]);
+        self.glGetIntegerv(GL_MINOR_VERSION, &mut version[1]);
+        self.version = version;
+        eprintln!(
+            "GLSL profile: {:?} {:?} (doubles: {}, compute: {}, geometry: {})",
+            self.glsl_profile,
+            self.version,
+            self.supports_double(),
+            self.supports_compute(),
+            self.supports_geometry(),
+        );
 
         Ok(())
     }
 
+    fn detect_glsl_profile(&self) -> GlslProfile {
+        let version = self.glGetString(GL_VERSION);
+        if version.is_null() {
+            return GlslProfile::default();
+        }
+        let version = unsafe { CStr::from_ptr(version as *const c_char) };
+        GlslProfile::from_gl_version(&version.to_string_lossy())
+    }
+
+    pub fn glsl_profile(&self) -> GlslProfile {
+        self.glsl_profile
+    }
+
+    pub fn supports_double(&self) -> bool {
+        self.glsl_profile.supports_double() && self.glfps.glProgramUniform1d.is_loaded()
+    }
+
+    /// Compute shaders and shader storage buffers, GL 4.3.
+    pub fn supports_compute(&self) -> bool {
+        self.glsl_profile == GlslProfile::Desktop410
+            && self.version >= [4, 3]
+            && self.glfps.glDispatchCompute.is_loaded()
+            && self.glfps.glMemoryBarrier.is_loaded()
+    }
+
+    pub fn supports_geometry(&self) -> bool {
+        self.glsl_profile == GlslProfile::Desktop410
+    }
+
     create_gl_wrapper!(GLenum glGetError( void ) );
     create_gl_wrapper!(void glDisable( GLenum cap ));
+    create_gl_wrapper!(void glEnable( GLenum cap ));
+    create_gl_wrapper!(GLboolean glIsEnabled( GLenum cap ));
+    create_gl_wrapper!(void glViewport( GLint x, GLint y, GLsizei width, GLsizei height ));
+    create_gl_wrapper!(void glScissor( GLint x, GLint y, GLsizei width, GLsizei height ));
+    create_gl_wrapper!(void glClearColor( GLfloat red, GLfloat green, GLfloat blue, GLfloat alpha ));
+    create_gl_wrapper!(void glClearDepth( GLdouble depth ));
+    create_gl_wrapper!(void glClear( GLbitfield mask ));
+    create_gl_wrapper!(void glBlendFuncSeparate( GLenum srcRGB, GLenum dstRGB, GLenum srcAlpha, GLenum dstAlpha ));
+    create_gl_wrapper!(void glBlendEquationSeparate( GLenum modeRGB, GLenum modeAlpha ));
+    create_gl_wrapper!(void glDepthFunc( GLenum func ));
+    create_gl_wrapper!(void glDepthMask( GLboolean flag ));
+    create_gl_wrapper!(void glCullFace( GLenum mode ));
     create_gl_wrapper!(void glGenVertexArrays(GLsizei n, GLuint *arrays));
+    create_gl_wrapper!(void glDeleteVertexArrays(GLsizei n, const GLuint *arrays));
     create_gl_wrapper!(void glBindVertexArray(GLuint array));
+    create_gl_wrapper!(void glDrawElementsInstanced(GLenum mode, GLsizei count, GLenum ttype, const GLvoid *indices, GLsizei instancecount));
+    create_gl_wrapper!(void glDeleteBuffers( GLsizei n, const GLuint *buffers ));
+    create_gl_wrapper!(void glBindBufferBase( GLenum target, GLuint index, GLuint buffer ));
+    create_gl_wrapper!(void glDispatchCompute( GLuint num_groups_x, GLuint num_groups_y, GLuint num_groups_z ));
+    create_gl_wrapper!(void glMemoryBarrier( GLbitfield barriers ));
 
     create_gl_wrapper!(GLuint glCreateShader(GLenum shaderType));
     create_gl_wrapper!(void glShaderSource(GLuint shader, GLsizei count, const GLchar **string, const GLint *length));
@@ -258,10 +553,28 @@ impl Gl {
     create_gl_wrapper!(void glProgramUniform1f( GLuint program, GLint location, GLfloat v0));
     create_gl_wrapper!(void glProgramUniform2fv( GLuint program, GLint location, GLsizei count, const GLfloat *value));
     create_gl_wrapper!(void glProgramUniform3fv( GLuint program, GLint location, GLsizei count, const GLfloat *value));
+    create_gl_wrapper!(void glProgramUniform4fv( GLuint program, GLint location, GLsizei count, const GLfloat *value));
+    create_gl_wrapper!(void glProgramUniform1i( GLuint program, GLint location, GLint v0));
     create_gl_wrapper!(void glProgramUniform1d( GLuint program, GLint location, GLdouble v0));
+    create_gl_wrapper!(void glProgramUniformMatrix4fv( GLuint program, GLint location, GLsizei count, GLboolean transpose, const GLfloat *value));
     create_gl_wrapper!(void glGetActiveUniform(GLuint program, GLuint index, GLsizei bufSize, GLsizei *length, GLint *size, GLenum *ttype, GLchar *name));
     create_gl_wrapper!(void glFinish( void ));
     create_gl_wrapper!(void glGetIntegerv( GLenum pname, GLint * data ));
+    create_gl_wrapper!(GLstring glGetString( GLenum name ));
+    create_gl_wrapper!(void glReadPixels( GLint x, GLint y, GLsizei width, GLsizei height, GLenum format, GLenum ttype, GLvoid *data ));
+
+    create_gl_wrapper!(void glGenFramebuffers( GLsizei n, GLuint *framebuffers ));
+    create_gl_wrapper!(void glDeleteFramebuffers( GLsizei n, const GLuint *framebuffers ));
+    create_gl_wrapper!(void glBindFramebuffer( GLenum target, GLuint framebuffer ));
+    create_gl_wrapper!(void glFramebufferTexture2D( GLenum target, GLenum attachment, GLenum textarget, GLuint texture, GLint level ));
+    create_gl_wrapper!(GLenum glCheckFramebufferStatus( GLenum target ));
+    create_gl_wrapper!(void glBlitFramebuffer( GLint srcX0, GLint srcY0, GLint srcX1, GLint srcY1, GLint dstX0, GLint dstY0, GLint dstX1, GLint dstY1, GLbitfield mask, GLenum filter ));
+    create_gl_wrapper!(void glGenTextures( GLsizei n, GLuint *textures ));
+    create_gl_wrapper!(void glDeleteTextures( GLsizei n, const GLuint *textures ));
+    create_gl_wrapper!(void glBindTexture( GLenum target, GLuint texture ));
+    create_gl_wrapper!(void glActiveTexture( GLenum texture ));
+    create_gl_wrapper!(void glTexImage2D( GLenum target, GLint level, GLint internalformat, GLsizei width, GLsizei height, GLint border, GLenum format, GLenum ttype, const GLvoid *data ));
+    create_gl_wrapper!(void glTexParameteri( GLenum target, GLenum pname, GLint param ));
 
     pub fn rects(&self, x1: i16, y1: i16, x2: i16, y2: i16) {
         unsafe {
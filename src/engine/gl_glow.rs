@@ -0,0 +1,796 @@
+#![allow(dead_code)]
+//! `Gl` on top of the `glow::Context` egui already uses, for the web build.
+//!
+//! Mirrors the raw function pointer `Gl` from `gl.rs`, so the engine code is the same for both.
+//! WebGL has no integer object names, so the glow objects are kept in tables and referred to by index.
+
+use super::gl::*;
+use super::GlslProfile;
+use color_eyre::Result;
+use core::cell::Cell;
+use core::cell::RefCell;
+use core::ffi::*;
+use eframe::glow;
+use eframe::glow::HasContext;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Objects created via glow, index + 1 is the name used by the engine, 0 is "no object".
+#[derive(Debug)]
+struct ObjectTable<T: Copy> {
+    objects: Vec<T>,
+}
+
+impl<T: Copy> Default for ObjectTable<T> {
+    fn default() -> Self {
+        Self {
+            objects: Vec::default(),
+        }
+    }
+}
+
+impl<T: Copy> ObjectTable<T> {
+    fn add(&mut self, o: T) -> GLuint {
+        self.objects.push(o);
+        self.objects.len() as GLuint
+    }
+    fn get(&self, name: GLuint) -> Option<T> {
+        if name == 0 {
+            None
+        } else {
+            self.objects.get(name as usize - 1).copied()
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Gl {
+    context: Option<Arc<glow::Context>>,
+
+    shaders: RefCell<ObjectTable<glow::Shader>>,
+    programs: RefCell<ObjectTable<glow::Program>>,
+    buffers: RefCell<ObjectTable<glow::Buffer>>,
+    vertex_arrays: RefCell<ObjectTable<glow::VertexArray>>,
+    framebuffers: RefCell<ObjectTable<glow::Framebuffer>>,
+    textures: RefCell<ObjectTable<glow::Texture>>,
+    uniform_locations: RefCell<Vec<glow::UniformLocation>>,
+    uniform_location_names: RefCell<HashMap<(GLuint, String), GLint>>,
+
+    // WebGL can not return the current program as a name
+    current_program: Cell<GLuint>,
+    // same for the framebuffer, 0 is the one egui paints to
+    current_framebuffer: Cell<GLuint>,
+}
+
+impl core::fmt::Debug for Gl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Gl")
+            .field("current_program", &self.current_program)
+            .finish()
+    }
+}
+
+impl Gl {
+    pub fn check_gl_error(&self, file: &str, line: u32) -> bool {
+        let error = self.glGetError();
+        match error {
+            0 => {
+                return false;
+            }
+            0x500 => {
+                eprintln!("GL_INVALID_ENUM - {file}:{line}");
+            }
+            0x0502 => {
+                eprintln!("GL_INVALID_OPERATION - {file}:{line}");
+            }
+            e => {
+                eprintln!("0x{e:04x?} - {file}:{line}");
+            }
+        }
+
+        true
+    }
+
+    pub fn load_glow(&mut self, context: Arc<glow::Context>) -> Result<()> {
+        self.context = Some(context);
+        Ok(())
+    }
+
+    fn gl(&self) -> &glow::Context {
+        self.context
+            .as_ref()
+            .expect("glow context should be loaded")
+    }
+
+    pub fn glsl_profile(&self) -> GlslProfile {
+        GlslProfile::Es300
+    }
+
+    pub fn supports_double(&self) -> bool {
+        false
+    }
+
+    /// WebGL2 has no compute shaders, or shader storage buffers.
+    pub fn supports_compute(&self) -> bool {
+        false
+    }
+
+    pub fn supports_geometry(&self) -> bool {
+        false
+    }
+
+    fn with_program<F>(&self, program: GLuint, f: F)
+    where
+        F: FnOnce(&glow::Context),
+    {
+        let current_program = self.current_program.get();
+        if current_program != program {
+            self.glUseProgram(program);
+        }
+        f(self.gl());
+        if current_program != program {
+            self.glUseProgram(current_program);
+        }
+    }
+
+    fn uniform_location(&self, location: GLint) -> Option<glow::UniformLocation> {
+        if location < 0 {
+            None
+        } else {
+            self.uniform_locations
+                .borrow()
+                .get(location as usize)
+                .cloned()
+        }
+    }
+
+    /// Copies `s` into a C string buffer, like the `Get*InfoLog` calls do.
+    fn write_string(s: &str, max_length: GLsizei, length: *mut GLsizei, out: *mut GLchar) {
+        if max_length <= 0 || out.is_null() {
+            return;
+        }
+        let len = s.len().min(max_length as usize - 1);
+        unsafe {
+            core::ptr::copy_nonoverlapping(s.as_ptr() as *const GLchar, out, len);
+            *out.add(len) = 0;
+            if !length.is_null() {
+                *length = len as GLsizei;
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub fn glGetError(&self) -> GLenum {
+        unsafe { self.gl().get_error() }
+    }
+    #[allow(non_snake_case)]
+    pub fn glDisable(&self, cap: GLenum) {
+        unsafe { self.gl().disable(cap) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glEnable(&self, cap: GLenum) {
+        unsafe { self.gl().enable(cap) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glIsEnabled(&self, cap: GLenum) -> GLboolean {
+        (unsafe { self.gl().is_enabled(cap) }) as GLboolean
+    }
+    #[allow(non_snake_case)]
+    pub fn glViewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        unsafe { self.gl().viewport(x, y, width, height) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glScissor(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        unsafe { self.gl().scissor(x, y, width, height) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glClearColor(&self, red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+        unsafe { self.gl().clear_color(red, green, blue, alpha) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glClearDepth(&self, depth: GLdouble) {
+        // WebGL has no double precision depth
+        unsafe { self.gl().clear_depth_f32(depth as f32) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glClear(&self, mask: GLbitfield) {
+        unsafe { self.gl().clear(mask) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glBlendFuncSeparate(
+        &self,
+        src_rgb: GLenum,
+        dst_rgb: GLenum,
+        src_alpha: GLenum,
+        dst_alpha: GLenum,
+    ) {
+        unsafe {
+            self.gl()
+                .blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha)
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glBlendEquationSeparate(&self, mode_rgb: GLenum, mode_alpha: GLenum) {
+        unsafe { self.gl().blend_equation_separate(mode_rgb, mode_alpha) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glDepthFunc(&self, func: GLenum) {
+        unsafe { self.gl().depth_func(func) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glDepthMask(&self, flag: GLboolean) {
+        unsafe { self.gl().depth_mask(flag != 0) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glCullFace(&self, mode: GLenum) {
+        unsafe { self.gl().cull_face(mode) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glGenVertexArrays(&self, n: GLsizei, arrays: *mut GLuint) {
+        for i in 0..n as usize {
+            let name = match unsafe { self.gl().create_vertex_array() } {
+                Ok(va) => self.vertex_arrays.borrow_mut().add(va),
+                Err(e) => {
+                    eprintln!("Failed creating vertex array -> {e}");
+                    0
+                }
+            };
+            unsafe { *arrays.add(i) = name };
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glDeleteVertexArrays(&self, n: GLsizei, arrays: *const GLuint) {
+        for i in 0..n as usize {
+            let name = unsafe { *arrays.add(i) };
+            if let Some(va) = self.vertex_arrays.borrow().get(name) {
+                unsafe { self.gl().delete_vertex_array(va) }
+            }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glBindVertexArray(&self, array: GLuint) {
+        let va = self.vertex_arrays.borrow().get(array);
+        unsafe { self.gl().bind_vertex_array(va) }
+    }
+
+    #[allow(non_snake_case)]
+    pub fn glDrawElementsInstanced(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        ttype: GLenum,
+        indices: *const GLvoid,
+        instancecount: GLsizei,
+    ) {
+        unsafe {
+            self.gl()
+                .draw_elements_instanced(mode, count, ttype, indices as i32, instancecount)
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glCreateShader(&self, shader_type: GLenum) -> GLuint {
+        match unsafe { self.gl().create_shader(shader_type) } {
+            Ok(s) => self.shaders.borrow_mut().add(s),
+            Err(e) => {
+                eprintln!("Failed creating shader -> {e}");
+                0
+            }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glShaderSource(
+        &self,
+        shader: GLuint,
+        count: GLsizei,
+        string: *const *const GLchar,
+        length: *const GLint,
+    ) {
+        let mut source = String::new();
+        for i in 0..count as usize {
+            let s = unsafe { *string.add(i) };
+            let l = if length.is_null() {
+                -1
+            } else {
+                unsafe { *length.add(i) }
+            };
+            if l < 0 {
+                let s = unsafe { CStr::from_ptr(s) };
+                source.push_str(&s.to_string_lossy());
+            } else {
+                let s = unsafe { core::slice::from_raw_parts(s as *const u8, l as usize) };
+                source.push_str(&String::from_utf8_lossy(s));
+            }
+        }
+        if let Some(s) = self.shaders.borrow().get(shader) {
+            unsafe { self.gl().shader_source(s, &source) }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glCompileShader(&self, shader: GLuint) {
+        if let Some(s) = self.shaders.borrow().get(shader) {
+            unsafe { self.gl().compile_shader(s) }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glGetShaderiv(&self, shader: GLuint, pname: GLenum, params: *mut GLint) {
+        let Some(s) = self.shaders.borrow().get(shader) else {
+            return;
+        };
+        let v = match pname {
+            GL_COMPILE_STATUS => (unsafe { self.gl().get_shader_compile_status(s) }) as GLint,
+            GL_INFO_LOG_LENGTH => unsafe { self.gl().get_shader_info_log(s) }.len() as GLint + 1,
+            o => {
+                eprintln!("glGetShaderiv 0x{o:04x} is not supported");
+                return;
+            }
+        };
+        unsafe { *params = v };
+    }
+    #[allow(non_snake_case)]
+    pub fn glGetShaderInfoLog(
+        &self,
+        shader: GLuint,
+        max_length: GLsizei,
+        length: *mut GLsizei,
+        info_log: *mut GLchar,
+    ) {
+        if let Some(s) = self.shaders.borrow().get(shader) {
+            let log = unsafe { self.gl().get_shader_info_log(s) };
+            Self::write_string(&log, max_length, length, info_log);
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glCreateProgram(&self) -> GLuint {
+        match unsafe { self.gl().create_program() } {
+            Ok(p) => self.programs.borrow_mut().add(p),
+            Err(e) => {
+                eprintln!("Failed creating program -> {e}");
+                0
+            }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glAttachShader(&self, program: GLuint, shader: GLuint) {
+        let p = self.programs.borrow().get(program);
+        let s = self.shaders.borrow().get(shader);
+        if let (Some(p), Some(s)) = (p, s) {
+            unsafe { self.gl().attach_shader(p, s) }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glLinkProgram(&self, program: GLuint) {
+        if let Some(p) = self.programs.borrow().get(program) {
+            unsafe { self.gl().link_program(p) }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glGetProgramiv(&self, program: GLuint, pname: GLenum, params: *mut GLint) {
+        let Some(p) = self.programs.borrow().get(program) else {
+            return;
+        };
+        let v = match pname {
+            GL_LINK_STATUS => (unsafe { self.gl().get_program_link_status(p) }) as GLint,
+            GL_INFO_LOG_LENGTH => unsafe { self.gl().get_program_info_log(p) }.len() as GLint + 1,
+            GL_ACTIVE_UNIFORMS => (unsafe { self.gl().get_active_uniforms(p) }) as GLint,
+            o => {
+                eprintln!("glGetProgramiv 0x{o:04x} is not supported");
+                return;
+            }
+        };
+        unsafe { *params = v };
+    }
+    #[allow(non_snake_case)]
+    pub fn glGetProgramInfoLog(
+        &self,
+        program: GLuint,
+        max_length: GLsizei,
+        length: *mut GLsizei,
+        info_log: *mut GLchar,
+    ) {
+        if let Some(p) = self.programs.borrow().get(program) {
+            let log = unsafe { self.gl().get_program_info_log(p) };
+            Self::write_string(&log, max_length, length, info_log);
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glUseProgram(&self, program: GLuint) {
+        let p = self.programs.borrow().get(program);
+        unsafe { self.gl().use_program(p) }
+        self.current_program.set(program);
+    }
+    #[allow(non_snake_case)]
+    pub fn glGetUniformLocation(&self, program: GLuint, name: *const GLchar) -> GLint {
+        let name = unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .to_string();
+        let key = (program, name);
+        if let Some(l) = self.uniform_location_names.borrow().get(&key) {
+            return *l;
+        }
+        let Some(p) = self.programs.borrow().get(program) else {
+            return -1;
+        };
+        let Some(l) = (unsafe { self.gl().get_uniform_location(p, &key.1) }) else {
+            return -1;
+        };
+        let mut uniform_locations = self.uniform_locations.borrow_mut();
+        uniform_locations.push(l);
+        let l = uniform_locations.len() as GLint - 1;
+        self.uniform_location_names.borrow_mut().insert(key, l);
+        l
+    }
+    #[allow(non_snake_case)]
+    pub fn glProgramUniform1f(&self, program: GLuint, location: GLint, v0: GLfloat) {
+        let l = self.uniform_location(location);
+        self.with_program(program, |gl| unsafe { gl.uniform_1_f32(l.as_ref(), v0) });
+    }
+    #[allow(non_snake_case)]
+    pub fn glProgramUniform2fv(
+        &self,
+        program: GLuint,
+        location: GLint,
+        count: GLsizei,
+        value: *const GLfloat,
+    ) {
+        let l = self.uniform_location(location);
+        let v = unsafe { core::slice::from_raw_parts(value, 2 * count as usize) };
+        self.with_program(program, |gl| unsafe {
+            gl.uniform_2_f32_slice(l.as_ref(), v)
+        });
+    }
+    #[allow(non_snake_case)]
+    pub fn glProgramUniform3fv(
+        &self,
+        program: GLuint,
+        location: GLint,
+        count: GLsizei,
+        value: *const GLfloat,
+    ) {
+        let l = self.uniform_location(location);
+        let v = unsafe { core::slice::from_raw_parts(value, 3 * count as usize) };
+        self.with_program(program, |gl| unsafe {
+            gl.uniform_3_f32_slice(l.as_ref(), v)
+        });
+    }
+    #[allow(non_snake_case)]
+    pub fn glProgramUniform4fv(
+        &self,
+        program: GLuint,
+        location: GLint,
+        count: GLsizei,
+        value: *const GLfloat,
+    ) {
+        let l = self.uniform_location(location);
+        let v = unsafe { core::slice::from_raw_parts(value, 4 * count as usize) };
+        self.with_program(program, |gl| unsafe {
+            gl.uniform_4_f32_slice(l.as_ref(), v)
+        });
+    }
+    #[allow(non_snake_case)]
+    pub fn glProgramUniform1i(&self, program: GLuint, location: GLint, v0: GLint) {
+        let l = self.uniform_location(location);
+        self.with_program(program, |gl| unsafe { gl.uniform_1_i32(l.as_ref(), v0) });
+    }
+    #[allow(non_snake_case)]
+    pub fn glProgramUniformMatrix4fv(
+        &self,
+        program: GLuint,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        let l = self.uniform_location(location);
+        let v = unsafe { core::slice::from_raw_parts(value, 16 * count as usize) };
+        self.with_program(program, |gl| unsafe {
+            gl.uniform_matrix_4_f32_slice(l.as_ref(), transpose != 0, v)
+        });
+    }
+    #[allow(non_snake_case)]
+    pub fn glProgramUniform1d(&self, program: GLuint, location: GLint, v0: GLdouble) {
+        self.glProgramUniform1f(program, location, v0 as GLfloat);
+    }
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn glGetActiveUniform(
+        &self,
+        program: GLuint,
+        index: GLuint,
+        buf_size: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        ttype: *mut GLenum,
+        name: *mut GLchar,
+    ) {
+        let Some(p) = self.programs.borrow().get(program) else {
+            return;
+        };
+        if let Some(au) = unsafe { self.gl().get_active_uniform(p, index) } {
+            unsafe {
+                *size = au.size;
+                *ttype = au.utype;
+            }
+            Self::write_string(&au.name, buf_size, length, name);
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glFinish(&self) {
+        unsafe { self.gl().finish() }
+    }
+    #[allow(non_snake_case)]
+    pub fn glGetIntegerv(&self, pname: GLenum, data: *mut GLint) {
+        let v = match pname {
+            GL_CURRENT_PROGRAM => self.current_program.get() as GLint,
+            GL_FRAMEBUFFER_BINDING => self.current_framebuffer.get() as GLint,
+            GL_VIEWPORT | GL_SCISSOR_BOX => {
+                let data = unsafe { core::slice::from_raw_parts_mut(data, 4) };
+                unsafe { self.gl().get_parameter_i32_slice(pname, data) };
+                return;
+            }
+            pname => unsafe { self.gl().get_parameter_i32(pname) },
+        };
+        unsafe { *data = v };
+    }
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn glReadPixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        ttype: GLenum,
+        data: *mut GLvoid,
+    ) {
+        if format != GL_RGBA || ttype != GL_UNSIGNED_BYTE {
+            eprintln!("glReadPixels only supports RGBA/UNSIGNED_BYTE");
+            return;
+        }
+        let len = 4 * width as usize * height as usize;
+        let pixels = unsafe { core::slice::from_raw_parts_mut(data as *mut u8, len) };
+        unsafe {
+            self.gl().read_pixels(
+                x,
+                y,
+                width,
+                height,
+                format,
+                ttype,
+                glow::PixelPackData::Slice(pixels),
+            )
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub fn glGenFramebuffers(&self, n: GLsizei, framebuffers: *mut GLuint) {
+        for i in 0..n as usize {
+            let name = match unsafe { self.gl().create_framebuffer() } {
+                Ok(fb) => self.framebuffers.borrow_mut().add(fb),
+                Err(e) => {
+                    eprintln!("Failed creating framebuffer -> {e}");
+                    0
+                }
+            };
+            unsafe { *framebuffers.add(i) = name };
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glDeleteFramebuffers(&self, n: GLsizei, framebuffers: *const GLuint) {
+        for i in 0..n as usize {
+            let name = unsafe { *framebuffers.add(i) };
+            if let Some(fb) = self.framebuffers.borrow().get(name) {
+                unsafe { self.gl().delete_framebuffer(fb) }
+            }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glBindFramebuffer(&self, target: GLenum, framebuffer: GLuint) {
+        let fb = self.framebuffers.borrow().get(framebuffer);
+        unsafe { self.gl().bind_framebuffer(target, fb) }
+        // `GL_FRAMEBUFFER_BINDING` is the draw framebuffer
+        if target != GL_READ_FRAMEBUFFER {
+            self.current_framebuffer.set(framebuffer);
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glFramebufferTexture2D(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    ) {
+        let t = self.textures.borrow().get(texture);
+        unsafe {
+            self.gl()
+                .framebuffer_texture_2d(target, attachment, textarget, t, level)
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glCheckFramebufferStatus(&self, target: GLenum) -> GLenum {
+        unsafe { self.gl().check_framebuffer_status(target) }
+    }
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn glBlitFramebuffer(
+        &self,
+        src_x0: GLint,
+        src_y0: GLint,
+        src_x1: GLint,
+        src_y1: GLint,
+        dst_x0: GLint,
+        dst_y0: GLint,
+        dst_x1: GLint,
+        dst_y1: GLint,
+        mask: GLbitfield,
+        filter: GLenum,
+    ) {
+        unsafe {
+            self.gl().blit_framebuffer(
+                src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1, mask, filter,
+            )
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glGenTextures(&self, n: GLsizei, textures: *mut GLuint) {
+        for i in 0..n as usize {
+            let name = match unsafe { self.gl().create_texture() } {
+                Ok(t) => self.textures.borrow_mut().add(t),
+                Err(e) => {
+                    eprintln!("Failed creating texture -> {e}");
+                    0
+                }
+            };
+            unsafe { *textures.add(i) = name };
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glDeleteTextures(&self, n: GLsizei, textures: *const GLuint) {
+        for i in 0..n as usize {
+            let name = unsafe { *textures.add(i) };
+            if let Some(t) = self.textures.borrow().get(name) {
+                unsafe { self.gl().delete_texture(t) }
+            }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glBindTexture(&self, target: GLenum, texture: GLuint) {
+        let t = self.textures.borrow().get(texture);
+        unsafe { self.gl().bind_texture(target, t) }
+    }
+    #[allow(non_snake_case)]
+    pub fn glActiveTexture(&self, texture: GLenum) {
+        unsafe { self.gl().active_texture(texture) }
+    }
+    /// Only supports 8 bit, and half float data.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn glTexImage2D(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        ttype: GLenum,
+        data: *const GLvoid,
+    ) {
+        let pixels = if data.is_null() {
+            None
+        } else {
+            let channels = match format {
+                GL_RED => 1,
+                _ => 4,
+            };
+            let bytes = match ttype {
+                GL_UNSIGNED_BYTE => 1,
+                GL_HALF_FLOAT => 2,
+                o => {
+                    eprintln!("glTexImage2D 0x{o:04x} data is not supported");
+                    return;
+                }
+            };
+            let len = channels * bytes * width as usize * height as usize;
+            Some(unsafe { core::slice::from_raw_parts(data as *const u8, len) })
+        };
+        unsafe {
+            self.gl().tex_image_2d(
+                target,
+                level,
+                internalformat,
+                width,
+                height,
+                border,
+                format,
+                ttype,
+                pixels,
+            )
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glTexParameteri(&self, target: GLenum, pname: GLenum, param: GLint) {
+        unsafe { self.gl().tex_parameter_i32(target, pname, param) }
+    }
+
+    pub fn gen_buffers(&self, n: c_int, buffers: *mut c_uint) {
+        for i in 0..n as usize {
+            let name = match unsafe { self.gl().create_buffer() } {
+                Ok(b) => self.buffers.borrow_mut().add(b),
+                Err(e) => {
+                    eprintln!("Failed creating buffer -> {e}");
+                    0
+                }
+            };
+            unsafe { *buffers.add(i) = name };
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub fn glDeleteBuffers(&self, n: GLsizei, buffers: *const GLuint) {
+        for i in 0..n as usize {
+            let name = unsafe { *buffers.add(i) };
+            if let Some(b) = self.buffers.borrow().get(name) {
+                unsafe { self.gl().delete_buffer(b) }
+            }
+        }
+    }
+    #[allow(non_snake_case)]
+    pub fn glBindBufferBase(&self, target: GLenum, index: GLuint, buffer: GLuint) {
+        let b = self.buffers.borrow().get(buffer);
+        unsafe { self.gl().bind_buffer_base(target, index, b) }
+    }
+    // never called, see `supports_compute`
+    #[allow(non_snake_case)]
+    pub fn glDispatchCompute(
+        &self,
+        _num_groups_x: GLuint,
+        _num_groups_y: GLuint,
+        _num_groups_z: GLuint,
+    ) {
+    }
+    #[allow(non_snake_case)]
+    pub fn glMemoryBarrier(&self, _barriers: GLbitfield) {}
+
+    pub fn bind_buffer(&self, target: c_uint, buffer: c_uint) {
+        let b = self.buffers.borrow().get(buffer);
+        unsafe { self.gl().bind_buffer(target, b) }
+    }
+
+    pub fn buffer_data(&self, target: c_uint, size: isize, data: *const c_void, usage: c_uint) {
+        let data = unsafe { core::slice::from_raw_parts(data as *const u8, size as usize) };
+        unsafe { self.gl().buffer_data_u8_slice(target, data, usage) }
+    }
+
+    pub fn enable_vertex_attrib_array(&self, index: c_uint) {
+        unsafe { self.gl().enable_vertex_attrib_array(index) }
+    }
+
+    pub fn vertex_attrib_pointer(
+        &self,
+        index: c_uint,
+        size: c_int,
+        ttype: c_uint,
+        normalized: c_uchar,
+        stride: c_int,
+        pointer: *const c_void,
+    ) {
+        unsafe {
+            self.gl().vertex_attrib_pointer_f32(
+                index,
+                size,
+                ttype,
+                normalized != 0,
+                stride,
+                pointer as i32,
+            )
+        }
+    }
+
+    pub fn draw_arrays(&self, mode: c_uint, first: c_int, count: c_int) {
+        unsafe { self.gl().draw_arrays(mode, first, count) }
+    }
+}
@@ -65,6 +65,109 @@ macro_rules! create_gl_wrapper {
     			}
     		}
     	};
+    	($return_type:ident $name:ident( $t0:ident $p0:ident, $t1:ident $p1:ident, $t2:ident $p2:ident, $t3:ident $p3:ident )) => {
+    		#[allow(non_snake_case)]
+    		#[allow(dead_code)]
+    		pub /*unsafe*/ fn $name(&self, $p0: $t0, $p1: $t1, $p2: $t2, $p3: $t3) -> $return_type {
+    			unsafe {
+    				core::mem::transmute::<*const core::ffi::c_void, extern "system" fn( $t0, $t1, $t2, $t3 ) -> $return_type>(self.glfps.$name.f)( $p0, $p1, $p2, $p3 )
+    			}
+    		}
+    	};
+    	($return_type:ident $name:ident( $t0:ident $p0:ident, $t1:ident $p1:ident, $t2:ident $p2:ident, $t3:ident $p3:ident, $t4:ident $p4:ident )) => {
+    		#[allow(non_snake_case)]
+    		#[allow(dead_code)]
+    		pub /*unsafe*/ fn $name(&self, $p0: $t0, $p1: $t1, $p2: $t2, $p3: $t3, $p4: $t4) -> $return_type {
+    			unsafe {
+    				core::mem::transmute::<*const core::ffi::c_void, extern "system" fn( $t0, $t1, $t2, $t3, $t4 ) -> $return_type>(self.glfps.$name.f)( $p0, $p1, $p2, $p3, $p4 )
+    			}
+    		}
+    	};
+    	($return_type:ident $name:ident(
+    		$t0:ident $p0:ident,
+    		$t1:ident $p1:ident,
+    		$t2:ident $p2:ident,
+    		$t3:ident $p3:ident,
+    		$t4:ident $p4:ident,
+    		$t5:ident $p5:ident,
+    		$t6:ident $p6:ident,
+    		$t7:ident $p7:ident,
+    		$t8:ident $p8:ident,
+    		$t9:ident $p9:ident
+    	)) => {
+    		#[allow(non_snake_case)]
+    		#[allow(dead_code)]
+    		#[allow(clippy::too_many_arguments)]
+    		pub /*unsafe*/ fn $name(
+    			&self,
+    			$p0: $t0,
+    			$p1: $t1,
+    			$p2: $t2,
+    			$p3: $t3,
+    			$p4: $t4,
+    			$p5: $t5,
+    			$p6: $t6,
+    			$p7: $t7,
+    			$p8: $t8,
+    			$p9: $t9,
+    			) -> $return_type {
+    			unsafe {
+    				core::mem::transmute::<*const core::ffi::c_void, extern "system" fn(
+    					$t0,
+    					$t1,
+    					$t2,
+    					$t3,
+    					$t4,
+    					$t5,
+    					$t6,
+    					$t7,
+    					$t8,
+    					$t9,
+    					) -> $return_type>(self.glfps.$name.f)( $p0, $p1, $p2, $p3, $p4, $p5, $p6, $p7, $p8, $p9 )
+    			}
+    		}
+    	};
+    	($return_type:ident $name:ident(
+    		$t0:ident $p0:ident,
+    		$t1:ident $p1:ident,
+    		$t2:ident $p2:ident,
+    		$t3:ident $p3:ident,
+    		$t4:ident $p4:ident,
+    		$t5:ident $p5:ident,
+    		$t6:ident $p6:ident,
+    		$t7:ident $p7:ident,
+    		const $t8:ident *$p8:ident
+    	)) => {
+    		#[allow(non_snake_case)]
+    		#[allow(dead_code)]
+    		#[allow(clippy::too_many_arguments)]
+    		pub /*unsafe*/ fn $name(
+    			&self,
+    			$p0: $t0,
+    			$p1: $t1,
+    			$p2: $t2,
+    			$p3: $t3,
+    			$p4: $t4,
+    			$p5: $t5,
+    			$p6: $t6,
+    			$p7: $t7,
+    			$p8: *const $t8,
+    			) -> $return_type {
+    			unsafe {
+    				core::mem::transmute::<*const core::ffi::c_void, extern "system" fn(
+    					$t0,
+    					$t1,
+    					$t2,
+    					$t3,
+    					$t4,
+    					$t5,
+    					$t6,
+    					$t7,
+    					*const $t8,
+    					) -> $return_type>(self.glfps.$name.f)( $p0, $p1, $p2, $p3, $p4, $p5, $p6, $p7, $p8 )
+    			}
+    		}
+    	};
         ($return_type:ident $name:ident(
             $t0:ident $p0:ident,
             $t1:ident $p1:ident,
@@ -90,6 +193,62 @@ macro_rules! create_gl_wrapper {
                 }
             }
         };
+        ($return_type:ident $name:ident(
+            $t0:ident $p0:ident,
+            $t1:ident $p1:ident,
+            $t2:ident $p2:ident,
+            $t3:ident $p3:ident,
+            const $t4:ident *$p4:ident
+        )) => {
+            #[allow(non_snake_case)]
+            #[allow(dead_code)]
+            pub /*unsafe*/ fn $name(
+                &self,
+                $p0: $t0,
+                $p1: $t1,
+                $p2: $t2,
+                $p3: $t3,
+                $p4: *const $t4,
+                ) -> $return_type {
+                unsafe {
+                    core::mem::transmute::<*const core::ffi::c_void, extern "system" fn(
+                        $t0,
+                        $t1,
+                        $t2,
+                        $t3,
+                        *const $t4,
+                        ) -> $return_type>(self.glfps.$name.f)( $p0, $p1, $p2, $p3, $p4 )
+                }
+            }
+        };
+        ($return_type:ident $name:ident(
+            $t0:ident $p0:ident,
+            $t1:ident $p1:ident,
+            $t2:ident $p2:ident,
+            const $t3:ident *$p3:ident,
+            $t4:ident $p4:ident
+        )) => {
+            #[allow(non_snake_case)]
+            #[allow(dead_code)]
+            pub /*unsafe*/ fn $name(
+                &self,
+                $p0: $t0,
+                $p1: $t1,
+                $p2: $t2,
+                $p3: *const $t3,
+                $p4: $t4,
+                ) -> $return_type {
+                unsafe {
+                    core::mem::transmute::<*const core::ffi::c_void, extern "system" fn(
+                        $t0,
+                        $t1,
+                        $t2,
+                        *const $t3,
+                        $t4,
+                        ) -> $return_type>(self.glfps.$name.f)( $p0, $p1, $p2, $p3, $p4 )
+                }
+            }
+        };
     	($return_type:ident $name:ident(
     		$t0:ident $p0:ident,
     		$t1:ident $p1:ident,
@@ -124,6 +283,41 @@ macro_rules! create_gl_wrapper {
     			}
     		}
     	};
+    	($return_type:ident $name:ident(
+    		$t0:ident $p0:ident,
+    		$t1:ident $p1:ident,
+    		$t2:ident $p2:ident,
+    		$t3:ident $p3:ident,
+    		$t4:ident $p4:ident,
+    		$t5:ident $p5:ident,
+    		$t6:ident *$p6:ident
+    	)) => {
+    		#[allow(non_snake_case)]
+    		#[allow(dead_code)]
+    		#[allow(clippy::too_many_arguments)]
+    		pub /*unsafe*/ fn $name(
+    			&self,
+    			$p0: $t0,
+    			$p1: $t1,
+    			$p2: $t2,
+    			$p3: $t3,
+    			$p4: $t4,
+    			$p5: $t5,
+    			$p6: *mut $t6,
+    			) -> $return_type {
+    			unsafe {
+    				core::mem::transmute::<*const core::ffi::c_void, extern "system" fn(
+    					$t0,
+    					$t1,
+    					$t2,
+    					$t3,
+    					$t4,
+    					$t5,
+    					*mut $t6,
+    					) -> $return_type>(self.glfps.$name.f)( $p0, $p1, $p2, $p3, $p4, $p5, $p6 )
+    			}
+    		}
+    	};
     	($return_type:ident $name:ident( $t0:ident $p0:ident, const $t1:ident *$p1:ident )) => {
     		#[allow(non_snake_case)]
     		#[allow(dead_code)]
@@ -0,0 +1,366 @@
+use crate::engine::gl::*;
+
+/// The uniforms Shadertoy offers, set from the built-in uniforms.
+const SHADERTOY_PRELUDE: &str = r#"#define SFRS_SHADERTOY 1
+uniform vec3 iResolution;
+uniform float iTime;
+uniform float iTimeDelta;
+uniform int iFrame;
+uniform float iFrameRate;
+uniform vec4 iMouse;
+uniform vec4 iDate;
+uniform sampler2D iChannel0;
+uniform sampler2D iChannel1;
+uniform sampler2D iChannel2;
+uniform sampler2D iChannel3;
+uniform vec3 iChannelResolution[4];
+uniform vec2 vViewportOrigin;
+"#;
+
+const SHADERTOY_MAIN: &str = r#"out vec4 sfrs_FragColor;
+void main() {
+    vec4 c = vec4(0.0);
+    mainImage(c, gl_FragCoord.xy - vViewportOrigin);
+    sfrs_FragColor = vec4(c.rgb, 1.0);
+}
+"#;
+
+/// The GLSL dialect the shaders are translated to before compiling.
+///
+/// Projects are written against desktop GLSL 4.10,
+/// for GLES/WebGL2 the sources are rewritten to GLSL ES 3.00.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GlslProfile {
+    #[default]
+    Desktop410,
+    Es300,
+}
+
+impl GlslProfile {
+    /// Picks the profile based on the string returned by `glGetString(GL_VERSION)`.
+    pub fn from_gl_version(version: &str) -> Self {
+        if version.contains("OpenGL ES") || version.contains("WebGL") {
+            Self::Es300
+        } else {
+            Self::Desktop410
+        }
+    }
+
+    pub fn version_line(&self) -> &'static str {
+        match self {
+            Self::Desktop410 => "#version 410",
+            Self::Es300 => "#version 300 es",
+        }
+    }
+
+    pub fn supports_double(&self) -> bool {
+        match self {
+            Self::Desktop410 => true,
+            Self::Es300 => false,
+        }
+    }
+
+    /// Shadertoy fragment shaders define `mainImage` instead of `main`.
+    pub fn is_shadertoy(source: &str, shader_type: GLenum) -> bool {
+        let has_main = source.match_indices("main").any(|(i, _)| {
+            let before = source[..i].chars().next_back();
+            let after = source[i + 4..].trim_start();
+            !before.is_some_and(|c| c.is_alphanumeric() || c == '_') && after.starts_with('(')
+        });
+        shader_type == GL_FRAGMENT_SHADER && source.contains("mainImage") && !has_main
+    }
+
+    /// Rewrites `source` so it compiles with this profile.
+    ///
+    /// Line numbers are kept via `#line`, so compile logs still match the original source.
+    /// Shadertoy shaders get their uniforms declared, and a `main` calling `mainImage`.
+    pub fn translate(&self, source: &str, shader_type: GLenum) -> String {
+        self.translate_with_prelude(source, "", shader_type)
+    }
+
+    /// Like [`GlslProfile::translate`], with `prelude` inserted before the source, starting at line 1.
+    pub fn translate_with_prelude(
+        &self,
+        source: &str,
+        prelude: &str,
+        shader_type: GLenum,
+    ) -> String {
+        let mut lines = source.lines().enumerate().peekable();
+
+        // skip leading empty lines and comments until we find the `#version`
+        let mut version = None;
+        while let Some((_, l)) = lines.peek() {
+            let t = l.trim();
+            if t.starts_with("#version") {
+                version = lines.next().map(|(i, l)| (i, l.trim().to_string()));
+                break;
+            } else if t.is_empty() || t.starts_with("//") {
+                lines.next();
+            } else {
+                break;
+            }
+        }
+
+        let mut out = String::with_capacity(prelude.len() + source.len() + 128);
+        let (version_line, first_line) = match (self, version) {
+            (Self::Desktop410, Some((i, v))) => (v, i + 2),
+            (_, Some((i, _))) => (self.version_line().to_string(), i + 2),
+            (_, None) => {
+                // no `#version`, the skipped lines are part of the source
+                lines = source.lines().enumerate().peekable();
+                let version_line = match (self, shader_type) {
+                    (Self::Desktop410, GL_COMPUTE_SHADER) => "#version 430",
+                    _ => self.version_line(),
+                };
+                (version_line.to_string(), 1)
+            }
+        };
+        out.push_str(&version_line);
+        out.push('\n');
+
+        // `#extension` has to come before any code, so move the source's up, before the inserted lines
+        let mut first_line = first_line;
+        let mut ahead = lines.clone();
+        while let Some((i, l)) = ahead.next() {
+            let t = l.trim();
+            if t.starts_with("#extension") {
+                out.push_str(t);
+                out.push('\n');
+                lines = ahead.clone();
+                first_line = i + 2;
+            } else if !t.is_empty() && !t.starts_with("//") {
+                break;
+            }
+        }
+
+        match self {
+            Self::Desktop410 => {
+                out.push_str("#define SFRS_GLSL_DESKTOP 1\n");
+            }
+            Self::Es300 => {
+                out.push_str("#define SFRS_GLSL_ES 1\n");
+                out.push_str("precision highp float;\n");
+                out.push_str("precision highp int;\n");
+            }
+        }
+        let shadertoy = Self::is_shadertoy(source, shader_type);
+        if shadertoy {
+            out.push_str(SHADERTOY_PRELUDE);
+        }
+        let push_line = |out: &mut String, l: &str| {
+            let l = if *self == Self::Es300 {
+                Self::strip_varying_location(l, shader_type)
+            } else {
+                l.to_string()
+            };
+            let l = if !self.supports_double() {
+                Self::replace_doubles(&l)
+            } else {
+                l
+            };
+            out.push_str(&l);
+            out.push('\n');
+        };
+        if !prelude.is_empty() {
+            out.push_str("#line 1\n");
+            for l in prelude.lines() {
+                push_line(&mut out, l);
+            }
+        }
+        out.push_str(&format!("#line {first_line}\n"));
+
+        for (_, l) in lines {
+            push_line(&mut out, l);
+        }
+        if shadertoy {
+            out.push_str(SHADERTOY_MAIN);
+        }
+
+        out
+    }
+
+    /// GLSL ES 3.00 only allows `layout(location=N)` on vertex inputs and fragment outputs.
+    fn strip_varying_location(line: &str, shader_type: GLenum) -> String {
+        let varying = match shader_type {
+            GL_VERTEX_SHADER => "out",
+            GL_FRAGMENT_SHADER => "in",
+            _ => return line.to_string(),
+        };
+
+        let t = line.trim_start();
+        let Some(rest) = t.strip_prefix("layout") else {
+            return line.to_string();
+        };
+        let rest = rest.trim_start();
+        let Some(rest) = rest.strip_prefix('(') else {
+            return line.to_string();
+        };
+        let Some((qualifiers, rest)) = rest.split_once(')') else {
+            return line.to_string();
+        };
+        let location_only = qualifiers
+            .split(',')
+            .all(|q| q.trim().starts_with("location"));
+        let rest = rest.trim_start();
+        let is_varying = rest
+            .strip_prefix(varying)
+            .is_some_and(|r| r.starts_with(|c: char| c.is_whitespace()));
+
+        if location_only && is_varying {
+            let indent = &line[..line.len() - t.len()];
+            format!("{indent}{rest}")
+        } else {
+            line.to_string()
+        }
+    }
+
+    fn replace_doubles(line: &str) -> String {
+        let mut out = String::with_capacity(line.len());
+        let mut word = String::new();
+        let flush = |word: &mut String, out: &mut String| {
+            let w = match word.as_str() {
+                "double" => "float",
+                "dvec2" => "vec2",
+                "dvec3" => "vec3",
+                "dvec4" => "vec4",
+                "dmat2" => "mat2",
+                "dmat3" => "mat3",
+                "dmat4" => "mat4",
+                w => w,
+            };
+            out.push_str(w);
+            word.clear();
+        };
+        for c in line.chars() {
+            if c.is_alphanumeric() || c == '_' {
+                word.push(c);
+            } else {
+                flush(&mut word, &mut out);
+                out.push(c);
+            }
+        }
+        flush(&mut word, &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERTEX: &str = "#version 410\n\nlayout(location=0)in vec2 v;\nlayout(location=0)out vec2 p;\nvoid main() {\n    p = v;\n}\n";
+    const FRAGMENT: &str = "#version 410\nuniform double fTime;\nout vec4 out_color;\nlayout(location = 0) in vec2 p;\nvoid main() {\n    out_color = vec4(p, float(fTime), 1.0);\n}\n";
+
+    #[test]
+    fn from_gl_version_works() {
+        assert_eq!(
+            GlslProfile::Es300,
+            GlslProfile::from_gl_version("OpenGL ES 3.0 (WebGL 2.0)")
+        );
+        assert_eq!(
+            GlslProfile::Desktop410,
+            GlslProfile::from_gl_version("4.1 Metal - 88")
+        );
+    }
+
+    #[test]
+    fn translate_desktop_keeps_source() {
+        let t = GlslProfile::Desktop410.translate(VERTEX, GL_VERTEX_SHADER);
+        assert!(t.starts_with("#version 410\n"));
+        assert!(t.contains("#line 2\n"));
+        assert!(t.contains("layout(location=0)out vec2 p;"));
+
+        let t = GlslProfile::Desktop410.translate(FRAGMENT, GL_FRAGMENT_SHADER);
+        assert!(t.contains("uniform double fTime;"));
+    }
+
+    #[test]
+    fn translate_es_works() {
+        let t = GlslProfile::Es300.translate(VERTEX, GL_VERTEX_SHADER);
+        assert!(t.starts_with("#version 300 es\n"));
+        assert!(t.contains("precision highp float;\n"));
+        assert!(t.contains("layout(location=0)in vec2 v;"));
+        assert!(t.contains("\nout vec2 p;"));
+        assert!(!t.contains("#version 410"));
+
+        let t = GlslProfile::Es300.translate(FRAGMENT, GL_FRAGMENT_SHADER);
+        assert!(t.contains("\nin vec2 p;"));
+        assert!(t.contains("uniform float fTime;"));
+        assert!(t.contains("float(fTime)"));
+    }
+
+    #[test]
+    fn translate_keeps_line_numbers() {
+        let t = GlslProfile::Desktop410.translate(VERTEX, GL_VERTEX_SHADER);
+        let (_, body) = t.split_once("#line 2\n").unwrap();
+        assert_eq!(
+            VERTEX.lines().skip(1).collect::<Vec<_>>(),
+            body.lines().collect::<Vec<_>>()
+        );
+
+        let t = GlslProfile::Es300.translate(VERTEX, GL_VERTEX_SHADER);
+        let (_, body) = t.split_once("#line 2\n").unwrap();
+        assert_eq!(VERTEX.lines().count() - 1, body.lines().count());
+
+        // without a version line everything is kept
+        let src = "// comment\nvoid main() {}\n";
+        let t = GlslProfile::Es300.translate(src, GL_FRAGMENT_SHADER);
+        let (_, body) = t.split_once("#line 1\n").unwrap();
+        assert_eq!(src, body);
+    }
+
+    #[test]
+    fn translate_shadertoy_works() {
+        let src = "void mainImage(out vec4 fragColor, in vec2 fragCoord) {\n    fragColor = vec4(fragCoord / iResolution.xy, 0.5 + 0.5 * sin(iTime), 1.0);\n}\n";
+        assert!(GlslProfile::is_shadertoy(src, GL_FRAGMENT_SHADER));
+        assert!(!GlslProfile::is_shadertoy(src, GL_VERTEX_SHADER));
+        assert!(!GlslProfile::is_shadertoy(FRAGMENT, GL_FRAGMENT_SHADER));
+
+        let t = GlslProfile::Es300.translate(src, GL_FRAGMENT_SHADER);
+        assert!(t.starts_with("#version 300 es\n"));
+        assert!(t.contains("uniform vec3 iResolution;"));
+        let (_, body) = t.split_once("#line 1\n").unwrap();
+        assert!(body.starts_with(src));
+        assert!(body.contains("mainImage(c, gl_FragCoord.xy - vViewportOrigin);"));
+
+        let t =
+            GlslProfile::Es300.translate_with_prelude(src, "double common;", GL_FRAGMENT_SHADER);
+        let (prelude, body) = t.split_once("#line 1\nfloat common;\n#line 1\n").unwrap();
+        assert!(prelude.contains("uniform vec3 iResolution;"));
+        assert!(body.starts_with(src));
+    }
+
+    #[test]
+    fn translate_keeps_extensions_first() {
+        let src =
+            "#version 300 es\n\n#extension GL_OES_standard_derivatives : enable\nvoid main() {}\n";
+        let t = GlslProfile::Es300.translate(src, GL_FRAGMENT_SHADER);
+        let extension = t.find("#extension").unwrap();
+        assert!(extension < t.find("precision").unwrap());
+        assert!(t.contains("#line 4\nvoid main() {}\n"));
+        assert_eq!(1, t.matches("#extension").count());
+
+        let src = "#extension GL_OES_standard_derivatives : enable\nvoid mainImage(out vec4 c, in vec2 p) {}\n";
+        let t = GlslProfile::Es300.translate(src, GL_FRAGMENT_SHADER);
+        assert!(t.find("#extension").unwrap() < t.find("uniform vec3 iResolution;").unwrap());
+        assert!(t.contains("#line 2\nvoid mainImage"));
+    }
+
+    #[test]
+    fn translate_compute_needs_430() {
+        let src = "layout(local_size_x = 64) in;\nvoid main() {}\n";
+        let t = GlslProfile::Desktop410.translate(src, GL_COMPUTE_SHADER);
+        assert!(t.starts_with("#version 430\n"));
+        let t =
+            GlslProfile::Desktop410.translate(&format!("#version 450\n{src}"), GL_COMPUTE_SHADER);
+        assert!(t.starts_with("#version 450\n"));
+    }
+
+    #[test]
+    fn replace_doubles_only_replaces_whole_words() {
+        assert_eq!(
+            "vec3 a = vec3(my_double, dvec3x, 1.0);",
+            GlslProfile::replace_doubles("dvec3 a = dvec3(my_double, dvec3x, 1.0);")
+        );
+    }
+}
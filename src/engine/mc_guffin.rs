@@ -1,33 +1,56 @@
 use super::gl::*;
+use crate::engine::AudioPlayer;
+use crate::engine::Clock;
+use crate::engine::FlowTime;
 use crate::engine::FlowVm;
+use crate::engine::LinkedProgram;
+use crate::engine::OutputTransform;
+use crate::engine::PreviewInput;
+use crate::engine::RenderState;
 use crate::engine::ResourceLogManager;
+use crate::project::ColorOutput;
 use crate::project::Project;
-use crate::project::PropertyValue;
+use crate::project::Resource;
 use crate::project::ResourceId;
 use color_eyre::Result;
+#[cfg(not(target_arch = "wasm32"))]
 use core::ffi::c_void;
+#[cfg(not(target_arch = "wasm32"))]
 use core::ffi::CStr;
 use std::borrow::Cow;
-use std::collections::HashMap;
 
 #[derive(Debug, Default)]
 pub struct McGuffin {
     gl: Gl,
 
     //pipeline: Pipeline,
-    properties_f32: HashMap<String, f32>,
-    properties_f64: HashMap<String, f64>,
-    properties_vec2_f32: HashMap<String, [f32; 2]>,
-    properties_vec3_f32: HashMap<String, [f32; 3]>,
-    properties_vec3_f32_size4: HashMap<String, [f32; 3 * 4]>,
     // shader_sources: HashMap<String, ShaderSource>,
     project: Project,
     project_version: u32,
+    /// Versions start over when another project is loaded.
+    project_load_generation: u32,
+    /// Sync track edits don't change the project version.
+    sync_tracks_version: u32,
 
     last_paint_duration: std::time::Duration,
     flow_vm: FlowVm,
+    audio_player: AudioPlayer,
 
     resource_log_manager: ResourceLogManager,
+
+    viewport: [i32; 4],
+    color_output: ColorOutput,
+    output_transform: OutputTransform,
+    capture_requested: bool,
+    captured_frame: Option<CapturedFrame>,
+}
+
+#[derive(Debug, Default)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA, top row first
+    pub pixels: Vec<u8>,
 }
 
 // :TODO: remove
@@ -50,6 +73,10 @@ impl McGuffin {
         &self.pipeline.uniform_manager()
     }
     */
+    /// All programs linked since the last call.
+    pub fn take_linked_programs(&mut self) -> Vec<LinkedProgram> {
+        self.resource_log_manager.take_linked_programs()
+    }
     pub fn get_resource_log(&self, resource_id: &ResourceId) -> Cow<'_, Vec<String>> {
         /*
         for (_n, ss) in self.shader_sources.iter() {
@@ -65,12 +92,19 @@ impl McGuffin {
         let not_found = vec![format!("Resource '{resource_id}' not found")];
         Cow::Owned(not_found)
     }
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn setup(&mut self, get_proc_address: &dyn Fn(&CStr) -> *const c_void) -> Result<()> {
         // load the gl functions we need
         // glRects
 
         self.gl.load_all(get_proc_address)?;
 
+        #[cfg(feature = "audio")]
+        match crate::engine::CpalAudioSink::new() {
+            Ok(sink) => self.audio_player.set_sink(Box::new(sink)),
+            Err(e) => eprintln!("No audio output, continuing without -> {e:?}"),
+        }
+
         // self.flow_vm.run_setup(&self.gl, &self.project)?;
 
         // create the program (vertex + fragment)
@@ -78,6 +112,11 @@ impl McGuffin {
 
         Ok(())
     }
+    /// Sets up the engine on top of the `glow::Context` egui uses, e.g. on web where there is no `get_proc_address`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn setup_glow(&mut self, gl: std::sync::Arc<eframe::glow::Context>) -> Result<()> {
+        self.gl.load_glow(gl)
+    }
     pub fn update(&mut self) -> Result<()> {
         /*
         self.pipeline.bind(&mut self.gl)?;
@@ -100,46 +139,117 @@ impl McGuffin {
         }
         self.gl.check_gl_error(std::file!(), std::line!());
         */
-        self.flow_vm.run_update(&self.gl)?;
+        let time = self.flow_vm.advance_time();
+        self.audio_player.sync(self.flow_vm.clock());
+        self.flow_vm
+            .set_audio_values(self.audio_player.values(time));
+        let offscreen = match self.color_output {
+            ColorOutput::Linear { .. } => self.output_transform.begin(&self.gl),
+            ColorOutput::Direct => {
+                self.output_transform.release(&self.gl);
+                false
+            }
+        };
+        let mut viewport = [0; 4];
+        self.gl.glGetIntegerv(GL_VIEWPORT, viewport.as_mut_ptr());
+        self.flow_vm.set_viewport(viewport);
+        let render_state = RenderState::save(&self.gl);
+        let result = self.flow_vm.run_update(&self.gl);
+        render_state.restore(&self.gl);
+        if offscreen {
+            self.output_transform.end(&self.gl, &self.color_output);
+        }
+        result?;
         self.gl.check_gl_error(std::file!(), std::line!());
         Ok(())
     }
 
+    /// Sets the area of the framebuffer we paint into, as `[x, y, width, height]` in pixels, from the bottom left.
+    pub fn set_viewport(&mut self, viewport: [i32; 4]) {
+        self.viewport = viewport;
+    }
+    /// Sets the pointer and keyboard state for the built-in uniforms.
+    pub fn set_input(&mut self, input: &PreviewInput) {
+        self.flow_vm.set_input(input);
+    }
+    pub fn request_capture(&mut self) {
+        self.capture_requested = true;
+    }
+    pub fn take_captured_frame(&mut self) -> Option<CapturedFrame> {
+        self.captured_frame.take()
+    }
+    fn capture(&mut self) {
+        let [x, y, w, h] = self.viewport;
+        if w <= 0 || h <= 0 {
+            return;
+        }
+        let row_len = 4 * w as usize;
+        let mut pixels = vec![0u8; row_len * h as usize];
+        self.gl.glReadPixels(
+            x,
+            y,
+            w,
+            h,
+            GL_RGBA,
+            GL_UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut _,
+        );
+        self.gl.check_gl_error(std::file!(), std::line!());
+
+        // GL starts at the bottom
+        let pixels = pixels
+            .chunks_exact(row_len)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        self.captured_frame = Some(CapturedFrame {
+            width: w as u32,
+            height: h as u32,
+            pixels,
+        });
+    }
+
     pub fn paint(&mut self, _gl: &eframe::glow::Context) {
-        let paint_start = std::time::Instant::now();
+        let paint_start = web_time::Instant::now();
         let _ = self.update();
+        if self.capture_requested {
+            self.capture_requested = false;
+            self.capture();
+        }
         self.gl.glFinish();
-        let paint_end = std::time::Instant::now();
+        let paint_end = web_time::Instant::now();
         let paint_duration = paint_end - paint_start;
         // eprintln!("{paint_duration:?}");
         self.last_paint_duration = paint_duration;
     }
 
-    fn set_property_f32(&mut self, name: &str, value: f32) {
-        self.properties_f32.insert(name.into(), value);
-    }
-    fn set_property_f64(&mut self, name: &str, value: f64) {
-        self.properties_f64.insert(name.into(), value);
-    }
-    fn set_property_vec2_f32(&mut self, name: &str, values: &[f32; 2]) {
-        self.properties_vec2_f32.insert(name.into(), *values);
-    }
-    fn set_property_vec3_f32(&mut self, name: &str, values: &[f32; 3]) {
-        self.properties_vec3_f32.insert(name.into(), *values);
-    }
-    fn set_property_vec3_f32_size4(&mut self, name: &str, values: &[f32; 3 * 4]) {
-        self.properties_vec3_f32_size4.insert(name.into(), *values);
-    }
-
     pub fn update_from_project(&mut self, project: &Project) {
-        if self.project_version != project.version() {
+        if self.project_version != project.version()
+            || self.project_load_generation != project.load_generation()
+        {
             eprintln!("Project changed {}", project.version());
             self.project = (*project).clone();
+            self.flow_vm
+                .set_time_wrap_period(project.time_wrap_period());
+            let (clip, tempo) = project
+                .soundtrack()
+                .and_then(|id| match project.resource_manager.get(id) {
+                    Some(Resource::Audio(ra)) => Some((ra.clip().cloned(), ra.tempo())),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            self.audio_player.set_clip(clip);
+            self.audio_player.set_tempo(tempo);
             let _todo = self.flow_vm.load(self.project.flow());
             let _todo = self
                 .flow_vm
                 .run_setup(&self.gl, project, &mut self.resource_log_manager);
             self.project_version = project.version();
+            self.project_load_generation = project.load_generation();
+            // might be a different project, with its own versions
+            self.flow_vm.set_sync_tracks(project.sync_tracks().clone());
+            self.sync_tracks_version = project.sync_tracks().version();
         }
         /*
                 match self.rebuild_program() {
@@ -149,26 +259,38 @@ impl McGuffin {
                     }
                 }
         */
-        project.with_property_manager(|pm| {
-            for (k, p) in pm.entries().iter() {
-                // :FUTURE: self.set_property( k, p );
-                match p.value() {
-                    PropertyValue::F32 { value, .. } => self.set_property_f32(k, *value),
-                    PropertyValue::Vec2F32 { values } => self.set_property_vec2_f32(k, &values),
-                    PropertyValue::Vec3F32 { values } => self.set_property_vec3_f32(k, &values),
-                    PropertyValue::Vec3F32Size4 { values } => {
-                        self.set_property_vec3_f32_size4(k, &values)
-                    }
-                    v => {
-                        eprintln!("Update for PropertyValue {v:?} not implemented");
-                    }
-                }
-            }
-        });
+        self.color_output = *project.color_output();
+        if self.sync_tracks_version != project.sync_tracks().version() {
+            self.flow_vm.set_sync_tracks(project.sync_tracks().clone());
+            self.sync_tracks_version = project.sync_tracks().version();
+        }
+        project.with_property_manager(|pm| self.flow_vm.set_property_values(pm));
+    }
+
+    pub fn flow_time(&self) -> &FlowTime {
+        self.flow_vm.flow_time()
+    }
+
+    pub fn clock(&self) -> &Clock {
+        self.flow_vm.clock()
+    }
+    pub fn clock_mut(&mut self) -> &mut Clock {
+        self.flow_vm.clock_mut()
+    }
+
+    /// Changes the label the flow starts at, and forces a rebuild of the flow.
+    pub fn set_start_label(&mut self, start_label: &str) {
+        self.flow_vm.set_start_label(start_label);
+        self.project_version = 0;
+    }
+
+    /// Clears the flow's previous frame, see [`crate::project::Feedback`].
+    pub fn reset_feedback(&mut self) {
+        self.flow_vm.reset_feedback();
     }
 
-    pub fn set_time(&mut self, time: f64) {
-        self.set_property_f32("fTime", time as f32); // :(
-        self.set_property_f64("fTime", time as f64); // :(
+    /// Uses the given time instead of the wall clock, e.g. for exporting frames.
+    pub fn set_fixed_time(&mut self, fixed_time: Option<f64>) {
+        self.flow_vm.set_fixed_time(fixed_time);
     }
 }
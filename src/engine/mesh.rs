@@ -0,0 +1,631 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// How the indices of a [`Mesh`] are drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    #[default]
+    Triangles,
+    LineStrip,
+    Points,
+}
+
+/// Indexed triangles, with a normal and a texture coordinate per vertex.
+///
+/// The generated meshes can also be line strips or points.
+#[derive(Default, Clone, PartialEq)]
+pub struct Mesh {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+    primitive: Primitive,
+}
+
+impl core::fmt::Debug for Mesh {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Mesh")
+            .field("vertices", &self.positions.len())
+            .field("indices", &self.indices.len())
+            .field("primitive", &self.primitive)
+            .finish()
+    }
+}
+
+impl Mesh {
+    /// The floats per vertex in [`Mesh::interleaved`].
+    pub const STRIDE: usize = 3 + 3 + 2;
+
+    /// Missing normals are calculated, missing texture coordinates are zero.
+    pub fn new(
+        positions: Vec<[f32; 3]>,
+        normals: Vec<[f32; 3]>,
+        uvs: Vec<[f32; 2]>,
+        indices: Vec<u32>,
+    ) -> Result<Self> {
+        if !indices.len().is_multiple_of(3) {
+            return Err(eyre!("{} indices are no triangles", indices.len()));
+        }
+        if let Some(i) = indices.iter().find(|i| **i as usize >= positions.len()) {
+            return Err(eyre!("Index {i} is out of {} vertices", positions.len()));
+        }
+        let mut mesh = Self {
+            positions,
+            normals,
+            uvs,
+            indices,
+            primitive: Primitive::Triangles,
+        };
+        if mesh.normals.len() != mesh.positions.len() {
+            mesh.calculate_normals();
+        }
+        mesh.uvs.resize(mesh.positions.len(), [0.0; 2]);
+        Ok(mesh)
+    }
+
+    /// A plane from `-1..1` in x and y, facing z, with `subdivisions` quads along each side.
+    pub fn plane(subdivisions: u32) -> Self {
+        let n = subdivisions.clamp(1, 1024);
+        let mut mesh = Self::default();
+        for j in 0..=n {
+            for i in 0..=n {
+                let uv = [i as f32 / n as f32, j as f32 / n as f32];
+                mesh.positions
+                    .push([uv[0] * 2.0 - 1.0, uv[1] * 2.0 - 1.0, 0.0]);
+                mesh.normals.push([0.0, 0.0, 1.0]);
+                mesh.uvs.push(uv);
+            }
+        }
+        for j in 0..n {
+            for i in 0..n {
+                let a = j * (n + 1) + i;
+                let d = a + n + 1;
+                mesh.indices.extend([a, a + 1, d + 1, a, d + 1, d]);
+            }
+        }
+        mesh
+    }
+
+    /// A unit sphere, with `subdivisions` rings from pole to pole, and twice as many segments around y.
+    pub fn sphere(subdivisions: u32) -> Self {
+        let rings = subdivisions.clamp(2, 512);
+        let segments = 2 * rings;
+        let mut mesh = Self::default();
+        for r in 0..=rings {
+            let v = r as f32 / rings as f32;
+            let (sin_theta, cos_theta) = (v * core::f32::consts::PI).sin_cos();
+            for s in 0..=segments {
+                let u = s as f32 / segments as f32;
+                let (sin_phi, cos_phi) = (u * core::f32::consts::TAU).sin_cos();
+                let p = [sin_theta * sin_phi, cos_theta, sin_theta * cos_phi];
+                mesh.positions.push(p);
+                mesh.normals.push(p);
+                mesh.uvs.push([u, 1.0 - v]);
+            }
+        }
+        for r in 0..rings {
+            for s in 0..segments {
+                let a = r * (segments + 1) + s;
+                let b = a + segments + 1;
+                mesh.indices.extend([a, b, a + 1, a + 1, b, b + 1]);
+            }
+        }
+        mesh
+    }
+
+    /// `points` from `-1` to `1` along x, the texture coordinate's u goes from `0` to `1`.
+    pub fn line_strip(points: u32) -> Self {
+        let n = points.clamp(2, 1 << 20);
+        let mut mesh = Self {
+            primitive: Primitive::LineStrip,
+            ..Default::default()
+        };
+        for i in 0..n {
+            let t = i as f32 / (n - 1) as f32;
+            mesh.positions.push([t * 2.0 - 1.0, 0.0, 0.0]);
+            mesh.normals.push([0.0, 0.0, 1.0]);
+            mesh.uvs.push([t, 0.0]);
+            mesh.indices.push(i);
+        }
+        mesh
+    }
+
+    /// A single point at the origin, to be placed by the vertex shader, e.g. per instance.
+    pub fn point() -> Self {
+        Self {
+            positions: vec![[0.0; 3]],
+            normals: vec![[0.0, 0.0, 1.0]],
+            uvs: vec![[0.5, 0.5]],
+            indices: vec![0],
+            primitive: Primitive::Points,
+        }
+    }
+
+    /// Loads an OBJ, glTF, or binary glTF file, by extension.
+    ///
+    /// Buffers of glTF files are read relative to the file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let data = std::fs::read(path)?;
+        match extension.as_str() {
+            "obj" => Self::from_obj(&String::from_utf8_lossy(&data)),
+            "gltf" => Self::from_gltf(&String::from_utf8_lossy(&data), path.parent()),
+            "glb" => Self::from_glb(&data, path.parent()),
+            e => Err(eyre!("Unsupported mesh format '{e}', expected OBJ or glTF")),
+        }
+    }
+
+    /// Polygons are split into fans, groups and materials are ignored.
+    pub fn from_obj(text: &str) -> Result<Self> {
+        let mut obj_positions: Vec<[f32; 3]> = Vec::new();
+        let mut obj_normals: Vec<[f32; 3]> = Vec::new();
+        let mut obj_uvs: Vec<[f32; 2]> = Vec::new();
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        let mut vertices: HashMap<(usize, Option<usize>, Option<usize>), u32> = HashMap::new();
+        let mut has_normals = true;
+
+        // 1 based, negative counts back from the last
+        let resolve = |index: &str, len: usize, line: usize| -> Result<usize> {
+            let i: i64 = index
+                .parse()
+                .map_err(|_| eyre!("Line {line}: bad index '{index}'"))?;
+            let i = if i < 0 { len as i64 + i } else { i - 1 };
+            if i < 0 || i >= len as i64 {
+                return Err(eyre!("Line {line}: index {index} is out of range"));
+            }
+            Ok(i as usize)
+        };
+
+        for (line_no, line) in text.lines().enumerate() {
+            let line_no = line_no + 1;
+            let mut parts = line.split_whitespace();
+            let floats = |parts: core::str::SplitWhitespace<'_>| -> Result<Vec<f32>> {
+                parts
+                    .map(|p| {
+                        p.parse()
+                            .map_err(|_| eyre!("Line {line_no}: bad number '{p}'"))
+                    })
+                    .collect()
+            };
+            match parts.next() {
+                Some("v") => {
+                    let v = floats(parts)?;
+                    if v.len() < 3 {
+                        return Err(eyre!("Line {line_no}: expected 3 coordinates"));
+                    }
+                    obj_positions.push([v[0], v[1], v[2]]);
+                }
+                Some("vn") => {
+                    let v = floats(parts)?;
+                    if v.len() < 3 {
+                        return Err(eyre!("Line {line_no}: expected 3 coordinates"));
+                    }
+                    obj_normals.push([v[0], v[1], v[2]]);
+                }
+                Some("vt") => {
+                    let v = floats(parts)?;
+                    obj_uvs.push([
+                        v.first().copied().unwrap_or_default(),
+                        v.get(1).copied().unwrap_or_default(),
+                    ]);
+                }
+                Some("f") => {
+                    let mut face = Vec::new();
+                    for corner in parts {
+                        let mut refs = corner.split('/');
+                        let p = resolve(refs.next().unwrap_or(""), obj_positions.len(), line_no)?;
+                        let t = match refs.next() {
+                            Some(t) if !t.is_empty() => Some(resolve(t, obj_uvs.len(), line_no)?),
+                            _ => None,
+                        };
+                        let n = match refs.next() {
+                            Some(n) if !n.is_empty() => {
+                                Some(resolve(n, obj_normals.len(), line_no)?)
+                            }
+                            _ => None,
+                        };
+                        has_normals &= n.is_some();
+                        let index = *vertices.entry((p, t, n)).or_insert_with(|| {
+                            positions.push(obj_positions[p]);
+                            normals.push(n.map(|n| obj_normals[n]).unwrap_or_default());
+                            uvs.push(t.map(|t| obj_uvs[t]).unwrap_or_default());
+                            positions.len() as u32 - 1
+                        });
+                        face.push(index);
+                    }
+                    if face.len() < 3 {
+                        return Err(eyre!("Line {line_no}: a face needs 3 vertices"));
+                    }
+                    for i in 1..face.len() - 1 {
+                        indices.extend([face[0], face[i], face[i + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if indices.is_empty() {
+            return Err(eyre!("No faces found"));
+        }
+        if !has_normals {
+            normals.clear();
+        }
+        Self::new(positions, normals, uvs, indices)
+    }
+
+    /// The triangles of all meshes, node transforms are ignored.
+    ///
+    /// Buffers can be data URIs, or files next to the glTF, in `parent`.
+    pub fn from_gltf(json: &str, parent: Option<&Path>) -> Result<Self> {
+        Self::from_gltf_with_binary(json, None, parent)
+    }
+
+    /// Binary glTF, the JSON chunk and the buffer in one file.
+    pub fn from_glb(data: &[u8], parent: Option<&Path>) -> Result<Self> {
+        let u32_at = |o: usize| -> Result<u32> {
+            data.get(o..o + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(|| eyre!("Truncated binary glTF"))
+        };
+        if data.get(0..4) != Some(b"glTF") {
+            return Err(eyre!("Not a binary glTF"));
+        }
+        let mut json = None;
+        let mut binary = None;
+        let mut offset = 12;
+        while offset + 8 <= data.len() {
+            let length = u32_at(offset)? as usize;
+            let chunk_type = u32_at(offset + 4)?;
+            let chunk = data
+                .get(offset + 8..offset + 8 + length)
+                .ok_or_else(|| eyre!("Truncated binary glTF"))?;
+            match chunk_type {
+                0x4E4F534A => json = Some(String::from_utf8_lossy(chunk).to_string()),
+                0x004E4942 => binary = Some(chunk.to_vec()),
+                _ => {}
+            }
+            offset += 8 + length;
+        }
+        let json = json.ok_or_else(|| eyre!("No JSON chunk in binary glTF"))?;
+        Self::from_gltf_with_binary(&json, binary, parent)
+    }
+
+    fn from_gltf_with_binary(
+        json: &str,
+        binary: Option<Vec<u8>>,
+        parent: Option<&Path>,
+    ) -> Result<Self> {
+        let gltf: serde_json::Value = serde_json::from_str(json)?;
+        let mut binary = binary;
+        let buffers = gltf["buffers"]
+            .as_array()
+            .map(|b| b.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|b| match b["uri"].as_str() {
+                Some(uri) if uri.starts_with("data:") => {
+                    let data = uri
+                        .split_once(";base64,")
+                        .map(|(_, d)| d)
+                        .ok_or_else(|| eyre!("Only base64 data URIs are supported"))?;
+                    Self::decode_base64(data)
+                }
+                Some(uri) => {
+                    let path = parent.map(|p| p.join(uri)).unwrap_or_else(|| uri.into());
+                    Ok(std::fs::read(&path)
+                        .map_err(|e| eyre!("Failed reading buffer {path:?} -> {e}"))?)
+                }
+                None => binary
+                    .take()
+                    .ok_or_else(|| eyre!("Buffer without uri, and no binary chunk")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        let mut has_normals = true;
+        for mesh in gltf["meshes"]
+            .as_array()
+            .map(|m| m.as_slice())
+            .unwrap_or_default()
+        {
+            for primitive in mesh["primitives"]
+                .as_array()
+                .map(|p| p.as_slice())
+                .unwrap_or_default()
+            {
+                if primitive["mode"].as_u64().unwrap_or(4) != 4 {
+                    eprintln!("Skipping primitive, only triangles are supported");
+                    continue;
+                }
+                let attributes = &primitive["attributes"];
+                let Some(position) = attributes["POSITION"].as_u64() else {
+                    continue;
+                };
+                let p = Self::gltf_floats(&gltf, &buffers, position as usize, 3)?;
+                let base = positions.len() as u32;
+                let count = p.len() / 3;
+                positions.extend(p.as_chunks::<3>().0);
+                match attributes["NORMAL"].as_u64() {
+                    Some(a) => {
+                        let n = Self::gltf_floats(&gltf, &buffers, a as usize, 3)?;
+                        normals.extend(n.as_chunks::<3>().0);
+                    }
+                    None => has_normals = false,
+                }
+                match attributes["TEXCOORD_0"].as_u64() {
+                    Some(a) => {
+                        let t = Self::gltf_floats(&gltf, &buffers, a as usize, 2)?;
+                        uvs.extend(t.as_chunks::<2>().0);
+                    }
+                    None => uvs.resize(positions.len(), [0.0; 2]),
+                }
+                match primitive["indices"].as_u64() {
+                    Some(a) => {
+                        let i = Self::gltf_indices(&gltf, &buffers, a as usize)?;
+                        indices.extend(i.into_iter().map(|i| base + i));
+                    }
+                    None => indices.extend(base..base + count as u32),
+                }
+            }
+        }
+        if indices.is_empty() {
+            return Err(eyre!("No triangles found"));
+        }
+        if !has_normals {
+            normals.clear();
+        }
+        Self::new(positions, normals, uvs, indices)
+    }
+
+    /// The bytes of each element of an accessor, and its component type.
+    fn gltf_accessor<'a>(
+        gltf: &serde_json::Value,
+        buffers: &'a [Vec<u8>],
+        accessor: usize,
+        element_size: impl Fn(u64) -> usize,
+    ) -> Result<(Vec<&'a [u8]>, u64)> {
+        let a = &gltf["accessors"][accessor];
+        let component_type = a["componentType"].as_u64().unwrap_or_default();
+        let count = a["count"].as_u64().unwrap_or_default() as usize;
+        let view = &gltf["bufferViews"][a["bufferView"]
+            .as_u64()
+            .ok_or_else(|| eyre!("Accessor {accessor} has no buffer view"))?
+            as usize];
+        let buffer = buffers
+            .get(view["buffer"].as_u64().unwrap_or_default() as usize)
+            .ok_or_else(|| eyre!("Accessor {accessor} has no buffer"))?;
+        let size = element_size(component_type);
+        let stride = view["byteStride"]
+            .as_u64()
+            .map(|s| s as usize)
+            .unwrap_or(size);
+        let start = view["byteOffset"].as_u64().unwrap_or_default() as usize
+            + a["byteOffset"].as_u64().unwrap_or_default() as usize;
+        (0..count)
+            .map(|i| {
+                let o = start + i * stride;
+                buffer
+                    .get(o..o + size)
+                    .ok_or_else(|| eyre!("Accessor {accessor} is out of its buffer"))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|e| (e, component_type))
+    }
+
+    fn gltf_floats(
+        gltf: &serde_json::Value,
+        buffers: &[Vec<u8>],
+        accessor: usize,
+        components: usize,
+    ) -> Result<Vec<f32>> {
+        let (elements, component_type) =
+            Self::gltf_accessor(gltf, buffers, accessor, |_| 4 * components)?;
+        if component_type != 5126 {
+            return Err(eyre!("Accessor {accessor} is not float"));
+        }
+        Ok(elements
+            .iter()
+            .flat_map(|e| e.as_chunks::<4>().0.iter().map(|b| f32::from_le_bytes(*b)))
+            .collect())
+    }
+
+    fn gltf_indices(
+        gltf: &serde_json::Value,
+        buffers: &[Vec<u8>],
+        accessor: usize,
+    ) -> Result<Vec<u32>> {
+        let size = |component_type| match component_type {
+            5121 => 1,
+            5123 => 2,
+            _ => 4,
+        };
+        let (elements, component_type) = Self::gltf_accessor(gltf, buffers, accessor, size)?;
+        elements
+            .iter()
+            .map(|b| match component_type {
+                5121 => Ok(b[0] as u32),
+                5123 => Ok(u16::from_le_bytes([b[0], b[1]]) as u32),
+                5125 => Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+                t => Err(eyre!("Unsupported index type {t}")),
+            })
+            .collect()
+    }
+
+    fn decode_base64(data: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+        let mut bits = 0u32;
+        let mut bit_count = 0;
+        for c in data.bytes() {
+            let v = match c {
+                b'A'..=b'Z' => c - b'A',
+                b'a'..=b'z' => c - b'a' + 26,
+                b'0'..=b'9' => c - b'0' + 52,
+                b'+' | b'-' => 62,
+                b'/' | b'_' => 63,
+                b'=' => break,
+                c if c.is_ascii_whitespace() => continue,
+                c => return Err(eyre!("Bad base64 character '{}'", c as char)),
+            };
+            bits = (bits << 6) | v as u32;
+            bit_count += 6;
+            if bit_count >= 8 {
+                bit_count -= 8;
+                bytes.push((bits >> bit_count) as u8);
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Smooth normals, the area weighted average of the faces around each vertex.
+    fn calculate_normals(&mut self) {
+        let mut normals = vec![[0.0f32; 3]; self.positions.len()];
+        for t in self.indices.as_chunks::<3>().0 {
+            let [a, b, c] = t.map(|i| self.positions[i as usize]);
+            let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let n = [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ];
+            for i in t {
+                for (a, n) in normals[*i as usize].iter_mut().zip(n) {
+                    *a += n;
+                }
+            }
+        }
+        for n in normals.iter_mut() {
+            let l = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2])
+                .sqrt()
+                .max(f32::EPSILON);
+            *n = n.map(|v| v / l);
+        }
+        self.normals = normals;
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+    pub fn primitive(&self) -> Primitive {
+        self.primitive
+    }
+    /// Position, normal, and texture coordinate of each vertex, see [`Mesh::STRIDE`].
+    pub fn interleaved(&self) -> Vec<f32> {
+        let mut data = Vec::with_capacity(self.positions.len() * Self::STRIDE);
+        for ((p, n), t) in self.positions.iter().zip(&self.normals).zip(&self.uvs) {
+            data.extend_from_slice(p);
+            data.extend_from_slice(n);
+            data.extend_from_slice(t);
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_obj_works() {
+        let obj = "# a quad\n\
+            v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+            vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+            vn 0 0 1\n\
+            f 1/1/1 2/2/1 3/3/1 4/4/1\n\
+            f -4/1/1 -2/3/1 -1/4/1\n";
+        let mesh = Mesh::from_obj(obj).unwrap();
+        assert_eq!(4, mesh.vertex_count());
+        assert_eq!(&[0, 1, 2, 0, 2, 3, 0, 2, 3], mesh.indices());
+        let data = mesh.interleaved();
+        assert_eq!(4 * Mesh::STRIDE, data.len());
+        assert_eq!(&[1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0], &data[16..24]);
+
+        // normals are calculated when missing
+        let mesh = Mesh::from_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+        assert_eq!(&[0.0, 0.0, 1.0], &mesh.interleaved()[3..6]);
+
+        assert!(Mesh::from_obj("v 0 0 0\nf 1 2 3\n").is_err());
+        assert!(Mesh::from_obj("v 0 0 0\n").is_err());
+    }
+
+    #[test]
+    fn generators_work() {
+        let plane = Mesh::plane(2);
+        assert_eq!(9, plane.vertex_count());
+        assert_eq!(2 * 2 * 6, plane.indices().len());
+        // counter clockwise, seen from the front
+        assert_eq!(&[0, 1, 4], &plane.indices()[0..3]);
+
+        let sphere = Mesh::sphere(4);
+        assert_eq!(5 * 9, sphere.vertex_count());
+        assert_eq!(4 * 8 * 6, sphere.indices().len());
+        for v in sphere.interleaved().chunks(Mesh::STRIDE) {
+            let l = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+            assert!((l - 1.0).abs() < 1e-5, "{v:?}");
+        }
+
+        let line = Mesh::line_strip(1);
+        assert_eq!(Primitive::LineStrip, line.primitive());
+        assert_eq!(&[0, 1], line.indices());
+        assert_eq!(&[1.0, 0.0, 0.0], &line.interleaved()[8..11]);
+
+        assert_eq!(Primitive::Points, Mesh::point().primitive());
+    }
+
+    #[test]
+    fn from_gltf_works() {
+        // one triangle, positions then u16 indices, padded to 4 bytes
+        let mut buffer = Vec::new();
+        for v in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+            buffer.extend(v.to_le_bytes());
+        }
+        for i in [0u16, 1, 2, 0] {
+            buffer.extend(i.to_le_bytes());
+        }
+        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let base64: String = buffer
+            .chunks(3)
+            .flat_map(|c| {
+                let b = [c[0], *c.get(1).unwrap_or(&0), *c.get(2).unwrap_or(&0)];
+                let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+                (0..=c.len()).map(move |i| CHARS[(n >> (18 - 6 * i) & 63) as usize] as char)
+            })
+            .collect();
+        let json = format!(
+            r#"{{
+            "buffers": [{{"byteLength": {}, "uri": "data:application/octet-stream;base64,{base64}"}}],
+            "bufferViews": [
+                {{"buffer": 0, "byteOffset": 0, "byteLength": 36}},
+                {{"buffer": 0, "byteOffset": 36, "byteLength": 6}}
+            ],
+            "accessors": [
+                {{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"}},
+                {{"bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR"}}
+            ],
+            "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}, "indices": 1}}]}}]
+        }}"#,
+            buffer.len()
+        );
+        let mesh = Mesh::from_gltf(&json, None).unwrap();
+        assert_eq!(3, mesh.vertex_count());
+        assert_eq!(&[0, 1, 2], mesh.indices());
+        assert_eq!(&[1.0, 0.0, 0.0], &mesh.interleaved()[8..11]);
+        assert_eq!(&[0.0, 0.0, 1.0], &mesh.interleaved()[11..14]);
+
+        assert!(Mesh::from_gltf(r#"{"meshes": []}"#, None).is_err());
+    }
+}
@@ -1,29 +1,81 @@
 mod mc_guffin;
+pub use mc_guffin::CapturedFrame;
 pub use mc_guffin::McGuffin;
 
 mod gl;
+#[cfg(target_arch = "wasm32")]
+mod gl_glow;
 mod gl_wrapper_macro;
+mod glsl_profile;
+pub use glsl_profile::GlslProfile;
 
+mod shader_annotation;
+pub use shader_annotation::ShaderAnnotation;
 mod shader_source;
 pub use shader_source::ShaderSource;
+mod builtin_uniforms;
+pub use builtin_uniforms::BuiltinUniforms;
+pub use builtin_uniforms::PreviewInput;
+mod output_transform;
+use output_transform::OutputTransform;
 mod pipeline;
 use pipeline::Pipeline;
+mod render_target;
+use render_target::RenderTarget;
 mod uniform;
 use uniform::Uniform;
 pub use uniform::UniformType;
 mod uniform_manager;
 pub use uniform_manager::UniformManager;
 
+mod audio_analysis;
+pub use audio_analysis::AudioAnalyzer;
+pub use audio_analysis::AudioValues;
+mod audio_clip;
+pub use audio_clip::AudioClip;
+mod audio_player;
+pub use audio_player::AudioPlayer;
+pub use audio_player::Tempo;
+mod audio_sink;
+pub use audio_sink::AudioSink;
+pub use audio_sink::NullAudioSink;
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+mod audio_sink_cpal;
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+pub use audio_sink_cpal::CpalAudioSink;
+
+mod mesh;
+pub use mesh::Mesh;
+pub use mesh::Primitive;
+
+mod storage_buffers;
+pub use storage_buffers::StorageBuffers;
+mod feedback_buffer;
+pub use feedback_buffer::FeedbackBuffer;
+
+mod render_state;
+pub use render_state::RenderState;
+
+mod clock;
+pub use clock::Clock;
+mod flow_time;
+pub use flow_time::FlowTime;
 mod flow_vm;
 pub use flow_vm::FlowVm;
 
 mod step_runner_data;
 pub use step_runner_data::StepRunnerData;
 
+mod step_runner_dispatch;
+pub use step_runner_dispatch::StepRunnerDispatch;
+mod step_runner_draw_mesh;
+pub use step_runner_draw_mesh::StepRunnerDrawMesh;
 mod step_runner_fullscreen_quad;
 pub use step_runner_fullscreen_quad::StepRunnerFullscreenQuad;
 mod step_runner_program;
 pub use step_runner_program::StepRunnerProgram;
+mod step_runner_render_state;
+pub use step_runner_render_state::StepRunnerRenderState;
 mod step_runner_set_uniform_f32;
 pub use step_runner_set_uniform_f32::StepRunnerSetUniformF32;
 mod step_runner_set_uniform_f64;
@@ -32,4 +84,6 @@ mod step_runner_set_uniform_vec3_f32;
 pub use step_runner_set_uniform_vec3_f32::StepRunnerSetUniformVec3F32;
 
 mod resource_log_manager;
+pub use resource_log_manager::LinkedProgram;
+pub use resource_log_manager::LinkedUniform;
 use resource_log_manager::ResourceLogManager;
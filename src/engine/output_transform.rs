@@ -0,0 +1,154 @@
+use crate::engine::gl::*;
+use crate::engine::Pipeline;
+use crate::engine::RenderTarget;
+use crate::engine::ShaderSource;
+use crate::project::ColorOutput;
+use crate::project::Tonemap;
+use std::collections::HashMap;
+
+const VERTEX_SHADER: &str = r#"#version 410
+layout(location=0)in vec2 v;
+out vec2 uv;
+void main() {
+    gl_Position = vec4(v, 0.0, 1.0);
+    uv = v * 0.5 + 0.5;
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"#version 410
+uniform sampler2D tTarget;
+uniform float fExposure;
+uniform float fTonemap;
+in vec2 uv;
+out vec4 out_color;
+
+vec3 aces(vec3 x) {
+    return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), 0.0, 1.0);
+}
+vec3 linear_to_srgb(vec3 c) {
+    c = clamp(c, 0.0, 1.0);
+    return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(vec3(0.0031308), c));
+}
+void main() {
+    vec3 c = max(texture(tTarget, uv).rgb * fExposure, vec3(0.0));
+    if (fTonemap > 1.5) {
+        c = aces(c);
+    } else if (fTonemap > 0.5) {
+        c = c / (1.0 + c);
+    }
+    out_color = vec4(linear_to_srgb(c), 1.0);
+}
+"#;
+
+/// Redirects the flow into a linear, half float target,
+/// and draws it tonemapped and sRGB encoded to the framebuffer egui paints to.
+#[derive(Debug, Default)]
+pub struct OutputTransform {
+    pipeline: Pipeline,
+    /// `None` until the first try, shaders are built once.
+    pipeline_ok: Option<bool>,
+    vertex_array: GLuint,
+    vertex_buffer: GLuint,
+
+    target: RenderTarget<1>,
+    active: bool,
+}
+
+impl OutputTransform {
+    /// Starts rendering into the target, sized to the current viewport.
+    ///
+    /// Returns `false` if there is no usable target, rendering then goes straight to the screen.
+    pub fn begin(&mut self, gl: &Gl) -> bool {
+        let mut viewport = [0; 4];
+        gl.glGetIntegerv(GL_VIEWPORT, viewport.as_mut_ptr());
+        let [_, _, w, h] = viewport;
+        if w <= 0 || h <= 0 || !self.ensure_pipeline(gl) {
+            return false;
+        }
+        self.target.save(gl, viewport);
+        if !self.target.ensure(gl, [w, h], "output transform") {
+            return false;
+        }
+        self.target.bind(gl, 0);
+        gl.check_gl_error(std::file!(), std::line!());
+        self.active = true;
+        true
+    }
+
+    /// Restores the screen, and draws the target to it.
+    pub fn end(&mut self, gl: &Gl, color_output: &ColorOutput) {
+        if !self.active {
+            return;
+        }
+        self.active = false;
+        self.target.restore(gl);
+
+        let (tonemap, exposure) = match color_output {
+            ColorOutput::Linear { tonemap, exposure } => (*tonemap, *exposure),
+            ColorOutput::Direct => (Tonemap::None, 1.0),
+        };
+        let tonemap = match tonemap {
+            Tonemap::None => 0.0,
+            Tonemap::Reinhard => 1.0,
+            Tonemap::Aces => 2.0,
+        };
+        let _ = self.pipeline.bind(gl);
+        self.pipeline.set_property(gl, "fExposure", exposure);
+        self.pipeline.set_property(gl, "fTonemap", tonemap);
+        // `tTarget` is never set, so it samples unit 0
+        gl.glActiveTexture(GL_TEXTURE0);
+        gl.glBindTexture(GL_TEXTURE_2D, self.target.textures()[0]);
+
+        gl.glBindVertexArray(self.vertex_array);
+        gl.bind_buffer(GL_ARRAY_BUFFER, self.vertex_buffer);
+        gl.enable_vertex_attrib_array(0);
+        gl.vertex_attrib_pointer(0, 2, GL_FLOAT, GL_FALSE as u8, 0, core::ptr::null());
+        gl.draw_arrays(GL_TRIANGLE_STRIP, 0, 4);
+
+        gl.glBindTexture(GL_TEXTURE_2D, 0);
+        gl.check_gl_error(std::file!(), std::line!());
+    }
+
+    /// Frees the target, e.g. when switching back to [`ColorOutput::Direct`].
+    pub fn release(&mut self, gl: &Gl) {
+        self.target.release(gl);
+    }
+
+    fn ensure_pipeline(&mut self, gl: &Gl) -> bool {
+        if let Some(ok) = self.pipeline_ok {
+            return ok;
+        }
+        let mut shader_sources = HashMap::default();
+        shader_sources.insert(
+            String::from("vertex"),
+            ShaderSource::new(GL_VERTEX_SHADER, VERTEX_SHADER.to_string()),
+        );
+        shader_sources.insert(
+            String::from("fragment"),
+            ShaderSource::new(GL_FRAGMENT_SHADER, FRAGMENT_SHADER.to_string()),
+        );
+        let ok = match self.pipeline.rebuild(gl, &mut shader_sources) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Failed building the output transform, rendering directly -> {e:?}");
+                false
+            }
+        };
+        if ok {
+            gl.glGenVertexArrays(1, &mut self.vertex_array);
+            gl.glBindVertexArray(self.vertex_array);
+            gl.gen_buffers(1, &mut self.vertex_buffer);
+            gl.bind_buffer(GL_ARRAY_BUFFER, self.vertex_buffer);
+            let vdata: &[f32] = &[1.0, -1.0, 1.0, 1.0, -1.0, -1.0, -1.0, 1.0];
+            gl.buffer_data(
+                GL_ARRAY_BUFFER,
+                (4 * vdata.len()) as isize,
+                vdata.as_ptr() as *const _,
+                GL_STATIC_DRAW,
+            );
+            gl.check_gl_error(std::file!(), std::line!());
+        }
+        self.pipeline_ok = Some(ok);
+        ok
+    }
+}
@@ -3,6 +3,8 @@ use crate::engine::uniform::UniformType;
 use crate::engine::ShaderSource;
 use crate::engine::Uniform;
 use crate::engine::UniformManager;
+use crate::project::Gradient;
+use crate::project::PropertyValue;
 use color_eyre::eyre::eyre;
 use color_eyre::eyre::ContextCompat;
 use color_eyre::Result;
@@ -23,82 +25,136 @@ impl Pipeline {
         gl.glUseProgram(self.program);
         Ok(())
     }
+    /// `false` until a rebuild succeeded.
+    pub fn is_linked(&self) -> bool {
+        self.program != 0
+    }
     pub fn uniform_manager(&self) -> &UniformManager {
         &self.uniform_manager
     }
 
-    pub fn set_property(&mut self, gl: &mut Gl, name: &str, value: f32) -> Result<()> {
-        if let Some(u) = self.uniform_manager.get_mut(name) {
-            match u.ttype() {
-                UniformType::Float => u.set_f32(gl, self.program, value),
-                _ => {}
+    /// Uploads the value, if the uniform is active and of a matching type.
+    pub fn set_property_value(&self, gl: &Gl, name: &str, value: &PropertyValue) {
+        match value {
+            PropertyValue::F32 { value } => self.set_property(gl, name, *value),
+            PropertyValue::F64 { value } => self.set_property_f64(gl, name, *value),
+            PropertyValue::Vec2F32 { values } => self.set_property_vec2_f32(gl, name, values),
+            PropertyValue::Vec3F32 { values } => self.set_property_vec3_f32(gl, name, values),
+            PropertyValue::Vec3F32Size4 { values } => {
+                self.set_property_vec3_f32_size4(gl, name, values)
+            }
+            PropertyValue::Bool { value } => {
+                self.set_property(gl, name, if *value { 1.0 } else { 0.0 })
             }
+            PropertyValue::Gradient { gradient } => self.set_property_gradient(gl, name, gradient),
+            // needs the aspect ratio of the viewport, see `StepRunnerProgram`
+            PropertyValue::Matrix { .. } => {}
+            PropertyValue::None => {}
         }
         if gl.check_gl_error(std::file!(), std::line!()) {
             eprintln!("Error after setting {name}");
         }
-
-        Ok(())
     }
-    pub fn set_property_vec2_f32(
-        &mut self,
-        gl: &mut Gl,
-        name: &str,
-        values: &[f32; 2],
-    ) -> Result<()> {
-        if let Some(u) = self.uniform_manager.get_mut(name) {
-            match u.ttype() {
-                UniformType::Vec2Float => u.set_vec2_f32(gl, self.program, values),
-                _ => {}
+    pub fn set_property(&self, gl: &Gl, name: &str, value: f32) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Float = u.ttype() {
+                u.set_f32(gl, self.program, value);
             }
         }
-        if gl.check_gl_error(std::file!(), std::line!()) {
-            eprintln!("Error after setting {name}");
+    }
+    pub fn set_property_f64(&self, gl: &Gl, name: &str, value: f64) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Double = u.ttype() {
+                u.set_f64(gl, self.program, value);
+            }
         }
-
-        Ok(())
     }
-    pub fn set_property_vec3_f32(
-        &mut self,
-        gl: &mut Gl,
-        name: &str,
-        values: &[f32; 3],
-    ) -> Result<()> {
-        if let Some(u) = self.uniform_manager.get_mut(name) {
-            match u.ttype() {
-                UniformType::Vec3Float => u.set_vec3_f32(gl, self.program, values),
-                _ => {}
+    pub fn set_property_vec2_f32(&self, gl: &Gl, name: &str, values: &[f32; 2]) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Vec2Float = u.ttype() {
+                u.set_vec2_f32(gl, self.program, values);
             }
         }
-        if gl.check_gl_error(std::file!(), std::line!()) {
-            eprintln!("Error after setting {name}");
+    }
+    pub fn set_property_vec3_f32(&self, gl: &Gl, name: &str, values: &[f32; 3]) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let (UniformType::Vec3Float, 1) = (u.ttype(), u.size()) {
+                u.set_vec3_f32(gl, self.program, values);
+            }
         }
-
-        Ok(())
     }
-    pub fn set_property_vec3_f32_size4(
-        &mut self,
-        gl: &mut Gl,
-        name: &str,
-        values: &[f32; 3 * 4],
-    ) -> Result<()> {
-        if let Some(u) = self.uniform_manager.get_mut(name) {
-            match u.ttype() {
-                UniformType::Vec3Float => u.set_vec3_f32_size4(gl, self.program, values),
-                _ => {}
+    /// Uploads evenly spaced samples, one per element of the `vec3` array.
+    pub fn set_property_gradient(&self, gl: &Gl, name: &str, gradient: &Gradient) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Vec3Float = u.ttype() {
+                let samples = gradient.bake(u.size() as usize);
+                u.set_vec3_f32_array(gl, self.program, &samples);
             }
         }
-        if gl.check_gl_error(std::file!(), std::line!()) {
-            eprintln!("Error after setting {name}");
+    }
+    pub fn set_property_vec4_f32(&self, gl: &Gl, name: &str, values: &[f32; 4]) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Vec4Float = u.ttype() {
+                u.set_vec4_f32(gl, self.program, values);
+            }
+        }
+    }
+    pub fn set_property_mat4_f32(&self, gl: &Gl, name: &str, values: &[f32; 16]) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Mat4Float = u.ttype() {
+                u.set_mat4_f32(gl, self.program, values);
+            }
+        }
+    }
+    pub fn set_property_i32(&self, gl: &Gl, name: &str, value: i32) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Int = u.ttype() {
+                u.set_i32(gl, self.program, value);
+            }
+        }
+    }
+    /// Points the sampler at a texture unit.
+    pub fn set_sampler(&self, gl: &Gl, name: &str, unit: u32) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Sampler2D = u.ttype() {
+                u.set_i32(gl, self.program, unit as i32);
+            }
+        }
+    }
+    /// For `vec3` arrays, e.g. `iChannelResolution[0]`.
+    pub fn set_property_vec3_f32_array(&self, gl: &Gl, name: &str, values: &[[f32; 3]]) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let UniformType::Vec3Float = u.ttype() {
+                let len = values.len().min(u.size() as usize);
+                u.set_vec3_f32_array(gl, self.program, &values[..len]);
+            }
+        }
+    }
+    pub fn set_property_vec3_f32_size4(&self, gl: &Gl, name: &str, values: &[f32; 3 * 4]) {
+        if let Some(u) = self.uniform_manager.get(name) {
+            if let (UniformType::Vec3Float, 4) = (u.ttype(), u.size()) {
+                u.set_vec3_f32_size4(gl, self.program, values);
+            }
         }
-
-        Ok(())
     }
     pub fn rebuild(
         &mut self,
         gl: &Gl,
         shader_sources: &mut HashMap<String, ShaderSource>,
     ) -> Result<()> {
+        if let Some(compute_shader_source) = shader_sources.get_mut("compute") {
+            if !gl.supports_compute() {
+                compute_shader_source.set_compile_log_from_string(String::from(
+                    "Compute shaders need OpenGL 4.3, which is not available",
+                ));
+                return Err(eyre!("Compute shaders are not supported"));
+            }
+            let compute_shader = self.compile_shader(gl, compute_shader_source)?;
+            self.program = self.link_program(gl, &[compute_shader])?;
+            eprintln!("pipeline.rebuild() -> success {}", self.program);
+            return Ok(());
+        }
+
         let vertex_shader = {
             let vertex_shader_source = shader_sources
                 .get_mut("vertex")
@@ -115,13 +171,32 @@ impl Pipeline {
             let fragment_shader = self.compile_shader(gl, fragment_shader_source)?;
             fragment_shader
         };
-        self.program = self.link_program(gl, vertex_shader, fragment_shader)?;
+        let geometry_shader = match shader_sources.get_mut("geometry") {
+            Some(geometry_shader_source) if !gl.supports_geometry() => {
+                geometry_shader_source.set_compile_log_from_string(String::from(
+                    "Geometry shaders are not available with WebGL",
+                ));
+                return Err(eyre!("Geometry shaders are not supported"));
+            }
+            Some(geometry_shader_source) => Some(self.compile_shader(gl, geometry_shader_source)?),
+            None => None,
+        };
+        let shaders: Vec<GLuint> = [Some(vertex_shader), geometry_shader, Some(fragment_shader)]
+            .into_iter()
+            .flatten()
+            .collect();
+        self.program = self.link_program(gl, &shaders)?;
         eprintln!("pipeline.rebuild() -> success {}", self.program);
         Ok(())
     }
     fn compile_shader(&mut self, gl: &Gl, shader_source: &mut ShaderSource) -> Result<GLuint> {
         // :TODO: verify shader type
-        let source = CString::new(shader_source.source())?;
+        let source = gl.glsl_profile().translate_with_prelude(
+            shader_source.source(),
+            shader_source.prelude(),
+            shader_source.shader_type(),
+        );
+        let source = CString::new(source)?;
         let shader = gl.glCreateShader(shader_source.shader_type());
 
         gl.glShaderSource(
@@ -157,10 +232,11 @@ impl Pipeline {
         Ok(shader)
     }
 
-    fn link_program(&mut self, gl: &Gl, vertex_shader: u32, fragment_shader: u32) -> Result<u32> {
+    fn link_program(&mut self, gl: &Gl, shaders: &[GLuint]) -> Result<u32> {
         let program = gl.glCreateProgram();
-        gl.glAttachShader(program, vertex_shader);
-        gl.glAttachShader(program, fragment_shader);
+        for shader in shaders {
+            gl.glAttachShader(program, *shader);
+        }
         gl.glLinkProgram(program);
 
         let mut status: GLint = GL_FALSE as GLint;
@@ -227,6 +303,13 @@ impl Pipeline {
                     }
                     self.uniform_manager.add_entry(name.clone(), u);
                 }
+                GL_DOUBLE => {
+                    let mut u = Uniform::new_double();
+                    if l != -1 {
+                        u.set_location(l);
+                    }
+                    self.uniform_manager.add_entry(name.clone(), u);
+                }
                 GL_FLOAT_VEC2 => {
                     let mut u = Uniform::new_vec2_float();
                     if l != -1 {
@@ -243,6 +326,34 @@ impl Pipeline {
                     }
                     self.uniform_manager.add_entry(name.clone(), u);
                 }
+                GL_FLOAT_VEC4 => {
+                    let mut u = Uniform::new_vec4_float();
+                    if l != -1 {
+                        u.set_location(l);
+                    }
+                    self.uniform_manager.add_entry(name.clone(), u);
+                }
+                GL_FLOAT_MAT4 => {
+                    let mut u = Uniform::new_mat4_float();
+                    if l != -1 {
+                        u.set_location(l);
+                    }
+                    self.uniform_manager.add_entry(name.clone(), u);
+                }
+                GL_INT => {
+                    let mut u = Uniform::new_int();
+                    if l != -1 {
+                        u.set_location(l);
+                    }
+                    self.uniform_manager.add_entry(name.clone(), u);
+                }
+                GL_SAMPLER_2D => {
+                    let mut u = Uniform::new_sampler_2d();
+                    if l != -1 {
+                        u.set_location(l);
+                    }
+                    self.uniform_manager.add_entry(name.clone(), u);
+                }
                 o => {
                     eprintln!("Uniform type 0x{o:04x} is not supported");
                 }
@@ -0,0 +1,73 @@
+use crate::engine::gl::*;
+
+/// The fixed function state the render state steps change, saved before the flow runs.
+///
+/// egui restores most of its state after a paint callback, but not the depth and cull settings,
+/// and the output transform draws with whatever the flow left behind, so everything is put back right after the flow.
+#[derive(Debug, Default)]
+pub struct RenderState {
+    blend: bool,
+    blend_func: [GLint; 4],
+    blend_equation: [GLint; 2],
+    depth_test: bool,
+    cull_face: bool,
+    viewport: [GLint; 4],
+}
+
+impl RenderState {
+    pub fn save(gl: &Gl) -> Self {
+        let mut s = Self {
+            blend: gl.glIsEnabled(GL_BLEND) != 0,
+            depth_test: gl.glIsEnabled(GL_DEPTH_TEST) != 0,
+            cull_face: gl.glIsEnabled(GL_CULL_FACE) != 0,
+            ..Default::default()
+        };
+        for (pname, v) in [
+            GL_BLEND_SRC_RGB,
+            GL_BLEND_DST_RGB,
+            GL_BLEND_SRC_ALPHA,
+            GL_BLEND_DST_ALPHA,
+        ]
+        .into_iter()
+        .zip(s.blend_func.iter_mut())
+        {
+            gl.glGetIntegerv(pname, v);
+        }
+        for (pname, v) in [GL_BLEND_EQUATION_RGB, GL_BLEND_EQUATION_ALPHA]
+            .into_iter()
+            .zip(s.blend_equation.iter_mut())
+        {
+            gl.glGetIntegerv(pname, v);
+        }
+        gl.glGetIntegerv(GL_VIEWPORT, s.viewport.as_mut_ptr());
+        s
+    }
+
+    /// Puts the saved state back, the depth and cull settings the state can't be read for go back to the GL defaults.
+    pub fn restore(&self, gl: &Gl) {
+        Self::set_enabled(gl, GL_BLEND, self.blend);
+        let [src_rgb, dst_rgb, src_alpha, dst_alpha] = self.blend_func.map(|v| v as GLenum);
+        gl.glBlendFuncSeparate(src_rgb, dst_rgb, src_alpha, dst_alpha);
+        let [mode_rgb, mode_alpha] = self.blend_equation.map(|v| v as GLenum);
+        gl.glBlendEquationSeparate(mode_rgb, mode_alpha);
+
+        Self::set_enabled(gl, GL_DEPTH_TEST, self.depth_test);
+        gl.glDepthFunc(GL_LESS);
+        gl.glDepthMask(GL_TRUE as GLboolean);
+
+        Self::set_enabled(gl, GL_CULL_FACE, self.cull_face);
+        gl.glCullFace(GL_BACK);
+
+        let [x, y, w, h] = self.viewport;
+        gl.glViewport(x, y, w, h);
+        gl.check_gl_error(std::file!(), std::line!());
+    }
+
+    fn set_enabled(gl: &Gl, cap: GLenum, enabled: bool) {
+        if enabled {
+            gl.glEnable(cap);
+        } else {
+            gl.glDisable(cap);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::engine::gl::*;
+
+/// `N` colour textures, each in its own framebuffer, sharing one depth texture.
+///
+/// Also saves the state of the framebuffer rendered to before, to restore it afterwards.
+#[derive(Debug)]
+pub struct RenderTarget<const N: usize> {
+    framebuffers: [GLuint; N],
+    textures: [GLuint; N],
+    depth_texture: GLuint,
+    size: [GLsizei; 2],
+    ok: bool,
+
+    // the state to restore
+    previous_framebuffer: GLint,
+    previous_viewport: [GLint; 4],
+    scissor_test: bool,
+}
+
+impl<const N: usize> Default for RenderTarget<N> {
+    fn default() -> Self {
+        Self {
+            framebuffers: [0; N],
+            textures: [0; N],
+            depth_texture: 0,
+            size: [0, 0],
+            ok: false,
+            previous_framebuffer: 0,
+            previous_viewport: [0; 4],
+            scissor_test: false,
+        }
+    }
+}
+
+impl<const N: usize> RenderTarget<N> {
+    pub fn size(&self) -> [GLsizei; 2] {
+        self.size
+    }
+    pub fn textures(&self) -> &[GLuint; N] {
+        &self.textures
+    }
+    pub fn framebuffer(&self, index: usize) -> GLuint {
+        self.framebuffers[index]
+    }
+    pub fn previous_framebuffer(&self) -> GLuint {
+        self.previous_framebuffer as GLuint
+    }
+    pub fn previous_viewport(&self) -> [GLint; 4] {
+        self.previous_viewport
+    }
+
+    /// Remembers the current framebuffer, and `viewport` in it, to be restored by [`RenderTarget::restore`].
+    pub fn save(&mut self, gl: &Gl, viewport: [GLint; 4]) {
+        gl.glGetIntegerv(GL_FRAMEBUFFER_BINDING, &mut self.previous_framebuffer);
+        self.previous_viewport = viewport;
+        self.scissor_test = gl.glIsEnabled(GL_SCISSOR_TEST) != 0;
+    }
+
+    /// Renders into the texture at `index`, with the viewport covering all of it.
+    pub fn bind(&self, gl: &Gl, index: usize) {
+        gl.glBindFramebuffer(GL_FRAMEBUFFER, self.framebuffers[index]);
+        gl.glViewport(0, 0, self.size[0], self.size[1]);
+        // egui's clip rect is in screen space
+        gl.glDisable(GL_SCISSOR_TEST);
+    }
+
+    /// Goes back to the framebuffer saved by [`RenderTarget::save`].
+    pub fn restore(&self, gl: &Gl) {
+        gl.glBindFramebuffer(GL_FRAMEBUFFER, self.previous_framebuffer as GLuint);
+        let [x, y, w, h] = self.previous_viewport;
+        gl.glViewport(x, y, w, h);
+        if self.scissor_test {
+            gl.glEnable(GL_SCISSOR_TEST);
+        }
+    }
+
+    /// (Re)creates the textures if the size changed, returns `false` if there is no usable target.
+    ///
+    /// Call after [`RenderTarget::save`], the saved framebuffer is bound again afterwards.
+    /// `name` is only used for reporting.
+    pub fn ensure(&mut self, gl: &Gl, size: [GLsizei; 2], name: &str) -> bool {
+        if self.size == size {
+            return self.ok;
+        }
+        self.release(gl);
+        self.size = size;
+
+        // half float targets need `EXT_color_buffer_float` on WebGL2, so fall back to 8 bit
+        for (internal_format, ttype) in [(GL_RGBA16F, GL_HALF_FLOAT), (GL_RGBA8, GL_UNSIGNED_BYTE)]
+        {
+            // for `Step::Depth`
+            gl.glGenTextures(1, &mut self.depth_texture);
+            gl.glBindTexture(GL_TEXTURE_2D, self.depth_texture);
+            for (pname, param) in [
+                (GL_TEXTURE_MIN_FILTER, GL_NEAREST),
+                (GL_TEXTURE_MAG_FILTER, GL_NEAREST),
+            ] {
+                gl.glTexParameteri(GL_TEXTURE_2D, pname, param as GLint);
+            }
+            gl.glTexImage2D(
+                GL_TEXTURE_2D,
+                0,
+                GL_DEPTH_COMPONENT24 as GLint,
+                size[0],
+                size[1],
+                0,
+                GL_DEPTH_COMPONENT,
+                GL_UNSIGNED_INT,
+                core::ptr::null(),
+            );
+
+            let mut complete = true;
+            for i in 0..N {
+                gl.glGenTextures(1, &mut self.textures[i]);
+                gl.glBindTexture(GL_TEXTURE_2D, self.textures[i]);
+                for (pname, param) in [
+                    (GL_TEXTURE_MIN_FILTER, GL_NEAREST),
+                    (GL_TEXTURE_MAG_FILTER, GL_NEAREST),
+                    (GL_TEXTURE_WRAP_S, GL_CLAMP_TO_EDGE),
+                    (GL_TEXTURE_WRAP_T, GL_CLAMP_TO_EDGE),
+                ] {
+                    gl.glTexParameteri(GL_TEXTURE_2D, pname, param as GLint);
+                }
+                gl.glTexImage2D(
+                    GL_TEXTURE_2D,
+                    0,
+                    internal_format as GLint,
+                    size[0],
+                    size[1],
+                    0,
+                    GL_RGBA,
+                    ttype,
+                    core::ptr::null(),
+                );
+                gl.glBindTexture(GL_TEXTURE_2D, 0);
+
+                gl.glGenFramebuffers(1, &mut self.framebuffers[i]);
+                gl.glBindFramebuffer(GL_FRAMEBUFFER, self.framebuffers[i]);
+                gl.glFramebufferTexture2D(
+                    GL_FRAMEBUFFER,
+                    GL_COLOR_ATTACHMENT0,
+                    GL_TEXTURE_2D,
+                    self.textures[i],
+                    0,
+                );
+                gl.glFramebufferTexture2D(
+                    GL_FRAMEBUFFER,
+                    GL_DEPTH_ATTACHMENT,
+                    GL_TEXTURE_2D,
+                    self.depth_texture,
+                    0,
+                );
+                complete &= gl.glCheckFramebufferStatus(GL_FRAMEBUFFER) == GL_FRAMEBUFFER_COMPLETE;
+            }
+            gl.glBindFramebuffer(GL_FRAMEBUFFER, self.previous_framebuffer as GLuint);
+            // drop errors from the failed attempt
+            let _ = gl.glGetError();
+            if complete {
+                if internal_format != GL_RGBA16F {
+                    eprintln!("No half float target, the {name} uses 8 bits");
+                }
+                self.ok = true;
+                return true;
+            }
+            eprintln!("The {name} target 0x{internal_format:04x} is incomplete");
+            let size = self.size;
+            self.release(gl);
+            self.size = size;
+        }
+        false
+    }
+
+    pub fn release(&mut self, gl: &Gl) {
+        for framebuffer in self.framebuffers.iter_mut().filter(|fb| **fb != 0) {
+            gl.glDeleteFramebuffers(1, framebuffer);
+            *framebuffer = 0;
+        }
+        for texture in self
+            .textures
+            .iter_mut()
+            .chain(std::iter::once(&mut self.depth_texture))
+            .filter(|t| **t != 0)
+        {
+            gl.glDeleteTextures(1, texture);
+            *texture = 0;
+        }
+        self.size = [0, 0];
+        self.ok = false;
+    }
+}
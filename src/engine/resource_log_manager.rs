@@ -1,10 +1,31 @@
 use std::collections::HashMap;
 
+use crate::engine::ShaderAnnotation;
+use crate::engine::UniformType;
 use crate::project::ResourceId;
 
+/// An active uniform of a linked program.
+#[derive(Debug, Default, Clone)]
+pub struct LinkedUniform {
+    /// The reflected name, arrays as `name[0]`.
+    pub name: String,
+    pub ttype: UniformType,
+    pub size: u8,
+}
+
+/// What was learned about a program when it was linked.
+#[derive(Debug, Default, Clone)]
+pub struct LinkedProgram {
+    pub resource_id: ResourceId,
+    pub uniforms: Vec<LinkedUniform>,
+    pub shader_annotations: Vec<ShaderAnnotation>,
+}
+
 #[derive(Debug, Default)]
 pub struct ResourceLogManager {
     logs: HashMap<ResourceId, Vec<String>>,
+    /// Programs linked since the last take.
+    linked_programs: Vec<LinkedProgram>,
 }
 
 impl ResourceLogManager {
@@ -15,4 +36,11 @@ impl ResourceLogManager {
     pub fn get(&self, resource_id: &ResourceId) -> Option<&Vec<String>> {
         self.logs.get(resource_id)
     }
+
+    pub fn add_linked_program(&mut self, linked_program: LinkedProgram) {
+        self.linked_programs.push(linked_program);
+    }
+    pub fn take_linked_programs(&mut self) -> Vec<LinkedProgram> {
+        core::mem::take(&mut self.linked_programs)
+    }
 }
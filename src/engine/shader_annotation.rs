@@ -0,0 +1,188 @@
+use crate::project::Gizmo;
+
+/// Property configuration for a uniform, from annotations in a GLSL comment.
+///
+/// The comment can be behind the declaration, or on the lines directly above it, e.g.
+/// `uniform float speed; // @range(0,10) @step(0.1) @default(1)`, `// @color`, or `// @gradient`.
+/// Colours and gradients tagged `@linear` are uploaded in linear light.
+/// `@position`, `@direction`, `@radius`, and `@nogizmo` pick the gizmo on the McGuffin preview.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ShaderAnnotation {
+    pub uniform: String,
+    pub glsl_type: String,
+    /// The array size, `1` for plain uniforms.
+    pub size: u32,
+    pub range: Option<(f32, f32)>,
+    pub step: Option<f32>,
+    pub default: Option<Vec<f32>>,
+    pub color: bool,
+    /// A `vec3` array sampled from a gradient.
+    pub gradient: bool,
+    pub linear: bool,
+    pub gizmo: Option<Gizmo>,
+}
+
+impl ShaderAnnotation {
+    /// Returns the annotations for all annotated uniforms in `source`.
+    pub fn parse(source: &str) -> Vec<ShaderAnnotation> {
+        let mut annotations = Vec::default();
+        let mut pending = String::default();
+        for line in source.lines() {
+            let (code, comment) = match line.split_once("//") {
+                Some((code, comment)) => (code.trim(), comment),
+                None => (line.trim(), ""),
+            };
+            if code.is_empty() {
+                if comment.contains('@') {
+                    pending.push(' ');
+                    pending.push_str(comment);
+                } else {
+                    pending.clear();
+                }
+                continue;
+            }
+            let tags = format!("{pending} {comment}");
+            pending.clear();
+            let Some(declaration) = code.strip_prefix("uniform ") else {
+                continue;
+            };
+            if !tags.contains('@') {
+                continue;
+            }
+            let mut template = ShaderAnnotation::default();
+            template.apply_tags(&tags);
+            annotations.extend(Self::parse_declaration(declaration, &template));
+        }
+        annotations
+    }
+
+    fn parse_declaration(declaration: &str, template: &ShaderAnnotation) -> Vec<ShaderAnnotation> {
+        let declaration = declaration.trim_end_matches(';');
+        let mut words = declaration
+            .split_whitespace()
+            .skip_while(|w| matches!(*w, "lowp" | "mediump" | "highp"));
+        let Some(glsl_type) = words.next() else {
+            return Vec::default();
+        };
+        let names: String = words.collect::<Vec<_>>().join(" ");
+        names
+            .split(',')
+            .filter_map(|n| {
+                let n = n.trim();
+                let (name, size) = match n.split_once('[') {
+                    Some((name, size)) => {
+                        (name.trim(), size.trim_end_matches(']').trim().parse().ok()?)
+                    }
+                    None => (n, 1),
+                };
+                if name.is_empty() {
+                    return None;
+                }
+                Some(ShaderAnnotation {
+                    uniform: name.to_string(),
+                    glsl_type: glsl_type.to_string(),
+                    size,
+                    ..template.clone()
+                })
+            })
+            .collect()
+    }
+
+    fn apply_tags(&mut self, tags: &str) {
+        for tag in tags.split('@').skip(1) {
+            let tag = tag.trim();
+            let (name, args) = match tag.split_once('(') {
+                Some((name, rest)) => {
+                    let args = rest.split(')').next().unwrap_or_default();
+                    let args: Vec<f32> = args
+                        .split(',')
+                        .filter_map(|a| a.trim().parse().ok())
+                        .collect();
+                    (name.trim(), args)
+                }
+                None => (
+                    tag.split_whitespace().next().unwrap_or_default(),
+                    Vec::default(),
+                ),
+            };
+            match (name, args.as_slice()) {
+                ("range", [min_value, max_value]) => self.range = Some((*min_value, *max_value)),
+                ("step", [step]) => self.step = Some(*step),
+                ("default", [_, ..]) => self.default = Some(args),
+                ("color", []) => self.color = true,
+                ("gradient", []) => self.gradient = true,
+                ("linear", []) => self.linear = true,
+                ("position", []) => self.gizmo = Some(Gizmo::Position),
+                ("direction", []) => self.gizmo = Some(Gizmo::Direction),
+                ("radius", []) => self.gizmo = Some(Gizmo::Radius),
+                ("nogizmo", []) => self.gizmo = Some(Gizmo::Hidden),
+                (o, _) => eprintln!("Ignoring invalid shader annotation `@{o}` in `{tag}`"),
+            }
+        }
+    }
+
+    /// The name the uniform is reflected as, arrays are reflected with their first element.
+    pub fn reflected_name(&self) -> String {
+        if self.size > 1 {
+            format!("{}[0]", self.uniform)
+        } else {
+            self.uniform.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_works() {
+        let source = r#"
+#version 410
+uniform float fSpeed; // @range(0,10) @step(0.1) @default(1)
+uniform float fPlain;
+// The main colour
+// @color @default(1.0, 0.5, 0.25)
+uniform highp vec3 vTint;
+uniform vec3 vPal[4]; // @color
+uniform float a, b; // @range(-1, 1) @radius
+uniform vec3 vSky[16]; // @gradient @linear
+// @range(0,1)
+
+uniform float fNotAnnotated;
+void main() {}
+"#;
+        let annotations = ShaderAnnotation::parse(source);
+        assert_eq!(
+            vec!["fSpeed", "vTint", "vPal", "a", "b", "vSky"],
+            annotations
+                .iter()
+                .map(|a| a.uniform.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            ShaderAnnotation {
+                uniform: "fSpeed".into(),
+                glsl_type: "float".into(),
+                size: 1,
+                range: Some((0.0, 10.0)),
+                step: Some(0.1),
+                default: Some(vec![1.0]),
+                color: false,
+                gradient: false,
+                linear: false,
+                gizmo: None,
+            },
+            annotations[0]
+        );
+        assert!(annotations[1].color);
+        assert_eq!(Some(vec![1.0, 0.5, 0.25]), annotations[1].default);
+        assert_eq!("vec3", annotations[1].glsl_type);
+        assert_eq!("vPal[0]", annotations[2].reflected_name());
+        assert_eq!(Some((-1.0, 1.0)), annotations[4].range);
+        assert_eq!(Some(Gizmo::Radius), annotations[4].gizmo);
+        assert!(annotations[5].gradient);
+        assert!(annotations[5].linear);
+        assert_eq!(16, annotations[5].size);
+    }
+}
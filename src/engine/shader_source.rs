@@ -7,6 +7,8 @@ use crate::project::ResourceId;
 pub struct ShaderSource {
     shader_type: GLenum,
     source: String,
+    /// Code inserted before the source, e.g. shared by several programs.
+    prelude: String,
     dirty: bool,
 
     compile_log: Vec<String>,
@@ -58,6 +60,12 @@ impl ShaderSource {
     pub fn source(&self) -> &str {
         &self.source
     }
+    pub fn prelude(&self) -> &str {
+        &self.prelude
+    }
+    pub fn set_prelude(&mut self, prelude: String) {
+        self.prelude = prelude;
+    }
     pub fn update_source(&mut self, source: String) {
         self.source = source;
         self.dirty = true;
@@ -0,0 +1,54 @@
+use crate::engine::gl::Gl;
+use crate::engine::gl::GL_ALL_BARRIER_BITS;
+use crate::engine::BuiltinUniforms;
+use crate::engine::ResourceLogManager;
+use crate::engine::StepRunnerData;
+use crate::engine::StepRunnerProgram;
+use crate::project::Project;
+use crate::project::PropertyValue;
+use crate::project::Step;
+use std::collections::HashMap;
+
+/// Runs a compute program, which is set up, and gets its uniforms, like the one of a `Program` step.
+///
+/// Does nothing without compute support, see [`Gl::supports_compute`].
+#[derive(Debug, Default)]
+pub struct StepRunnerDispatch {}
+
+impl StepRunnerDispatch {
+    pub fn create_data(&self) -> Option<Box<dyn StepRunnerData>> {
+        StepRunnerProgram::default().create_data()
+    }
+    pub fn run_setup(
+        &self,
+        gl: &Gl,
+        project: &Project,
+        resource_log_manager: &mut ResourceLogManager,
+        step: &Step,
+        data: &mut Option<Box<dyn StepRunnerData>>,
+    ) {
+        StepRunnerProgram::default().run_setup(gl, project, resource_log_manager, step, data);
+    }
+    pub fn run_render(
+        &self,
+        gl: &Gl,
+        property_values: &HashMap<String, PropertyValue>,
+        builtin_uniforms: &BuiltinUniforms,
+        step: &Step,
+        data: &Option<Box<dyn StepRunnerData>>,
+    ) {
+        let Step::Dispatch { groups, .. } = step else {
+            return;
+        };
+        let sr = StepRunnerProgram::default();
+        if !gl.supports_compute() || !sr.is_linked(data) {
+            return;
+        }
+        sr.run_render(gl, property_values, builtin_uniforms, data);
+        let [x, y, z] = *groups;
+        gl.glDispatchCompute(x, y, z);
+        // the writes are read back by anything, vertex pulls, draws, or the next dispatch
+        gl.glMemoryBarrier(GL_ALL_BARRIER_BITS);
+        gl.check_gl_error(std::file!(), std::line!());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::engine::gl::Gl;
+use crate::engine::gl::GL_ARRAY_BUFFER;
+use crate::engine::gl::GL_ELEMENT_ARRAY_BUFFER;
+use crate::engine::gl::GL_FALSE;
+use crate::engine::gl::GL_FLOAT;
+use crate::engine::gl::GL_LINE_STRIP;
+use crate::engine::gl::GL_POINTS;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::engine::gl::GL_PROGRAM_POINT_SIZE;
+use crate::engine::gl::GL_STATIC_DRAW;
+use crate::engine::gl::GL_TRIANGLES;
+use crate::engine::gl::GL_UNSIGNED_INT;
+use crate::engine::Mesh;
+use crate::engine::Primitive;
+use crate::engine::StepRunnerData;
+use crate::project::Project;
+use crate::project::Resource;
+use crate::project::Step;
+use core::any::Any;
+use std::sync::Arc;
+
+/// Draws a mesh resource, or a generated mesh, with the bound program.
+///
+/// The vertex shader gets the position at location 0, the normal at 1, and the texture coordinate at 2,
+/// `gl_InstanceID` counts the instances. Points are sized by `gl_PointSize`.
+#[derive(Debug, Default)]
+pub struct StepRunnerDrawMesh {}
+
+impl StepRunnerDrawMesh {
+    pub fn create_data(&self) -> Option<Box<dyn StepRunnerData>> {
+        let d = StepRunnerDataDrawMesh::default();
+        Some(Box::new(d))
+    }
+    pub fn run_setup(
+        &self,
+        gl: &Gl,
+        project: &Project,
+        step: &Step,
+        data: &mut Option<Box<dyn StepRunnerData>>,
+    ) {
+        let Some(data) = data
+            .as_mut()
+            .and_then(|d| d.as_any_mut().downcast_mut::<StepRunnerDataDrawMesh>())
+        else {
+            unimplemented!();
+        };
+        let (mesh, instances) = match step {
+            Step::DrawMesh {
+                resource_id,
+                instances,
+                ..
+            } => match project.resource_manager.get(resource_id) {
+                Some(Resource::Mesh(rm)) => match rm.mesh() {
+                    Some(mesh) => (mesh.clone(), *instances),
+                    None => {
+                        eprintln!("Mesh {resource_id} is not loaded");
+                        return;
+                    }
+                },
+                _ => {
+                    eprintln!("Resource {resource_id} is not a mesh");
+                    return;
+                }
+            },
+            Step::Plane {
+                subdivisions,
+                instances,
+                ..
+            } => (Arc::new(Mesh::plane(*subdivisions)), *instances),
+            Step::Sphere {
+                subdivisions,
+                instances,
+                ..
+            } => (Arc::new(Mesh::sphere(*subdivisions)), *instances),
+            Step::LineStrip {
+                points, instances, ..
+            } => (Arc::new(Mesh::line_strip(*points)), *instances),
+            Step::Particles { count, quads, .. } => {
+                let mesh = if *quads {
+                    Mesh::plane(1)
+                } else {
+                    Mesh::point()
+                };
+                (Arc::new(mesh), *count)
+            }
+            _ => return,
+        };
+        data.instances = instances;
+        data.mode = match mesh.primitive() {
+            Primitive::Triangles => GL_TRIANGLES,
+            Primitive::LineStrip => GL_LINE_STRIP,
+            Primitive::Points => GL_POINTS,
+        };
+
+        gl.glGenVertexArrays(1, &mut data.vertex_array_id);
+        gl.glBindVertexArray(data.vertex_array_id);
+
+        let vdata = mesh.interleaved();
+        gl.gen_buffers(1, &mut data.vertex_buffer_id);
+        gl.bind_buffer(GL_ARRAY_BUFFER, data.vertex_buffer_id);
+        gl.buffer_data(
+            GL_ARRAY_BUFFER,
+            (4 * vdata.len()) as isize,
+            vdata.as_ptr() as *const _,
+            GL_STATIC_DRAW,
+        );
+
+        let indices = mesh.indices();
+        gl.gen_buffers(1, &mut data.index_buffer_id);
+        gl.bind_buffer(GL_ELEMENT_ARRAY_BUFFER, data.index_buffer_id);
+        gl.buffer_data(
+            GL_ELEMENT_ARRAY_BUFFER,
+            (4 * indices.len()) as isize,
+            indices.as_ptr() as *const _,
+            GL_STATIC_DRAW,
+        );
+        data.index_count = indices.len() as i32;
+        gl.check_gl_error(std::file!(), std::line!());
+        eprintln!("DrawMesh {mesh:?} x {instances}");
+    }
+    /// Deletes the vertex array and buffers, `data` might belong to another step runner, which is ignored.
+    pub fn run_teardown(&self, gl: &Gl, data: &mut Option<Box<dyn StepRunnerData>>) {
+        let Some(data) = data
+            .as_mut()
+            .and_then(|d| d.as_any_mut().downcast_mut::<StepRunnerDataDrawMesh>())
+        else {
+            return;
+        };
+        if data.vertex_array_id != 0 {
+            gl.glDeleteVertexArrays(1, &data.vertex_array_id);
+        }
+        for buffer in [data.vertex_buffer_id, data.index_buffer_id] {
+            if buffer != 0 {
+                gl.glDeleteBuffers(1, &buffer);
+            }
+        }
+        *data = StepRunnerDataDrawMesh::default();
+    }
+    pub fn run_render(&self, gl: &Gl, data: &Option<Box<dyn StepRunnerData>>) {
+        let Some(data) = data
+            .as_ref()
+            .and_then(|d| d.as_any().downcast_ref::<StepRunnerDataDrawMesh>())
+        else {
+            unimplemented!();
+        };
+        if data.index_count == 0 || data.instances == 0 {
+            return;
+        }
+        gl.glBindVertexArray(data.vertex_array_id);
+        gl.bind_buffer(GL_ARRAY_BUFFER, data.vertex_buffer_id);
+        let stride = (4 * Mesh::STRIDE) as i32;
+        for (index, size, offset) in [(0, 3, 0), (1, 3, 3), (2, 2, 6)] {
+            gl.enable_vertex_attrib_array(index);
+            gl.vertex_attrib_pointer(
+                index,
+                size,
+                GL_FLOAT,
+                GL_FALSE as u8,
+                stride,
+                (4 * offset) as *const _,
+            );
+        }
+        gl.bind_buffer(GL_ELEMENT_ARRAY_BUFFER, data.index_buffer_id);
+        // WebGL always takes the size from the shader
+        #[cfg(not(target_arch = "wasm32"))]
+        if data.mode == GL_POINTS {
+            gl.glEnable(GL_PROGRAM_POINT_SIZE);
+        }
+        gl.glDrawElementsInstanced(
+            data.mode,
+            data.index_count,
+            GL_UNSIGNED_INT,
+            core::ptr::null(),
+            data.instances as i32,
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        if data.mode == GL_POINTS {
+            gl.glDisable(GL_PROGRAM_POINT_SIZE);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct StepRunnerDataDrawMesh {
+    vertex_array_id: u32,
+    vertex_buffer_id: u32,
+    index_buffer_id: u32,
+    index_count: i32,
+    instances: u32,
+    mode: u32,
+}
+
+impl StepRunnerData for StepRunnerDataDrawMesh {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
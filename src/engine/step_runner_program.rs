@@ -1,11 +1,19 @@
 use crate::engine::gl::Gl;
+use crate::engine::gl::GL_COMPUTE_SHADER;
 use crate::engine::gl::GL_FRAGMENT_SHADER;
+use crate::engine::gl::GL_GEOMETRY_SHADER;
 use crate::engine::gl::GL_VERTEX_SHADER;
+use crate::engine::BuiltinUniforms;
+use crate::engine::LinkedProgram;
+use crate::engine::LinkedUniform;
 use crate::engine::Pipeline;
 use crate::engine::ResourceLogManager;
+use crate::engine::ShaderAnnotation;
 use crate::engine::ShaderSource;
 use crate::engine::StepRunnerData;
+use crate::project::ChannelInput;
 use crate::project::Project;
+use crate::project::PropertyValue;
 use crate::project::Resource;
 use crate::project::ResourceId;
 use crate::project::ShaderType;
@@ -43,6 +51,23 @@ impl StepRunnerProgram {
                         s.set_resource_id(resource_id);
                         return Some((String::from("vertex"), s));
                     }
+                    ShaderType::Geometry => {
+                        let mut s = ShaderSource::new(GL_GEOMETRY_SHADER, rt.text().to_string());
+                        s.last_project_version = rt.version();
+                        s.set_resource_id(resource_id);
+                        return Some((String::from("geometry"), s));
+                    }
+                    ShaderType::Compute => {
+                        let mut s = ShaderSource::new(GL_COMPUTE_SHADER, rt.text().to_string());
+                        s.last_project_version = rt.version();
+                        s.set_resource_id(resource_id);
+                        return Some((String::from("compute"), s));
+                    }
+                    ShaderType::Common => {
+                        let mut s = ShaderSource::new(GL_FRAGMENT_SHADER, rt.text().to_string());
+                        s.last_project_version = rt.version();
+                        return Some((String::from("common"), s));
+                    }
                 }
                 /*
                 } else {
@@ -66,7 +91,7 @@ impl StepRunnerProgram {
         data: &mut Option<Box<dyn StepRunnerData>>,
     ) {
         eprintln!("Setting up program from step {step:?}");
-        if let Step::Program { resource_id, .. } = step {
+        if let Step::Program { resource_id, .. } | Step::Dispatch { resource_id, .. } = step {
             if let Some(data) = data {
                 match data.as_any_mut().downcast_mut::<StepRunnerDataProgram>() {
                     Some(data) => {
@@ -74,10 +99,12 @@ impl StepRunnerProgram {
                         let _todo = pipeline.setup(gl);
                         // shader_sources: &mut HashMap<String, ShaderSource>,
                         let mut shader_sources = HashMap::default();
+                        let mut common = String::new();
 
                         if let Some(r) = project.resource_manager.get(resource_id) {
                             match r {
                                 Resource::Program(rp) => {
+                                    data.channels = *rp.channels();
                                     for s in rp.shaders() {
                                         let resource_id = s.resource_id();
                                         if let Some((name, shader_source)) =
@@ -87,7 +114,12 @@ impl StepRunnerProgram {
                                                 s.shader_type(),
                                             )
                                         {
-                                            shader_sources.insert(name, shader_source);
+                                            if name == "common" {
+                                                common.push_str(shader_source.source());
+                                                common.push('\n');
+                                            } else {
+                                                shader_sources.insert(name, shader_source);
+                                            }
                                         } else {
                                             eprintln!(
                                                 "Creating ShaderSource from Resource failed!"
@@ -105,7 +137,11 @@ impl StepRunnerProgram {
                             eprintln!("Could not find program resource {resource_id}");
                         }
 
-                        // project.resource_manager.get(resource_id)
+                        for name in ["fragment", "compute"] {
+                            if let Some(s) = shader_sources.get_mut(name) {
+                                s.set_prelude(common.clone());
+                            }
+                        }
 
                         let _todo = pipeline.rebuild(gl, &mut shader_sources).map_err(|e| {
                             eprintln!("Failed rebuilding pipeline {e:?}");
@@ -113,6 +149,35 @@ impl StepRunnerProgram {
                         });
 
                         gl.check_gl_error(std::file!(), std::line!());
+
+                        // remember the reflected, i.e. active, uniforms, and their annotations
+                        let uniforms = pipeline.uniform_manager().entries();
+                        let shader_annotations = shader_sources
+                            .values()
+                            .flat_map(|ss| ShaderAnnotation::parse(ss.source()))
+                            .filter(|a| uniforms.contains_key(&a.reflected_name()))
+                            .collect();
+                        resource_log_manager.add_linked_program(LinkedProgram {
+                            resource_id: resource_id.clone(),
+                            uniforms: uniforms
+                                .iter()
+                                .map(|(name, u)| LinkedUniform {
+                                    name: name.clone(),
+                                    ttype: *u.ttype(),
+                                    size: u.size(),
+                                })
+                                .collect(),
+                            shader_annotations,
+                        });
+
+                        // map the uniforms to the properties of this program, or the ones they are linked to
+                        project.with_property_manager(|pm| {
+                            data.properties = uniforms
+                                .keys()
+                                .map(|u| (u.clone(), pm.resolve(resource_id, u)))
+                                .collect();
+                        });
+
                         pipeline.bind(gl);
                         data.pipeline = pipeline;
 
@@ -138,11 +203,33 @@ impl StepRunnerProgram {
         }
     }
     pub fn run_teardown(&self, _data: &mut Option<Box<dyn StepRunnerData>>) {}
-    pub fn run_render(&self, gl: &Gl, data: &Option<Box<dyn StepRunnerData>>) {
+    pub fn is_linked(&self, data: &Option<Box<dyn StepRunnerData>>) -> bool {
+        data.as_ref()
+            .and_then(|d| d.as_any().downcast_ref::<StepRunnerDataProgram>())
+            .is_some_and(|d| d.pipeline.is_linked())
+    }
+    pub fn run_render(
+        &self,
+        gl: &Gl,
+        property_values: &HashMap<String, PropertyValue>,
+        builtin_uniforms: &BuiltinUniforms,
+        data: &Option<Box<dyn StepRunnerData>>,
+    ) {
         if let Some(data) = data {
             match data.as_any().downcast_ref::<StepRunnerDataProgram>() {
                 Some(data) => {
                     let _todo = data.pipeline.bind(gl);
+                    for (uniform, property) in data.properties.iter() {
+                        match property_values.get(property) {
+                            Some(PropertyValue::Matrix { matrix }) => {
+                                let m = matrix.to_mat4(builtin_uniforms.aspect());
+                                data.pipeline.set_property_mat4_f32(gl, uniform, &m);
+                            }
+                            Some(value) => data.pipeline.set_property_value(gl, uniform, value),
+                            None => {}
+                        }
+                    }
+                    builtin_uniforms.upload(gl, &data.pipeline, &data.channels);
                 }
                 None => {
                     unimplemented!();
@@ -157,6 +244,9 @@ impl StepRunnerProgram {
 #[derive(Debug, Default)]
 struct StepRunnerDataProgram {
     pipeline: Pipeline,
+    /// The active uniforms, and the properties they are set from.
+    properties: Vec<(String, String)>,
+    channels: [ChannelInput; 4],
 }
 
 impl StepRunnerData for StepRunnerDataProgram {
@@ -0,0 +1,157 @@
+use crate::engine::gl::*;
+use crate::project::BlendMode;
+use crate::project::CullMode;
+use crate::project::DepthTest;
+use crate::project::Step;
+
+/// Sets the fixed function state for the following steps, see [`crate::engine::RenderState`] for the reset.
+#[derive(Debug, Default)]
+pub struct StepRunnerRenderState {}
+
+impl StepRunnerRenderState {
+    /// Applies the state of `step`, `viewport` is the one the flow renders to.
+    ///
+    /// Returns the new viewport for [`Step::Viewport`].
+    pub fn run_render(&self, gl: &Gl, step: &Step, viewport: &[GLint; 4]) -> Option<[GLint; 4]> {
+        match step {
+            Step::Clear { color, depth, .. } => {
+                let mut mask = 0;
+                if let Some([r, g, b, a]) = color {
+                    gl.glClearColor(*r, *g, *b, *a);
+                    mask |= GL_COLOR_BUFFER_BIT;
+                }
+                if let Some(depth) = depth {
+                    gl.glClearDepth(*depth as GLdouble);
+                    mask |= GL_DEPTH_BUFFER_BIT;
+                }
+                if mask != 0 {
+                    Self::clear(gl, mask);
+                }
+            }
+            Step::Blend { mode, .. } => {
+                let (src, dst) = match mode {
+                    BlendMode::Off => {
+                        gl.glDisable(GL_BLEND);
+                        return None;
+                    }
+                    BlendMode::Alpha => (GL_SRC_ALPHA, GL_ONE_MINUS_SRC_ALPHA),
+                    BlendMode::Premultiplied => (GL_ONE, GL_ONE_MINUS_SRC_ALPHA),
+                    BlendMode::Additive => (GL_SRC_ALPHA, GL_ONE),
+                    BlendMode::Multiply => (GL_DST_COLOR, GL_ZERO),
+                };
+                gl.glEnable(GL_BLEND);
+                gl.glBlendEquationSeparate(GL_FUNC_ADD, GL_FUNC_ADD);
+                // the alpha accumulates coverage, like egui does
+                gl.glBlendFuncSeparate(src, dst, GL_ONE, GL_ONE_MINUS_SRC_ALPHA);
+            }
+            Step::Depth { test, write, .. } => {
+                let func = match test {
+                    // writing the depth needs the test enabled
+                    DepthTest::Off if *write => GL_ALWAYS,
+                    DepthTest::Off => 0,
+                    DepthTest::Less => GL_LESS,
+                    DepthTest::LessEqual => GL_LEQUAL,
+                    DepthTest::Greater => GL_GREATER,
+                    DepthTest::GreaterEqual => GL_GEQUAL,
+                    DepthTest::Equal => GL_EQUAL,
+                };
+                if func == 0 {
+                    gl.glDisable(GL_DEPTH_TEST);
+                } else {
+                    gl.glEnable(GL_DEPTH_TEST);
+                    gl.glDepthFunc(func);
+                }
+                gl.glDepthMask(*write as GLboolean);
+            }
+            Step::Cull { mode, .. } => match mode {
+                CullMode::Off => gl.glDisable(GL_CULL_FACE),
+                CullMode::Back | CullMode::Front => {
+                    gl.glEnable(GL_CULL_FACE);
+                    gl.glCullFace(if *mode == CullMode::Back {
+                        GL_BACK
+                    } else {
+                        GL_FRONT
+                    });
+                }
+            },
+            Step::Viewport { rect, .. } => {
+                let rect = Self::viewport_rect(viewport, rect);
+                let [x, y, w, h] = rect;
+                gl.glViewport(x, y, w, h);
+                return Some(rect);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Clears the current viewport only, glClear ignores it, and only respects the scissor box.
+    fn clear(gl: &Gl, mask: GLbitfield) {
+        let mut viewport = [0; 4];
+        gl.glGetIntegerv(GL_VIEWPORT, viewport.as_mut_ptr());
+        let mut scissor_box = [0; 4];
+        gl.glGetIntegerv(GL_SCISSOR_BOX, scissor_box.as_mut_ptr());
+        let scissor_test = gl.glIsEnabled(GL_SCISSOR_TEST) != 0;
+
+        let [x, y, w, h] = if scissor_test {
+            Self::intersect(&viewport, &scissor_box)
+        } else {
+            viewport
+        };
+        gl.glEnable(GL_SCISSOR_TEST);
+        gl.glScissor(x, y, w, h);
+        gl.glClear(mask);
+
+        let [x, y, w, h] = scissor_box;
+        gl.glScissor(x, y, w, h);
+        if !scissor_test {
+            gl.glDisable(GL_SCISSOR_TEST);
+        }
+    }
+
+    /// `rect` as fractions of `viewport`, in pixels.
+    fn viewport_rect(viewport: &[GLint; 4], rect: &[f32; 4]) -> [GLint; 4] {
+        let [vx, vy, vw, vh] = viewport.map(|v| v as f32);
+        let [x, y, w, h] = rect.map(|v| v.clamp(0.0, 1.0));
+        let x0 = (vx + x * vw).round();
+        let y0 = (vy + y * vh).round();
+        let x1 = (vx + (x + w).min(1.0) * vw).round();
+        let y1 = (vy + (y + h).min(1.0) * vh).round();
+        [x0, y0, x1 - x0, y1 - y0].map(|v| v as GLint)
+    }
+
+    fn intersect(a: &[GLint; 4], b: &[GLint; 4]) -> [GLint; 4] {
+        let x0 = a[0].max(b[0]);
+        let y0 = a[1].max(b[1]);
+        let x1 = (a[0] + a[2]).min(b[0] + b[2]);
+        let y1 = (a[1] + a[3]).min(b[1] + b[3]);
+        [x0, y0, (x1 - x0).max(0), (y1 - y0).max(0)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rects_work() {
+        let viewport = [10, 20, 200, 100];
+        assert_eq!(
+            viewport,
+            StepRunnerRenderState::viewport_rect(&viewport, &[0.0, 0.0, 1.0, 1.0])
+        );
+        assert_eq!(
+            [110, 20, 100, 50],
+            StepRunnerRenderState::viewport_rect(&viewport, &[0.5, 0.0, 0.5, 0.5])
+        );
+        // clamped to the viewport
+        assert_eq!(
+            [110, 70, 100, 50],
+            StepRunnerRenderState::viewport_rect(&viewport, &[0.5, 0.5, 1.0, 2.0])
+        );
+        assert_eq!(
+            [50, 50, 50, 0],
+            StepRunnerRenderState::intersect(&[0, 0, 100, 50], &[50, 50, 100, 100])
+        );
+    }
+}
@@ -58,13 +58,7 @@ impl StepRunnerSetUniformF32 {
                         if data.location >= 0 {
                             let mut program: GLint = 0;
                             gl.glGetIntegerv(GL_CURRENT_PROGRAM, &mut program);
-                            let value =
-                                value
-                                    .parse::<f32>()
-                                    .unwrap_or_else(|_| match value.as_ref() {
-                                        "${TIME}" => (flow_vm.time().rem_euclid(2048.0)) as f32, // wrap to avoid precision issues, will _jerk_ after ~0.5h
-                                        _ => 0.0,
-                                    });
+                            let value = flow_vm.resolve_f32(value);
                             //if value != data.value {
                             //eprintln!("Value changed to {value} for {name}");
                             //data.value = value;
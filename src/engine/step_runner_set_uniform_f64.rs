@@ -58,17 +58,17 @@ impl StepRunnerSetUniformF64 {
                         if data.location >= 0 {
                             let mut program: GLint = 0;
                             gl.glGetIntegerv(GL_CURRENT_PROGRAM, &mut program);
-                            let value =
-                                value
-                                    .parse::<f64>()
-                                    .unwrap_or_else(|_| match value.as_ref() {
-                                        "${TIME}" => flow_vm.time(),
-                                        _ => 0.0,
-                                    });
                             //if value != data.value {
                             //eprintln!("Value changed to {value} for {name}");
                             //data.value = value;
-                            gl.glProgramUniform1d(program as u32, data.location, value);
+                            if gl.supports_double() {
+                                let value = flow_vm.resolve_f64(value);
+                                gl.glProgramUniform1d(program as u32, data.location, value);
+                            } else {
+                                // the uniform was translated to a float, so use the wrapped time
+                                let value = flow_vm.resolve_f32(value);
+                                gl.glProgramUniform1f(program as u32, data.location, value);
+                            }
                         // :TODO:
                         //}
                         } else {
@@ -0,0 +1,78 @@
+use crate::engine::gl::*;
+use crate::project::Project;
+use crate::project::Resource;
+use crate::project::ResourceId;
+use std::collections::HashMap;
+
+/// The GPU side of the buffer resources.
+///
+/// Kept across flow rebuilds, so simulations keep their state while the project is edited,
+/// buffers are only cleared when their resource's layout changes, it is reset, or another project is loaded.
+#[derive(Debug, Default)]
+pub struct StorageBuffers {
+    buffers: HashMap<ResourceId, StorageBuffer>,
+    /// The [`Project::load_generation`] the buffers belong to.
+    load_generation: u32,
+}
+
+#[derive(Debug, Default)]
+struct StorageBuffer {
+    buffer: GLuint,
+    binding: u32,
+    version: u32,
+    size: usize,
+}
+
+impl StorageBuffers {
+    /// Creates, clears, and deletes buffers to match the buffer resources of `project`.
+    pub fn sync(&mut self, gl: &Gl, project: &Project) {
+        if !gl.supports_compute() {
+            return;
+        }
+        // resource ids and versions of another project say nothing about these buffers
+        let same_project = self.load_generation == project.load_generation();
+        self.load_generation = project.load_generation();
+        let resources = project.resource_manager.resources();
+        self.buffers.retain(|id, b| {
+            let keep = same_project && matches!(resources.get(id), Some(Resource::Buffer(_)));
+            if !keep {
+                gl.glDeleteBuffers(1, &b.buffer);
+            }
+            keep
+        });
+        for (id, r) in resources.iter() {
+            let Resource::Buffer(rb) = r else {
+                continue;
+            };
+            let b = self.buffers.entry(id.clone()).or_default();
+            if b.buffer != 0 && b.version == rb.version() && b.size == rb.size() {
+                continue;
+            }
+            if b.buffer == 0 {
+                gl.gen_buffers(1, &mut b.buffer);
+            }
+            b.binding = rb.binding();
+            b.version = rb.version();
+            b.size = rb.size();
+
+            let zeros = vec![0u8; b.size];
+            gl.bind_buffer(GL_SHADER_STORAGE_BUFFER, b.buffer);
+            gl.buffer_data(
+                GL_SHADER_STORAGE_BUFFER,
+                b.size as isize,
+                zeros.as_ptr() as *const _,
+                GL_DYNAMIC_COPY,
+            );
+            gl.bind_buffer(GL_SHADER_STORAGE_BUFFER, 0);
+            gl.check_gl_error(std::file!(), std::line!());
+            eprintln!("Buffer {id} {} bytes at binding {}", b.size, b.binding);
+        }
+    }
+
+    /// Binds all buffers to their shader storage block bindings.
+    pub fn bind(&self, gl: &Gl) {
+        for b in self.buffers.values() {
+            gl.glBindBufferBase(GL_SHADER_STORAGE_BUFFER, b.binding, b.buffer);
+        }
+    }
+}
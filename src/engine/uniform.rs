@@ -1,12 +1,17 @@
 use crate::engine::gl::*;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub enum UniformType {
     #[default]
     Unknown,
     Float,
+    Double,
     Vec2Float,
     Vec3Float,
+    Vec4Float,
+    Mat4Float,
+    Int,
+    Sampler2D,
 }
 
 #[derive(Default, Debug)]
@@ -29,12 +34,27 @@ impl Uniform {
     pub fn new_float() -> Self {
         Self::new(UniformType::Float, 1)
     }
+    pub fn new_double() -> Self {
+        Self::new(UniformType::Double, 1)
+    }
     pub fn new_vec2_float() -> Self {
         Self::new(UniformType::Vec2Float, 1)
     }
     pub fn new_vec3_float(size: u8) -> Self {
         Self::new(UniformType::Vec3Float, size)
     }
+    pub fn new_vec4_float() -> Self {
+        Self::new(UniformType::Vec4Float, 1)
+    }
+    pub fn new_mat4_float() -> Self {
+        Self::new(UniformType::Mat4Float, 1)
+    }
+    pub fn new_int() -> Self {
+        Self::new(UniformType::Int, 1)
+    }
+    pub fn new_sampler_2d() -> Self {
+        Self::new(UniformType::Sampler2D, 1)
+    }
     pub fn new(ttype: UniformType, size: u8) -> Self {
         Self {
             location: None,
@@ -50,24 +70,60 @@ impl Uniform {
         self.location = None;
     }
 
-    pub fn set_f32(&mut self, gl: &mut Gl, program: u32, value: f32) {
+    pub fn set_f32(&self, gl: &Gl, program: u32, value: f32) {
         if let Some(l) = self.location {
             gl.glProgramUniform1f(program, l, value);
         }
     }
-    pub fn set_vec2_f32(&mut self, gl: &mut Gl, program: u32, values: &[f32; 2]) {
+    pub fn set_f64(&self, gl: &Gl, program: u32, value: f64) {
+        if let Some(l) = self.location {
+            if gl.supports_double() {
+                gl.glProgramUniform1d(program, l, value);
+            } else {
+                gl.glProgramUniform1f(program, l, value as f32);
+            }
+        }
+    }
+    pub fn set_vec2_f32(&self, gl: &Gl, program: u32, values: &[f32; 2]) {
         if let Some(l) = self.location {
             gl.glProgramUniform2fv(program, l, 1, values.as_ptr());
         }
     }
-    pub fn set_vec3_f32(&mut self, gl: &mut Gl, program: u32, values: &[f32; 3]) {
+    pub fn set_vec3_f32(&self, gl: &Gl, program: u32, values: &[f32; 3]) {
         if let Some(l) = self.location {
             gl.glProgramUniform3fv(program, l, 1, values.as_ptr());
         }
     }
-    pub fn set_vec3_f32_size4(&mut self, gl: &mut Gl, program: u32, values: &[f32; 3 * 4]) {
+    pub fn set_vec4_f32(&self, gl: &Gl, program: u32, values: &[f32; 4]) {
+        if let Some(l) = self.location {
+            gl.glProgramUniform4fv(program, l, 1, values.as_ptr());
+        }
+    }
+    /// Column major, like GLSL's `mat4`.
+    pub fn set_mat4_f32(&self, gl: &Gl, program: u32, values: &[f32; 16]) {
+        if let Some(l) = self.location {
+            gl.glProgramUniformMatrix4fv(program, l, 1, GL_FALSE as GLboolean, values.as_ptr());
+        }
+    }
+    /// Also sets the texture unit of samplers.
+    pub fn set_i32(&self, gl: &Gl, program: u32, value: i32) {
+        if let Some(l) = self.location {
+            gl.glProgramUniform1i(program, l, value);
+        }
+    }
+    pub fn set_vec3_f32_size4(&self, gl: &Gl, program: u32, values: &[f32; 3 * 4]) {
         if let Some(l) = self.location {
             gl.glProgramUniform3fv(program, l, 4, values.as_ptr());
         }
     }
+    pub fn set_vec3_f32_array(&self, gl: &Gl, program: u32, values: &[[f32; 3]]) {
+        if let Some(l) = self.location {
+            gl.glProgramUniform3fv(
+                program,
+                l,
+                values.len() as GLsizei,
+                values.as_ptr() as *const f32,
+            );
+        }
+    }
 }
@@ -27,6 +27,9 @@ impl UniformManager {
         &self.entries
     }
 
+    pub fn get(&self, name: &str) -> Option<&Uniform> {
+        self.entries.get(name)
+    }
     pub fn get_mut(&mut self, name: &str) -> Option<&mut Uniform> {
         self.entries.get_mut(name)
     }
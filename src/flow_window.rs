@@ -1,5 +1,8 @@
 use crate::command::FlowCommand;
 use crate::command_queue::COMMAND_QUEUE;
+use crate::project::Feedback;
+use crate::project::FeedbackFilter;
+use crate::project::FeedbackWrap;
 use crate::project::GridPos;
 use crate::project::GridRect;
 use crate::project::Step;
@@ -45,7 +48,7 @@ impl From<&FlowWindow> for FlowWindowSave {
 }
 
 impl FlowWindow {
-    fn update_sidepanel(&mut self, ui: &mut egui::Ui, _state: &mut State) {
+    fn update_sidepanel(&mut self, ui: &mut egui::Ui, state: &mut State) {
         egui::SidePanel::left("left_panel")
             .resizable(false)
             //.exact_height(16.0)
@@ -149,8 +152,49 @@ impl FlowWindow {
                 //self.target_grid_pos = new_target_grid_pos;
                 self.target_grid_rect = new_target_grid_rect;
                 self.selected_grid_rect = new_selected_grid_rect;
+
+                ui.separator();
+                Self::update_feedback(ui, state);
             });
     }
+
+    fn update_feedback(ui: &mut egui::Ui, state: &mut State) {
+        let mut feedback = state.project.flow().feedback().copied();
+        let mut enabled = feedback.is_some();
+        if ui
+            .checkbox(&mut enabled, "Feedback")
+            .on_hover_text("Renders the flow into a double buffered texture, programs read the previous frame as `tFeedback`")
+            .changed()
+        {
+            feedback = enabled.then(Feedback::default);
+        }
+        if let Some(feedback) = &mut feedback {
+            egui::ComboBox::from_label("Filter")
+                .selected_text(format!("{}", feedback.filter))
+                .show_ui(ui, |ui| {
+                    for f in FeedbackFilter::ALL {
+                        ui.selectable_value(&mut feedback.filter, f, format!("{f}"));
+                    }
+                });
+            egui::ComboBox::from_label("Wrap")
+                .selected_text(format!("{}", feedback.wrap))
+                .show_ui(ui, |ui| {
+                    for w in FeedbackWrap::ALL {
+                        ui.selectable_value(&mut feedback.wrap, w, format!("{w}"));
+                    }
+                });
+            if ui
+                .button("Reset Feedback")
+                .on_hover_text("Clears the previous frame")
+                .clicked()
+            {
+                let _ = COMMAND_QUEUE.send(Command::ResetFeedback);
+            }
+        }
+        if feedback.as_ref() != state.project.flow().feedback() {
+            state.project.with_flow_mut(|f| f.set_feedback(feedback));
+        }
+    }
 }
 impl Window for FlowWindow {
     fn name(&self) -> &str {
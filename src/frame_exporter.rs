@@ -0,0 +1,49 @@
+use crate::engine::CapturedFrame;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct FrameExporter {
+    folder: PathBuf,
+    frame_count: u32,
+    fps: f64,
+    frame: u32,
+}
+
+impl FrameExporter {
+    pub fn new(folder: PathBuf, frame_count: u32, fps: f64) -> Self {
+        Self {
+            folder,
+            frame_count,
+            fps,
+            frame: 0,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.frame >= self.frame_count
+    }
+
+    /// The time of the frame that will be captured next.
+    pub fn time(&self) -> f64 {
+        self.frame as f64 / self.fps
+    }
+
+    pub fn save_frame(&mut self, captured_frame: &CapturedFrame) -> Result<()> {
+        let mut filename = self.folder.clone();
+        filename.push(format!("frame_{:05}.png", self.frame));
+
+        let image = image::RgbaImage::from_raw(
+            captured_frame.width,
+            captured_frame.height,
+            captured_frame.pixels.clone(),
+        )
+        .ok_or_else(|| eyre!("Captured frame has wrong size"))?;
+        image.save(&filename)?;
+        eprintln!("Exported {filename:?}");
+
+        self.frame += 1;
+        Ok(())
+    }
+}
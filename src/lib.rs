@@ -3,6 +3,13 @@
 mod app;
 pub use app::TemplateApp;
 
+mod cli_options;
+pub use cli_options::CliMode;
+pub use cli_options::CliOptions;
+pub use cli_options::USAGE;
+mod frame_exporter;
+use frame_exporter::FrameExporter;
+
 mod engine;
 // mod rotating_triangle;
 
@@ -17,12 +24,18 @@ use mc_guffin_container::McGuffinContainer;
 mod state;
 
 mod flow_window;
+mod mc_guffin_gizmos;
 mod mc_guffin_window;
 mod performance_window;
+mod presets_window;
 mod project_window;
 mod properties_window;
+mod remote_window;
 mod resources_window;
+mod script_window;
 mod shaders_window;
+mod sync_window;
+mod transport_window;
 mod window;
 mod window_manager;
 use window_manager::WindowManager;
@@ -33,20 +46,34 @@ mod property_ui;
 mod property_ui_value;
 use property_ui_value::PropertyUiValue;
 mod property_ui_value_f32;
+mod property_ui_value_f64;
+mod property_ui_value_gradient;
+mod property_ui_value_matrix;
 mod property_ui_value_vec2_f32;
 mod property_ui_value_vec3_f32;
 mod property_ui_value_vec3_f32_size4;
 
 mod project;
+pub use project::Project;
 
+mod midi_input;
+mod osc_message;
+mod osc_server;
 mod path_helper;
+mod rocket_client;
+mod script_runner;
+pub use script_runner::ScriptRunner;
 mod time_series;
 
 mod step_editor;
 mod step_editor_ui;
 use step_editor::StepEditor;
+mod step_editor_dispatch;
+mod step_editor_draw_mesh;
+mod step_editor_geometry;
 mod step_editor_label;
 mod step_editor_program;
+mod step_editor_render_state;
 mod step_editor_scratch;
 mod step_editor_set_uniform_f32;
 mod step_editor_set_uniform_f64;
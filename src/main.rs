@@ -4,24 +4,144 @@
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
+    use eframe_template::CliMode;
+    use eframe_template::CliOptions;
+    use std::sync::atomic::AtomicI32;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
+    let cli_options = match CliOptions::from_env() {
+        Ok(cli_options) => cli_options,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", eframe_template::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match cli_options.mode() {
+        CliMode::Help => {
+            println!("{}", eframe_template::USAGE);
+            return Ok(());
+        }
+        CliMode::Validate => {
+            std::process::exit(validate(&cli_options));
+        }
+        CliMode::Script { file, save } => {
+            std::process::exit(run_script(&cli_options, file, *save));
+        }
+        CliMode::ExportFrames { folder, .. } => {
+            if let Err(e) = std::fs::create_dir_all(folder) {
+                eprintln!("Failed creating {folder:?} -> {e}");
+                std::process::exit(1);
+            }
+        }
+        CliMode::Editor => {}
+    }
+
+    let inner_size = cli_options.window_size().unwrap_or([400.0, 300.0]);
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([400.0, 300.0])
+            .with_inner_size(inner_size)
             .with_min_inner_size([300.0, 220.0])
+            .with_fullscreen(cli_options.fullscreen())
             .with_icon(
                 // NOTE: Adding an icon is optional
                 eframe::icon_data::from_png_bytes(&include_bytes!("../assets/icon-256.png")[..])
                     .unwrap(),
             ),
+        // for `Step::Depth` when rendering directly
+        depth_buffer: 24,
         ..Default::default()
     };
+    let exit_code = Arc::new(AtomicI32::new(0));
+    let app_exit_code = Arc::clone(&exit_code);
     eframe::run_native(
         "SixtyFour-rs",
         native_options,
-        Box::new(|cc| Box::new(eframe_template::TemplateApp::new(cc))),
-    )
+        Box::new(move |cc| {
+            Box::new(eframe_template::TemplateApp::new_with_cli_options(
+                cc,
+                &cli_options,
+                app_exit_code,
+            ))
+        }),
+    )?;
+
+    match exit_code.load(Ordering::Relaxed) {
+        0 => Ok(()),
+        exit_code => std::process::exit(exit_code),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn validate(cli_options: &eframe_template::CliOptions) -> i32 {
+    let Some(project_path) = cli_options.project_path() else {
+        eprintln!("--validate needs a project path");
+        return 2;
+    };
+    let project = match eframe_template::Project::try_load(project_path) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Failed loading project {project_path:?} -> {e}");
+            return 1;
+        }
+    };
+
+    let problems = project.validate(cli_options.start_label().unwrap_or("start"));
+    for p in problems.iter() {
+        eprintln!("{p}");
+    }
+    if problems.is_empty() {
+        println!("{project_path:?} is valid");
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_script(
+    cli_options: &eframe_template::CliOptions,
+    file: &std::path::Path,
+    save: bool,
+) -> i32 {
+    let Some(project_path) = cli_options.project_path() else {
+        eprintln!("--script needs a project path");
+        return 2;
+    };
+    let script = match std::fs::read_to_string(file) {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("Failed reading script {file:?} -> {e}");
+            return 2;
+        }
+    };
+    let mut project = match eframe_template::Project::try_load(project_path) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Failed loading project {project_path:?} -> {e}");
+            return 1;
+        }
+    };
+
+    let mut script_runner = eframe_template::ScriptRunner::default();
+    let result = script_runner.run(&mut project, &script);
+    for line in script_runner.output() {
+        println!("{line}");
+    }
+    if let Err(e) = result {
+        eprintln!("Script {file:?} failed -> {e}");
+        return 1;
+    }
+    if save {
+        if let Err(e) = project.save(project_path) {
+            eprintln!("Failed saving project {project_path:?} -> {e}");
+            return 1;
+        }
+    }
+    0
 }
 
 // When compiling to web using trunk:
@@ -30,7 +150,11 @@ fn main() {
     // Redirect `log` message to `console.log` and friends:
     eframe::WebLogger::init(log::LevelFilter::Debug).ok();
 
-    let web_options = eframe::WebOptions::default();
+    let web_options = eframe::WebOptions {
+        // for `Step::Depth` when rendering directly
+        depth_buffer: 24,
+        ..Default::default()
+    };
 
     wasm_bindgen_futures::spawn_local(async {
         eframe::WebRunner::new()
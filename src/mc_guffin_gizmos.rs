@@ -0,0 +1,204 @@
+use crate::project::Gizmo;
+use crate::project::Project;
+use crate::project::PropertyManager;
+use crate::project::PropertyValue;
+use egui::Color32;
+use egui::Pos2;
+use egui::Rect;
+use egui::Stroke;
+
+/// Overlays on the McGuffin preview, to edit properties in the shader's coordinates, see [`Gizmo`].
+#[derive(Debug, Default)]
+pub struct McGuffinGizmos {}
+
+impl McGuffinGizmos {
+    const HANDLE_RADIUS: f32 = 6.0;
+    const TRACKBALL_RADIUS: f32 = 24.0;
+
+    /// Normalized coordinates, `-1..1` with y up, for a position in `rect`.
+    pub fn to_normalized(rect: Rect, pos: Pos2) -> [f32; 2] {
+        let np = ((pos - rect.min) / rect.size()) * egui::Vec2::new(2.0, -2.0)
+            + egui::Vec2::new(-1.0, 1.0);
+        [np.x, np.y]
+    }
+    pub fn to_screen(rect: Rect, [x, y]: [f32; 2]) -> Pos2 {
+        egui::pos2(
+            rect.min.x + (x + 1.0) * 0.5 * rect.width(),
+            rect.min.y + (1.0 - y) * 0.5 * rect.height(),
+        )
+    }
+
+    /// Draws the gizmos of the properties used by the flow over `rect`, and applies drags to them.
+    pub fn update(&mut self, ui: &mut egui::Ui, rect: Rect, project: &mut Project) {
+        let mut gizmos = Vec::default();
+        project.with_property_manager(|pm| {
+            for (name, p) in pm.entries().iter() {
+                if pm.programs_using(name).is_empty() {
+                    continue;
+                }
+                if let Some(gizmo) = Gizmo::resolve(name, p) {
+                    gizmos.push((name.clone(), gizmo, p.value().clone()));
+                }
+            }
+        });
+
+        let painter = ui.painter_at(rect);
+        let mut trackballs = 0;
+        let mut changed = Vec::default();
+        for (name, gizmo, value) in gizmos {
+            let label = PropertyManager::split_scoped_name(&name)
+                .map(|(_, u)| u)
+                .unwrap_or(&name)
+                .to_string();
+            let id = ui.id().with(("gizmo", &name));
+            let value = match (gizmo, value) {
+                (Gizmo::Position, PropertyValue::Vec2F32 { values }) => {
+                    let pos = Self::to_screen(rect, values);
+                    let r = Self::handle(ui, &painter, id, pos, &label);
+                    r.dragged()
+                        .then(|| r.interact_pointer_pos())
+                        .flatten()
+                        .map(|p| PropertyValue::Vec2F32 {
+                            values: Self::to_normalized(rect, p),
+                        })
+                }
+                (Gizmo::Radius, PropertyValue::F32 { value }) => {
+                    let points = (0..64)
+                        .map(|i| {
+                            let a = i as f32 / 64.0 * core::f32::consts::TAU;
+                            Self::to_screen(rect, [value * a.cos(), value * a.sin()])
+                        })
+                        .collect();
+                    painter.add(egui::Shape::closed_line(
+                        points,
+                        Stroke::new(1.5_f32, Color32::YELLOW),
+                    ));
+                    let pos = Self::to_screen(rect, [value, 0.0]);
+                    let r = Self::handle(ui, &painter, id, pos, &label);
+                    r.dragged()
+                        .then(|| r.interact_pointer_pos())
+                        .flatten()
+                        .map(|p| {
+                            let [x, y] = Self::to_normalized(rect, p);
+                            PropertyValue::F32 {
+                                value: (x * x + y * y).sqrt(),
+                            }
+                        })
+                }
+                (Gizmo::Direction, PropertyValue::Vec3F32 { values }) => {
+                    let center = rect.left_bottom()
+                        + egui::vec2(
+                            (2 * trackballs + 1) as f32 * (Self::TRACKBALL_RADIUS + 8.0),
+                            -Self::TRACKBALL_RADIUS - 16.0,
+                        );
+                    trackballs += 1;
+                    Self::trackball(ui, &painter, id, center, values, &label)
+                        .map(|values| PropertyValue::Vec3F32 { values })
+                }
+                _ => None,
+            };
+            if let Some(value) = value {
+                changed.push((name, value));
+            }
+        }
+
+        if !changed.is_empty() {
+            project.with_property_manager_mut(|pm| {
+                for (name, value) in changed.iter() {
+                    if let Some(p) = pm.get_mut(name) {
+                        *p.value_mut() = value.clone();
+                    }
+                }
+            });
+        }
+    }
+
+    fn handle(
+        ui: &egui::Ui,
+        painter: &egui::Painter,
+        id: egui::Id,
+        pos: Pos2,
+        label: &str,
+    ) -> egui::Response {
+        let size = egui::Vec2::splat(2.0 * Self::HANDLE_RADIUS + 4.0);
+        let r = ui
+            .interact(Rect::from_center_size(pos, size), id, egui::Sense::drag())
+            .on_hover_text(label);
+        let color = if r.hovered() || r.dragged() {
+            Color32::WHITE
+        } else {
+            Color32::YELLOW
+        };
+        painter.circle(
+            pos,
+            Self::HANDLE_RADIUS,
+            color,
+            Stroke::new(1.0_f32, Color32::BLACK),
+        );
+        painter.text(
+            pos + egui::vec2(Self::HANDLE_RADIUS + 2.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            label,
+            egui::FontId::monospace(10.0),
+            Color32::WHITE,
+        );
+        r
+    }
+
+    /// Dragging turns the direction, horizontally around the y axis, vertically towards it.
+    fn trackball(
+        ui: &egui::Ui,
+        painter: &egui::Painter,
+        id: egui::Id,
+        center: Pos2,
+        direction: [f32; 3],
+        label: &str,
+    ) -> Option<[f32; 3]> {
+        let radius = Self::TRACKBALL_RADIUS;
+        let r = ui
+            .interact(
+                Rect::from_center_size(center, egui::Vec2::splat(2.0 * radius)),
+                id,
+                egui::Sense::drag(),
+            )
+            .on_hover_text(format!(
+                "{label} {:.3}, {:.3}, {:.3}",
+                direction[0], direction[1], direction[2]
+            ));
+        let color = if r.hovered() || r.dragged() {
+            Color32::WHITE
+        } else {
+            Color32::YELLOW
+        };
+        painter.circle(
+            center,
+            radius,
+            Color32::from_black_alpha(96),
+            Stroke::new(1.0_f32, color),
+        );
+        let [x, y, z] = direction;
+        let length = (x * x + y * y + z * z).sqrt().max(f32::EPSILON);
+        let tip = center + egui::vec2(x, -y) / length * radius;
+        painter.line_segment([center, tip], Stroke::new(2.0_f32, color));
+        // filled when pointing towards the viewer
+        if z >= 0.0 {
+            painter.circle_filled(tip, 3.0, color);
+        } else {
+            painter.circle_stroke(tip, 3.0, Stroke::new(1.0_f32, color));
+        }
+        painter.text(
+            center + egui::vec2(0.0, radius + 2.0),
+            egui::Align2::CENTER_TOP,
+            label,
+            egui::FontId::monospace(10.0),
+            Color32::WHITE,
+        );
+        if r.dragged() {
+            let delta = r.drag_delta() / radius;
+            if delta != egui::Vec2::ZERO {
+                return Some(Gizmo::rotate_direction(direction, delta.x, -delta.y));
+            }
+        }
+        None
+    }
+}
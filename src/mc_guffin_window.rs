@@ -1,4 +1,7 @@
 use crate::command_queue::COMMAND_QUEUE;
+use crate::engine::BuiltinUniforms;
+use crate::engine::PreviewInput;
+use crate::mc_guffin_gizmos::McGuffinGizmos;
 use crate::project::PropertyValue;
 use crate::state::State;
 use crate::window::Window;
@@ -7,12 +10,25 @@ use color_eyre::Result;
 use egui::Key;
 use egui::Rect;
 
-#[derive(Default)]
 pub struct McGuffinWindow {
     is_open: bool,
     was_fullscreen: bool,
 
     previous_rect: Option<Rect>,
+    show_gizmos: bool,
+    gizmos: McGuffinGizmos,
+}
+
+impl Default for McGuffinWindow {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            was_fullscreen: false,
+            previous_rect: None,
+            show_gizmos: true,
+            gizmos: McGuffinGizmos::default(),
+        }
+    }
 }
 
 impl core::fmt::Debug for McGuffinWindow {
@@ -28,6 +44,15 @@ struct McGuffinWindowSave {
 
     #[serde(default)]
     previous_rect: Option<Rect>,
+
+    #[serde(default = "McGuffinWindowSave::default_show_gizmos")]
+    show_gizmos: bool,
+}
+
+impl McGuffinWindowSave {
+    fn default_show_gizmos() -> bool {
+        true
+    }
 }
 
 impl From<&McGuffinWindow> for McGuffinWindowSave {
@@ -35,6 +60,7 @@ impl From<&McGuffinWindow> for McGuffinWindowSave {
         Self {
             is_open: mw.is_open,
             previous_rect: mw.previous_rect.clone(),
+            show_gizmos: mw.show_gizmos,
         }
     }
 }
@@ -59,6 +85,7 @@ impl Window for McGuffinWindow {
 
         self.is_open = save.is_open;
         self.previous_rect = save.previous_rect.take();
+        self.show_gizmos = save.show_gizmos;
     }
 
     fn update(&mut self, ctx: &egui::Context, state: &mut State) {
@@ -139,28 +166,58 @@ impl McGuffinWindow {
             self.previous_rect = Some( rect );
         }
         */
+        // pixels from the bottom left, like `gl_FragCoord`
+        let active = sense.hovered() || sense.dragged() || state.mc_guffin_is_fullscreen;
+        let input = ui.input(|i| PreviewInput {
+            pointer: i.pointer.latest_pos().filter(|_| active).map(|p| {
+                [
+                    (p.x - rect.min.x) * i.pixels_per_point,
+                    (rect.max.y - p.y) * i.pixels_per_point,
+                ]
+            }),
+            pointer_down: sense.is_pointer_button_down_on(),
+            keys_down: if active {
+                BuiltinUniforms::keys_down(i)
+            } else {
+                Vec::default()
+            },
+        });
         if let Some(mc_guffin) = state.mc_guffin_cloned() {
+            mc_guffin.lock().set_input(&input);
             let callback = egui::PaintCallback {
                 rect,
                 callback: std::sync::Arc::new(eframe::egui_glow::CallbackFn::new(
-                    move |_info, painter| {
-                        mc_guffin.lock().paint(painter.gl());
+                    move |info, painter| {
+                        let clip = info.clip_rect_in_pixels();
+                        let mut mg = mc_guffin.lock();
+                        mg.set_viewport([
+                            clip.left_px,
+                            clip.from_bottom_px,
+                            clip.width_px,
+                            clip.height_px,
+                        ]);
+                        mg.paint(painter.gl());
                     },
                 )),
             };
             ui.painter().add(callback);
         }
+        sense.context_menu(|ui| {
+            ui.checkbox(&mut self.show_gizmos, "Show Gizmos")
+                .on_hover_text("Handles to edit positions, directions, and radii on the preview");
+        });
+        if self.show_gizmos {
+            self.gizmos.update(ui, rect, &mut state.project);
+        }
         if let Some(click_pos) = sense.interact_pointer_pos() {
-            let rs = rect.max - rect.min;
-            let np = ((click_pos - rect.min) / rs) * egui::Vec2::new(2.0, -2.0)
-                + egui::Vec2::new(-1.0, 1.0);
+            let [x, y] = McGuffinGizmos::to_normalized(rect, click_pos);
 
             state.project.with_property_manager_mut(|pm| {
-                if let Some(p) = pm.get_mut("fMouseClick") {
+                for p in pm.uniform_entries_mut("fMouseClick") {
                     match p.value_mut() {
                         PropertyValue::Vec2F32 { ref mut values } => {
-                            values[0] = np.x;
-                            values[1] = np.y;
+                            values[0] = x;
+                            values[1] = y;
                         }
                         _ => {}
                     }
@@ -168,16 +225,14 @@ impl McGuffinWindow {
             });
         }
         if let Some(click_pos) = sense.hover_pos() {
-            let rs = rect.max - rect.min;
-            let np = ((click_pos - rect.min) / rs) * egui::Vec2::new(2.0, -2.0)
-                + egui::Vec2::new(-1.0, 1.0);
+            let [x, y] = McGuffinGizmos::to_normalized(rect, click_pos);
 
             state.project.with_property_manager_mut(|pm| {
-                if let Some(p) = pm.get_mut("fMouseHover") {
+                for p in pm.uniform_entries_mut("fMouseHover") {
                     match p.value_mut() {
                         PropertyValue::Vec2F32 { ref mut values } => {
-                            values[0] = np.x;
-                            values[1] = np.y;
+                            values[0] = x;
+                            values[1] = y;
                         }
                         _ => {}
                     }
@@ -0,0 +1,177 @@
+use crate::project::MidiSource;
+use std::sync::mpsc::Receiver;
+
+/// A control change or note, with the value normalized to `0..1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MidiEvent {
+    pub source: MidiSource,
+    pub value: f32,
+}
+
+impl MidiEvent {
+    /// Parses a raw MIDI message, everything but control changes and notes is ignored.
+    pub fn parse(message: &[u8]) -> Option<Self> {
+        let (&status, data) = message.split_first()?;
+        let channel = status & 0x0f;
+        let (source, value) = match (status & 0xf0, data) {
+            (0xb0, [controller, value, ..]) => (
+                MidiSource::ControlChange {
+                    channel,
+                    controller: *controller,
+                },
+                *value,
+            ),
+            (0x90, [note, velocity, ..]) => (
+                MidiSource::Note {
+                    channel,
+                    note: *note,
+                },
+                *velocity,
+            ),
+            (0x80, [note, _, ..]) => (
+                MidiSource::Note {
+                    channel,
+                    note: *note,
+                },
+                0,
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            source,
+            value: value as f32 / 127.0,
+        })
+    }
+}
+
+/// Receives raw MIDI messages, from a device, or from any other sender, e.g. a recording.
+#[cfg_attr(
+    not(all(feature = "midi", not(target_arch = "wasm32"))),
+    allow(dead_code)
+)]
+pub struct MidiInput {
+    name: String,
+    receiver: Receiver<Vec<u8>>,
+    #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+    _connection: Option<midir::MidiInputConnection<()>>,
+}
+
+impl core::fmt::Debug for MidiInput {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MidiInput")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl MidiInput {
+    #[cfg_attr(
+        not(all(feature = "midi", not(target_arch = "wasm32"))),
+        allow(dead_code)
+    )]
+    pub fn from_receiver(name: &str, receiver: Receiver<Vec<u8>>) -> Self {
+        Self {
+            name: name.to_string(),
+            receiver,
+            #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+            _connection: None,
+        }
+    }
+
+    #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+    pub fn port_names() -> Vec<String> {
+        let Ok(input) = midir::MidiInput::new("sixtyfour") else {
+            return Vec::default();
+        };
+        input
+            .ports()
+            .iter()
+            .filter_map(|p| input.port_name(p).ok())
+            .collect()
+    }
+
+    #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+    pub fn connect(port_name: &str) -> color_eyre::Result<Self> {
+        use color_eyre::eyre::eyre;
+
+        let input = midir::MidiInput::new("sixtyfour")?;
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|p| input.port_name(p).ok().as_deref() == Some(port_name))
+            .ok_or_else(|| eyre!("MIDI port {port_name} not found"))?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let connection = input
+            .connect(
+                &port,
+                "sixtyfour-input",
+                move |_stamp, message, _| {
+                    let _ = sender.send(message.to_vec());
+                },
+                (),
+            )
+            .map_err(|e| eyre!("Failed connecting to {port_name}: {e}"))?;
+
+        let mut mi = Self::from_receiver(port_name, receiver);
+        mi._connection = Some(connection);
+        Ok(mi)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the events received since the last poll.
+    pub fn poll(&mut self) -> Vec<MidiEvent> {
+        self.receiver
+            .try_iter()
+            .filter_map(|m| MidiEvent::parse(&m))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_stream_works() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut mi = MidiInput::from_receiver("recording", receiver);
+        for m in [
+            vec![0xb1, 7, 127],
+            vec![0xf8],
+            vec![0x90, 60, 64],
+            vec![0x80, 60, 64],
+            vec![0xb0, 1],
+        ] {
+            sender.send(m).unwrap();
+        }
+
+        let note = MidiSource::Note {
+            channel: 0,
+            note: 60,
+        };
+        assert_eq!(
+            vec![
+                MidiEvent {
+                    source: MidiSource::ControlChange {
+                        channel: 1,
+                        controller: 7
+                    },
+                    value: 1.0
+                },
+                MidiEvent {
+                    source: note,
+                    value: 64.0 / 127.0
+                },
+                MidiEvent {
+                    source: note,
+                    value: 0.0
+                },
+            ],
+            mi.poll()
+        );
+        assert!(mi.poll().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Bool(bool),
+    Nil,
+}
+
+impl OscArg {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(v) => Some(*v as f64),
+            Self::Long(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v as f64),
+            Self::Double(v) => Some(*v),
+            Self::Bool(v) => Some(if *v { 1.0 } else { 0.0 }),
+            Self::String(s) => s.parse().ok(),
+            Self::Nil => None,
+        }
+    }
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded OSC 1.0 message, bundles are flattened into their messages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let d = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| eyre!("OSC packet too short"))?;
+        self.pos += len;
+        Ok(d)
+    }
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into()?))
+    }
+    /// Strings are zero terminated, and padded to 4 bytes.
+    fn string(&mut self) -> Result<String> {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let len = rest
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| eyre!("Unterminated OSC string"))?;
+        let s = core::str::from_utf8(&rest[..len])?.to_string();
+        self.pos += (len + 4) & !3;
+        Ok(s)
+    }
+    fn is_done(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+}
+
+impl OscMessage {
+    pub fn decode_packet(data: &[u8]) -> Result<Vec<OscMessage>> {
+        let mut messages = Vec::default();
+        Self::decode_into(data, &mut messages)?;
+        Ok(messages)
+    }
+
+    fn decode_into(data: &[u8], messages: &mut Vec<OscMessage>) -> Result<()> {
+        let mut r = Reader { data, pos: 0 };
+        let address = r.string()?;
+        if address == "#bundle" {
+            let _timetag = r.u64()?;
+            while !r.is_done() {
+                let size = r.u32()? as usize;
+                Self::decode_into(r.take(size)?, messages)?;
+            }
+            return Ok(());
+        }
+        if !address.starts_with('/') {
+            return Err(eyre!("Invalid OSC address '{address}'"));
+        }
+
+        // very old senders omit the type tags
+        let tags = if r.is_done() {
+            String::from(",")
+        } else {
+            r.string()?
+        };
+        let tags = tags
+            .strip_prefix(',')
+            .ok_or_else(|| eyre!("Invalid OSC type tags '{tags}'"))?;
+        let mut args = Vec::with_capacity(tags.len());
+        for t in tags.chars() {
+            let arg = match t {
+                'i' => OscArg::Int(r.u32()? as i32),
+                'h' => OscArg::Long(r.u64()? as i64),
+                'f' => OscArg::Float(f32::from_bits(r.u32()?)),
+                'd' => OscArg::Double(f64::from_bits(r.u64()?)),
+                's' | 'S' => OscArg::String(r.string()?),
+                'T' => OscArg::Bool(true),
+                'F' => OscArg::Bool(false),
+                'N' | 'I' => OscArg::Nil,
+                'b' => {
+                    let size = r.u32()? as usize;
+                    r.take((size + 3) & !3)?;
+                    OscArg::Nil
+                }
+                o => return Err(eyre!("Unsupported OSC type tag '{o}'")),
+            };
+            args.push(arg);
+        }
+        messages.push(OscMessage { address, args });
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        fn push_string(data: &mut Vec<u8>, s: &str) {
+            data.extend_from_slice(s.as_bytes());
+            let pad = 4 - s.len() % 4;
+            data.extend(core::iter::repeat_n(0, pad));
+        }
+        let mut data = Vec::default();
+        push_string(&mut data, &self.address);
+        let tags: String = core::iter::once(',')
+            .chain(self.args.iter().map(|a| match a {
+                OscArg::Int(_) => 'i',
+                OscArg::Long(_) => 'h',
+                OscArg::Float(_) => 'f',
+                OscArg::Double(_) => 'd',
+                OscArg::String(_) => 's',
+                OscArg::Bool(true) => 'T',
+                OscArg::Bool(false) => 'F',
+                OscArg::Nil => 'N',
+            }))
+            .collect();
+        push_string(&mut data, &tags);
+        for a in self.args.iter() {
+            match a {
+                OscArg::Int(v) => data.extend_from_slice(&v.to_be_bytes()),
+                OscArg::Long(v) => data.extend_from_slice(&v.to_be_bytes()),
+                OscArg::Float(v) => data.extend_from_slice(&v.to_be_bytes()),
+                OscArg::Double(v) => data.extend_from_slice(&v.to_be_bytes()),
+                OscArg::String(s) => push_string(&mut data, s),
+                OscArg::Bool(_) | OscArg::Nil => {}
+            }
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_works() {
+        let m = OscMessage {
+            address: "/property/fSpeed".into(),
+            args: vec![
+                OscArg::Float(0.5),
+                OscArg::Int(-3),
+                OscArg::String("abcd".into()),
+                OscArg::Double(2.0),
+                OscArg::Bool(true),
+            ],
+        };
+        let data = m.encode();
+        assert_eq!(0, data.len() % 4);
+        assert_eq!(vec![m], OscMessage::decode_packet(&data).unwrap());
+    }
+
+    #[test]
+    fn bundles_are_flattened() {
+        let a = OscMessage {
+            address: "/a".into(),
+            args: vec![OscArg::Int(1)],
+        };
+        let b = OscMessage {
+            address: "/b".into(),
+            args: vec![],
+        };
+        let mut data = Vec::default();
+        data.extend_from_slice(b"#bundle\0");
+        data.extend_from_slice(&1u64.to_be_bytes());
+        for m in [&a, &b] {
+            let e = m.encode();
+            data.extend_from_slice(&(e.len() as u32).to_be_bytes());
+            data.extend_from_slice(&e);
+        }
+        assert_eq!(vec![a, b], OscMessage::decode_packet(&data).unwrap());
+
+        assert!(OscMessage::decode_packet(b"nope\0\0\0\0").is_err());
+        assert!(OscMessage::decode_packet(&data[..data.len() - 2]).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::command::TransportCommand;
+use crate::command_queue::COMMAND_QUEUE;
+use crate::osc_message::OscArg;
+use crate::osc_message::OscMessage;
+use crate::Command;
+use color_eyre::Result;
+use std::net::SocketAddr;
+use std::net::UdpSocket;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Receives OSC messages over UDP, and sends them as [`Command`]s to the [`COMMAND_QUEUE`].
+///
+/// - `/property/<name> <values...>` sets the property
+/// - `/flow/goto <label>` starts the flow at the label
+/// - `/transport/play`, `/transport/pause`, `/transport/toggle`
+/// - `/transport/step <frames>`, `/transport/speed <speed>`, `/transport/seek <seconds>`
+/// - `/fullscreen [0|1]` toggles, leaves, or enters fullscreen
+/// - `/ping` is answered with `/pong`
+#[derive(Debug)]
+pub struct OscServer {
+    local_addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    message_count: Arc<AtomicU64>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for OscServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        // the thread owns the socket, so wait for it, to allow binding the port again right away
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl OscServer {
+    pub const DEFAULT_PORT: u16 = 9064;
+
+    pub fn start(address: &str) -> Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+        let local_addr = socket.local_addr()?;
+        let running = Arc::new(AtomicBool::new(true));
+        let message_count = Arc::new(AtomicU64::new(0));
+
+        let r = running.clone();
+        let mc = message_count.clone();
+        let thread = std::thread::spawn(move || {
+            let mut buffer = [0u8; 65536];
+            while r.load(Ordering::Relaxed) {
+                let Ok((len, sender)) = socket.recv_from(&mut buffer) else {
+                    continue;
+                };
+                match OscMessage::decode_packet(&buffer[..len]) {
+                    Ok(messages) => {
+                        for m in messages {
+                            mc.fetch_add(1, Ordering::Relaxed);
+                            if m.address == "/ping" {
+                                let pong = OscMessage {
+                                    address: "/pong".into(),
+                                    args: Vec::default(),
+                                };
+                                let _ = socket.send_to(&pong.encode(), sender);
+                                continue;
+                            }
+                            match Self::command_for(&m) {
+                                Some(command) => {
+                                    let _ = COMMAND_QUEUE.send(command);
+                                }
+                                None => eprintln!("Unhandled OSC message {m:?}"),
+                            }
+                        }
+                    }
+                    Err(e) => eprintln!("Invalid OSC packet from {sender} -> {e:?}"),
+                }
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            running,
+            message_count,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+    pub fn message_count(&self) -> u64 {
+        self.message_count.load(Ordering::Relaxed)
+    }
+
+    pub fn command_for(m: &OscMessage) -> Option<Command> {
+        let f64_arg = |i: usize| m.args.get(i).and_then(OscArg::as_f64);
+        if let Some(name) = m.address.strip_prefix("/property/") {
+            let values: Vec<f64> = m.args.iter().filter_map(OscArg::as_f64).collect();
+            if name.is_empty() || values.is_empty() {
+                return None;
+            }
+            return Some(Command::SetProperty {
+                name: name.to_string(),
+                values,
+            });
+        }
+        let transport_command = match m.address.as_str() {
+            "/flow/goto" => {
+                let label = m.args.first().and_then(OscArg::as_str)?;
+                return Some(Command::SetStartLabel {
+                    label: label.to_string(),
+                });
+            }
+            "/fullscreen" => {
+                return Some(match f64_arg(0) {
+                    None => Command::ToggleFullscreen,
+                    Some(0.0) => Command::LeaveFullscreen,
+                    Some(_) => Command::EnterFullscreen,
+                });
+            }
+            "/transport/play" => TransportCommand::Play,
+            "/transport/pause" => TransportCommand::Pause,
+            "/transport/toggle" => TransportCommand::TogglePlay,
+            "/transport/step" => TransportCommand::StepFrames {
+                frames: f64_arg(0).unwrap_or(1.0) as i32,
+            },
+            "/transport/speed" => TransportCommand::SetSpeed { speed: f64_arg(0)? },
+            "/transport/seek" => TransportCommand::Seek { time: f64_arg(0)? },
+            _ => return None,
+        };
+        Some(Command::Transport { transport_command })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(address: &str, args: Vec<OscArg>) -> OscMessage {
+        OscMessage {
+            address: address.into(),
+            args,
+        }
+    }
+
+    #[test]
+    fn command_for_works() {
+        match OscServer::command_for(&message(
+            "/property/fColor",
+            vec![OscArg::Float(0.5), OscArg::Int(1)],
+        )) {
+            Some(Command::SetProperty { name, values }) => {
+                assert_eq!("fColor", name);
+                assert_eq!(vec![0.5, 1.0], values);
+            }
+            o => panic!("unexpected {o:?}"),
+        }
+        match OscServer::command_for(&message("/flow/goto", vec![OscArg::String("intro".into())])) {
+            Some(Command::SetStartLabel { label }) => assert_eq!("intro", label),
+            o => panic!("unexpected {o:?}"),
+        }
+        assert!(matches!(
+            OscServer::command_for(&message("/transport/seek", vec![OscArg::Double(3.5)])),
+            Some(Command::Transport {
+                transport_command: TransportCommand::Seek { time }
+            }) if time == 3.5
+        ));
+        assert!(matches!(
+            OscServer::command_for(&message("/fullscreen", vec![OscArg::Int(0)])),
+            Some(Command::LeaveFullscreen)
+        ));
+        assert!(OscServer::command_for(&message("/transport/seek", vec![])).is_none());
+        assert!(OscServer::command_for(&message("/property/fColor", vec![])).is_none());
+        assert!(OscServer::command_for(&message("/unknown", vec![])).is_none());
+    }
+
+    #[test]
+    fn loopback_ping_works() {
+        let server = OscServer::start("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        client
+            .send_to(&message("/ping", vec![]).encode(), server.local_addr())
+            .unwrap();
+
+        let mut buffer = [0u8; 64];
+        let (len, _) = client.recv_from(&mut buffer).unwrap();
+        assert_eq!(
+            vec![message("/pong", vec![])],
+            OscMessage::decode_packet(&buffer[..len]).unwrap()
+        );
+        assert_eq!(1, server.message_count());
+    }
+
+    #[test]
+    fn restart_on_same_port_works() {
+        let server = OscServer::start("127.0.0.1:0").unwrap();
+        let address = server.local_addr().to_string();
+        drop(server);
+        assert!(OscServer::start(&address).is_ok());
+    }
+}
@@ -47,6 +47,15 @@ impl Window for PerformanceWindow {
                 let min_d = state.paint_time_series().min(20);
                 let max_d = state.paint_time_series().max(20);
                 ui.label(format!("{min_d} < {avg_d} < {max_d}"));
+                if let Some(mgc) = state.mc_guffin() {
+                    let flow_time = *mgc.lock().flow_time();
+                    ui.label(format!(
+                        "Frame {} Time {:.3}s (wraps at {}s)",
+                        flow_time.frame(),
+                        flow_time.time(),
+                        flow_time.wrap_period()
+                    ));
+                }
                 let durations: Vec<_> = state
                     .paint_time_series()
                     .values()
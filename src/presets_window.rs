@@ -0,0 +1,225 @@
+use crate::project::PresetTransition;
+use crate::state::State;
+use crate::window::Window;
+use web_time::Duration;
+
+#[derive(Debug)]
+pub struct PresetsWindow {
+    is_open: bool,
+    new_preset_name: String,
+    /// Properties captured by "Capture Selected".
+    selected_properties: Vec<String>,
+    morph_from: String,
+    morph_to: String,
+    morph_t: f32,
+    transition_duration: f32,
+}
+
+impl Default for PresetsWindow {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            new_preset_name: String::default(),
+            selected_properties: Vec::default(),
+            morph_from: String::default(),
+            morph_to: String::default(),
+            morph_t: 0.0,
+            transition_duration: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct PresetsWindowSave {
+    #[serde(default)]
+    is_open: bool,
+    #[serde(default)]
+    transition_duration: f32,
+}
+
+impl From<&PresetsWindow> for PresetsWindowSave {
+    fn from(pw: &PresetsWindow) -> Self {
+        Self {
+            is_open: pw.is_open,
+            transition_duration: pw.transition_duration,
+        }
+    }
+}
+
+impl Window for PresetsWindow {
+    fn name(&self) -> &str {
+        "Presets"
+    }
+    fn is_open(&self) -> bool {
+        self.is_open
+    }
+    fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    fn update(&mut self, ctx: &egui::Context, state: &mut State) {
+        let mut is_open = self.is_open;
+        egui::Window::new("Presets")
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut is_open)
+            .show(ctx, |ui| {
+                self.update_capture(ui, state);
+                ui.separator();
+
+                let names: Vec<String> = state
+                    .project
+                    .presets()
+                    .presets()
+                    .iter()
+                    .map(|p| p.name.clone())
+                    .collect();
+                let mut recall = None;
+                let mut remove = None;
+                for name in names.iter() {
+                    ui.horizontal(|ui| {
+                        if ui.button("[X]").on_hover_text("Delete").clicked() {
+                            remove = Some(name.clone());
+                        }
+                        if ui.button("Recall").clicked() {
+                            recall = Some(name.clone());
+                        }
+                        let count = state
+                            .project
+                            .presets()
+                            .get(name)
+                            .map(|p| p.values.len())
+                            .unwrap_or_default();
+                        ui.label(format!("{name} ({count} properties)"));
+                    });
+                }
+                if let Some(name) = recall {
+                    state.preset_transition = None;
+                    state.project.recall_preset(&name);
+                }
+                if let Some(name) = remove {
+                    state.project.remove_preset(&name);
+                }
+                ui.separator();
+
+                self.update_morph(ui, state, &names);
+            });
+        self.is_open = is_open;
+    }
+    fn serialize(&self) -> String {
+        let save: PresetsWindowSave = self.into();
+
+        ron::ser::to_string(&save).unwrap_or_default()
+    }
+    fn deserialize(&mut self, data: &str) {
+        let save: PresetsWindowSave = ron::from_str(data).unwrap_or_default();
+
+        self.is_open = save.is_open;
+        if save.transition_duration > 0.0 {
+            self.transition_duration = save.transition_duration;
+        }
+    }
+}
+
+impl PresetsWindow {
+    fn update_capture(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_preset_name)
+                    .hint_text("preset name")
+                    .desired_width(120.0),
+            );
+            let has_name = !self.new_preset_name.is_empty();
+            if ui
+                .add_enabled(has_name, egui::Button::new("Capture All"))
+                .clicked()
+            {
+                state.project.capture_preset(&self.new_preset_name, None);
+            }
+            if ui
+                .add_enabled(
+                    has_name && !self.selected_properties.is_empty(),
+                    egui::Button::new("Capture Selected"),
+                )
+                .clicked()
+            {
+                state
+                    .project
+                    .capture_preset(&self.new_preset_name, Some(&self.selected_properties));
+            }
+        });
+        egui::CollapsingHeader::new(format!(
+            "Selected Properties ({})",
+            self.selected_properties.len()
+        ))
+        .show(ui, |ui| {
+            let mut names = Vec::default();
+            state.project.with_property_manager(|pm| {
+                names = pm.entries().keys().cloned().collect();
+            });
+            ui.horizontal_wrapped(|ui| {
+                for name in names {
+                    let mut selected = self.selected_properties.contains(&name);
+                    if ui.checkbox(&mut selected, &name).changed() {
+                        if selected {
+                            self.selected_properties.push(name);
+                        } else {
+                            self.selected_properties.retain(|n| *n != name);
+                        }
+                    }
+                }
+            });
+        });
+    }
+
+    fn update_morph(&mut self, ui: &mut egui::Ui, state: &mut State, names: &[String]) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            for (label, selected) in [("From", &mut self.morph_from), ("To", &mut self.morph_to)] {
+                egui::ComboBox::from_label(label)
+                    .selected_text(selected.as_str())
+                    .show_ui(ui, |ui| {
+                        for name in names {
+                            changed |= ui.selectable_value(selected, name.clone(), name).changed();
+                        }
+                    });
+            }
+        });
+        let can_morph = names.contains(&self.morph_from) && names.contains(&self.morph_to);
+        ui.add_enabled_ui(can_morph, |ui| {
+            changed |= ui
+                .add(egui::Slider::new(&mut self.morph_t, 0.0..=1.0).text("Morph"))
+                .changed();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut self.transition_duration)
+                        .speed(0.1)
+                        .clamp_range(0.0..=600.0)
+                        .suffix("s"),
+                );
+                if ui
+                    .button("Transition")
+                    .on_hover_text("Morphs from `From` to `To` over time")
+                    .clicked()
+                {
+                    state.preset_transition = Some(PresetTransition::new(
+                        &self.morph_from,
+                        &self.morph_to,
+                        Duration::from_secs_f32(self.transition_duration),
+                    ));
+                    self.morph_t = 1.0;
+                }
+                if let Some(transition) = &state.preset_transition {
+                    ui.label(format!("{:.0}%", transition.t() * 100.0));
+                    ui.ctx().request_repaint();
+                }
+            });
+        });
+        if changed && can_morph {
+            state.preset_transition = None;
+            state
+                .project
+                .morph_presets(&self.morph_from, &self.morph_to, self.morph_t);
+        }
+    }
+}
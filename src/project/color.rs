@@ -0,0 +1,133 @@
+//! Colour space conversions, colours are stored as sRGB, `0..1`.
+
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+pub fn linear_to_srgb(c: f32) -> f32 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// See <https://bottosson.github.io/posts/oklab/>
+pub fn srgb_to_oklab(srgb: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = srgb.map(srgb_to_linear);
+    let l = (0.41222147 * r + 0.53633254 * g + 0.05144599 * b).cbrt();
+    let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+    let s = (0.08830246 * r + 0.28171884 * g + 0.6299787 * b).cbrt();
+    [
+        0.21045426 * l + 0.7936178 * m - 0.00407205 * s,
+        1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+        0.02590404 * l + 0.78277177 * m - 0.80867577 * s,
+    ]
+}
+pub fn oklab_to_srgb([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = (l + 0.39633778 * a + 0.21580376 * b).powi(3);
+    let m_ = (l - 0.105561346 * a - 0.06385417 * b).powi(3);
+    let s_ = (l - 0.08948418 * a - 1.2914855 * b).powi(3);
+    [
+        4.0767417 * l_ - 3.3077116 * m_ + 0.23096994 * s_,
+        -1.268438 * l_ + 2.6097574 * m_ - 0.34131938 * s_,
+        -0.0041960863 * l_ - 0.7034186 * m_ + 1.7076147 * s_,
+    ]
+    .map(linear_to_srgb)
+}
+
+/// The colour space a colour property is uploaded to shaders in.
+///
+/// Values are always edited and stored as sRGB,
+/// `Linear` converts them on upload, for shaders working in linear light, e.g. with [`ColorOutput::Linear`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+impl ColorSpace {
+    pub const ALL: [ColorSpace; 2] = [Self::Srgb, Self::Linear];
+
+    /// Converts a stored sRGB colour to this space.
+    pub fn convert(&self, srgb: [f32; 3]) -> [f32; 3] {
+        match self {
+            Self::Srgb => srgb,
+            Self::Linear => srgb.map(srgb_to_linear),
+        }
+    }
+}
+
+impl core::fmt::Display for ColorSpace {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Srgb => write!(f, "sRGB"),
+            Self::Linear => write!(f, "Linear"),
+        }
+    }
+}
+
+/// The curve mapping linear, high dynamic range colours to `0..1` before the sRGB encoding.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Tonemap {
+    /// Clamps.
+    None,
+    Reinhard,
+    /// The ACES filmic curve fit by Krzysztof Narkowicz.
+    #[default]
+    Aces,
+}
+
+impl Tonemap {
+    pub const ALL: [Tonemap; 3] = [Self::None, Self::Reinhard, Self::Aces];
+}
+
+impl core::fmt::Display for Tonemap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Reinhard => write!(f, "Reinhard"),
+            Self::Aces => write!(f, "ACES"),
+        }
+    }
+}
+
+/// How the output of the flow gets to the screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum ColorOutput {
+    /// The flow renders straight to the screen, shader outputs are taken as sRGB.
+    #[default]
+    Direct,
+    /// The flow renders to a linear, half float target,
+    /// which is scaled by `exposure`, tonemapped, and encoded as sRGB for the screen.
+    Linear { tonemap: Tonemap, exposure: f32 },
+}
+
+impl ColorOutput {
+    pub fn default_linear() -> Self {
+        Self::Linear {
+            tonemap: Tonemap::default(),
+            exposure: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oklab_roundtrip_works() {
+        for c in [[0.2, 0.5, 0.8], [1.0, 1.0, 1.0], [0.9, 0.1, 0.3]] {
+            let rt = oklab_to_srgb(srgb_to_oklab(c));
+            for i in 0..3 {
+                assert!((c[i] - rt[i]).abs() < 1e-3, "{c:?} {rt:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! The previous frame of the flow, for trails, reaction-diffusion, and cellular automata.
+
+/// How `tFeedback` is filtered, `Nearest` keeps cells exact.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum FeedbackFilter {
+    Nearest,
+    #[default]
+    Linear,
+}
+
+impl FeedbackFilter {
+    pub const ALL: [FeedbackFilter; 2] = [Self::Nearest, Self::Linear];
+}
+
+impl core::fmt::Display for FeedbackFilter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Nearest => write!(f, "Nearest"),
+            Self::Linear => write!(f, "Linear"),
+        }
+    }
+}
+
+/// What `tFeedback` returns outside of `0..1`, `Repeat` wraps simulations around the edges.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum FeedbackWrap {
+    #[default]
+    Clamp,
+    Repeat,
+}
+
+impl FeedbackWrap {
+    pub const ALL: [FeedbackWrap; 2] = [Self::Clamp, Self::Repeat];
+}
+
+impl core::fmt::Display for FeedbackWrap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Clamp => write!(f, "Clamp"),
+            Self::Repeat => write!(f, "Repeat"),
+        }
+    }
+}
+
+/// Declares a double buffered texture for the flow.
+///
+/// The flow renders into one half, while programs sample the other, last frame's output, as `tFeedback`.
+/// The halves swap at the end of each frame, and are cleared when the viewport is resized.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Feedback {
+    #[serde(default)]
+    pub filter: FeedbackFilter,
+    #[serde(default)]
+    pub wrap: FeedbackWrap,
+}
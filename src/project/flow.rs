@@ -1,4 +1,5 @@
 use crate::project::Block;
+use crate::project::Feedback;
 use crate::project::GridPos;
 use crate::project::Step;
 
@@ -10,6 +11,10 @@ pub struct Flow {
     #[serde(default)]
     steps: Vec<(Step, GridPos)>,
 
+    /// The previous frame texture, `None` renders straight to the output.
+    #[serde(default)]
+    feedback: Option<Feedback>,
+
     #[serde(skip)]
     version: u32,
 }
@@ -55,6 +60,16 @@ impl Flow {
         self.version
     }
 
+    pub fn feedback(&self) -> Option<&Feedback> {
+        self.feedback.as_ref()
+    }
+    pub fn set_feedback(&mut self, feedback: Option<Feedback>) {
+        if feedback != self.feedback {
+            self.feedback = feedback;
+            self.version += 1;
+        }
+    }
+
     pub fn add_step(&mut self, pos: &GridPos, step: Step) {
         // :TODO: position might already be in use
         if self.steps.iter().any(|(_s, p)| *p == *pos) {
@@ -103,3 +118,28 @@ impl Flow {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::FeedbackFilter;
+
+    #[test]
+    fn feedback_is_optional() {
+        let mut flow: Flow = ron::from_str("(steps: [])").unwrap();
+        assert_eq!(None, flow.feedback());
+
+        let feedback = Feedback {
+            filter: FeedbackFilter::Nearest,
+            ..Default::default()
+        };
+        flow.set_feedback(Some(feedback));
+        assert_eq!(1, flow.version());
+        flow.set_feedback(Some(feedback));
+        assert_eq!(1, flow.version());
+
+        let data = ron::to_string(&flow).unwrap();
+        let flow: Flow = ron::from_str(&data).unwrap();
+        assert_eq!(Some(&feedback), flow.feedback());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::project::Property;
+use crate::project::PropertyConfig;
+use crate::project::PropertyManager;
+use crate::project::PropertyValue;
+
+/// An overlay on the McGuffin preview, to edit a property in the shader's coordinates.
+///
+/// Positions are in normalized coordinates, `-1..1` with y up, like `fMouseClick`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Gizmo {
+    /// Picked by type and name, see [`Gizmo::resolve`].
+    #[default]
+    Auto,
+    Hidden,
+    /// A draggable handle, for `vec2` positions.
+    Position,
+    /// A trackball, for `vec3` directions.
+    Direction,
+    /// A ring around the origin, for `float` radii.
+    Radius,
+}
+
+impl Gizmo {
+    pub const ALL: [Gizmo; 5] = [
+        Self::Auto,
+        Self::Hidden,
+        Self::Position,
+        Self::Direction,
+        Self::Radius,
+    ];
+
+    /// The gizmo shown for the property, if any.
+    ///
+    /// `Auto` gives every `vec2` a handle, except the ones set from the mouse,
+    /// `vec3`s named like a direction a trackball, and `float`s named like a radius a ring.
+    pub fn resolve(name: &str, property: &Property) -> Option<Gizmo> {
+        let uniform = PropertyManager::split_scoped_name(name)
+            .map(|(_, u)| u)
+            .unwrap_or(name);
+        let lower = uniform.to_lowercase();
+        let gizmo = match property.gizmo {
+            Self::Auto => match (&property.value, &property.config) {
+                (PropertyValue::Vec2F32 { .. }, _)
+                    if !matches!(uniform, "fMouseClick" | "fMouseHover") =>
+                {
+                    Self::Position
+                }
+                (PropertyValue::Vec3F32 { .. }, PropertyConfig::F32 { .. })
+                    if lower.contains("dir") =>
+                {
+                    Self::Direction
+                }
+                (PropertyValue::F32 { .. }, _) if lower.contains("radius") => Self::Radius,
+                _ => return None,
+            },
+            gizmo => gizmo,
+        };
+        match (gizmo, &property.value) {
+            (Self::Position, PropertyValue::Vec2F32 { .. })
+            | (Self::Direction, PropertyValue::Vec3F32 { .. })
+            | (Self::Radius, PropertyValue::F32 { .. }) => Some(gizmo),
+            _ => None,
+        }
+    }
+
+    /// Turns `direction` by `yaw` around the y axis, and `pitch` towards it, keeping its length.
+    pub fn rotate_direction(direction: [f32; 3], yaw: f32, pitch: f32) -> [f32; 3] {
+        let [x, y, z] = direction;
+        let length = (x * x + y * y + z * z).sqrt();
+        if length <= f32::EPSILON {
+            return direction;
+        }
+        let azimuth = x.atan2(z) + yaw;
+        let limit = core::f32::consts::FRAC_PI_2 - 0.001;
+        let elevation = ((y / length).clamp(-1.0, 1.0).asin() + pitch).clamp(-limit, limit);
+        [
+            length * elevation.cos() * azimuth.sin(),
+            length * elevation.sin(),
+            length * elevation.cos() * azimuth.cos(),
+        ]
+    }
+}
+
+impl core::fmt::Display for Gizmo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "Auto"),
+            Self::Hidden => write!(f, "Hidden"),
+            Self::Position => write!(f, "Position"),
+            Self::Direction => write!(f, "Direction"),
+            Self::Radius => write!(f, "Radius"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_works() {
+        let position = Property::default_vec2_f32(&[0.0, 0.0]);
+        assert_eq!(
+            Some(Gizmo::Position),
+            Gizmo::resolve("p/vCenter", &position)
+        );
+        assert_eq!(None, Gizmo::resolve("p/fMouseClick", &position));
+
+        let direction = Property::default_vec3_f32(&[0.0, 0.0, 1.0]);
+        assert_eq!(
+            Some(Gizmo::Direction),
+            Gizmo::resolve("vLightDir", &direction)
+        );
+        assert_eq!(None, Gizmo::resolve("vScale", &direction));
+
+        let mut radius = Property::default_f32(0.5);
+        assert_eq!(Some(Gizmo::Radius), Gizmo::resolve("p/fRadius", &radius));
+        radius.gizmo = Gizmo::Hidden;
+        assert_eq!(None, Gizmo::resolve("p/fRadius", &radius));
+        radius.gizmo = Gizmo::Position;
+        assert_eq!(None, Gizmo::resolve("p/fRadius", &radius));
+    }
+
+    #[test]
+    fn rotate_direction_works() {
+        let d = Gizmo::rotate_direction([0.0, 0.0, 2.0], core::f32::consts::FRAC_PI_2, 0.0);
+        assert!((d[0] - 2.0).abs() < 1e-5 && d[2].abs() < 1e-5, "{d:?}");
+        let d = Gizmo::rotate_direction([0.0, 0.0, 1.0], 0.0, 10.0);
+        assert!(d[1] > 0.99 && d[1] < 1.0, "{d:?}");
+    }
+}
@@ -0,0 +1,322 @@
+use crate::project::linear_to_srgb;
+use crate::project::oklab_to_srgb;
+use crate::project::srgb_to_linear;
+use crate::project::srgb_to_oklab;
+use crate::project::ColorSpace;
+use core::f32::consts::TAU;
+
+/// The colour space stops are blended in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum GradientInterpolation {
+    Srgb,
+    Linear,
+    #[default]
+    Oklab,
+}
+
+impl GradientInterpolation {
+    pub const ALL: [GradientInterpolation; 3] = [Self::Srgb, Self::Linear, Self::Oklab];
+}
+
+impl core::fmt::Display for GradientInterpolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Srgb => write!(f, "sRGB"),
+            Self::Linear => write!(f, "Linear"),
+            Self::Oklab => write!(f, "OKLab"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct GradientStop {
+    /// `0..1`
+    pub position: f32,
+    /// sRGB
+    pub color: [f32; 3],
+}
+
+/// A colour gradient, uploaded to shaders as a `vec3` uniform array of evenly spaced samples.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum Gradient {
+    Stops {
+        stops: Vec<GradientStop>,
+        interpolation: GradientInterpolation,
+    },
+    /// The cosine palette `a + b * cos(2pi * (c * t + d))`, stored as `a, b, c, d`.
+    Cosine { values: [f32; 3 * 4] },
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self::Stops {
+            stops: vec![
+                GradientStop {
+                    position: 0.0,
+                    color: [0.0, 0.0, 0.0],
+                },
+                GradientStop {
+                    position: 1.0,
+                    color: [1.0, 1.0, 1.0],
+                },
+            ],
+            interpolation: GradientInterpolation::default(),
+        }
+    }
+}
+
+impl Gradient {
+    /// The colour at `t`, `0..1`, as sRGB.
+    pub fn sample(&self, t: f32) -> [f32; 3] {
+        match self {
+            Self::Stops {
+                stops,
+                interpolation,
+            } => Self::sample_stops(stops, *interpolation, t),
+            Self::Cosine { values } => core::array::from_fn(|i| {
+                values[i]
+                    + values[3 + i] * (TAU * (values[2 * 3 + i] * t + values[3 * 3 + i])).cos()
+            }),
+        }
+    }
+
+    fn sample_stops(
+        stops: &[GradientStop],
+        interpolation: GradientInterpolation,
+        t: f32,
+    ) -> [f32; 3] {
+        let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+            return [0.0; 3];
+        };
+        if t <= first.position {
+            return first.color;
+        }
+        let Some(i) = stops.iter().position(|s| s.position > t) else {
+            return last.color;
+        };
+        let (a, b) = (&stops[i - 1], &stops[i]);
+        let f = (t - a.position) / (b.position - a.position);
+        let mix = |a: [f32; 3], b: [f32; 3]| -> [f32; 3] {
+            core::array::from_fn(|i| a[i] + (b[i] - a[i]) * f)
+        };
+        match interpolation {
+            GradientInterpolation::Srgb => mix(a.color, b.color),
+            GradientInterpolation::Linear => {
+                mix(a.color.map(srgb_to_linear), b.color.map(srgb_to_linear)).map(linear_to_srgb)
+            }
+            GradientInterpolation::Oklab => {
+                oklab_to_srgb(mix(srgb_to_oklab(a.color), srgb_to_oklab(b.color)))
+            }
+        }
+    }
+
+    /// `count` evenly spaced samples, from `0` to `1`.
+    pub fn bake(&self, count: usize) -> Vec<[f32; 3]> {
+        let d = (count.max(2) - 1) as f32;
+        (0..count).map(|i| self.sample(i as f32 / d)).collect()
+    }
+
+    /// The gradient with its colours converted to `space`, as dense stops, for uploading to shaders.
+    pub fn in_space(&self, space: ColorSpace) -> Gradient {
+        match space {
+            ColorSpace::Srgb => self.clone(),
+            ColorSpace::Linear => {
+                const COUNT: usize = 64;
+                let d = (COUNT - 1) as f32;
+                let stops = self
+                    .bake(COUNT)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, color)| GradientStop {
+                        position: i as f32 / d,
+                        color: space.convert(color),
+                    })
+                    .collect();
+                // the colours are converted already, so blend them as they are
+                Self::Stops {
+                    stops,
+                    interpolation: GradientInterpolation::Srgb,
+                }
+            }
+        }
+    }
+
+    /// Keeps the stops sorted, e.g. after moving them.
+    pub fn sort_stops(&mut self) {
+        if let Self::Stops { stops, .. } = self {
+            stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        }
+    }
+
+    /// Converts to `count` evenly spaced stops.
+    pub fn to_stops(&self, count: usize) -> Gradient {
+        let d = (count.max(2) - 1) as f32;
+        let stops = self
+            .bake(count.max(2))
+            .into_iter()
+            .enumerate()
+            .map(|(i, color)| GradientStop {
+                position: i as f32 / d,
+                color,
+            })
+            .collect();
+        Self::Stops {
+            stops,
+            interpolation: GradientInterpolation::Oklab,
+        }
+    }
+
+    /// The cosine palette nearest to this gradient.
+    ///
+    /// For each channel the frequency `c` is searched, for which `a + p * cos(2pi * c * t) + q * sin(2pi * c * t)`
+    /// is a linear least squares fit, giving `b` and `d` from `p` and `q`.
+    pub fn fit_cosine(&self) -> Gradient {
+        const SAMPLES: usize = 64;
+        let samples = self.bake(SAMPLES);
+        let ts: Vec<f32> = (0..SAMPLES)
+            .map(|i| i as f32 / (SAMPLES - 1) as f32)
+            .collect();
+        let mut values = [0.0; 3 * 4];
+        for channel in 0..3 {
+            let ys: Vec<f32> = samples.iter().map(|s| s[channel]).collect();
+            let mut best = (
+                f32::MAX,
+                [ys.iter().sum::<f32>() / SAMPLES as f32, 0.0, 1.0, 0.0],
+            );
+            for step in 1..=40 {
+                let c = step as f32 * 0.05;
+                let Some((a, p, q)) = Self::fit_harmonic(&ts, &ys, c) else {
+                    continue;
+                };
+                let error: f32 = ts
+                    .iter()
+                    .zip(ys.iter())
+                    .map(|(t, y)| {
+                        let w = TAU * c * t;
+                        (a + p * w.cos() + q * w.sin() - y).powi(2)
+                    })
+                    .sum();
+                if error < best.0 {
+                    let b = (p * p + q * q).sqrt();
+                    let d = (-q).atan2(p) / TAU;
+                    best = (error, [a, b, c, d.rem_euclid(1.0)]);
+                }
+            }
+            for (i, v) in best.1.iter().enumerate() {
+                values[i * 3 + channel] = *v;
+            }
+        }
+        Self::Cosine { values }
+    }
+
+    /// Least squares `(a, p, q)` for `y = a + p * cos(2pi * c * t) + q * sin(2pi * c * t)`.
+    fn fit_harmonic(ts: &[f32], ys: &[f32], c: f32) -> Option<(f32, f32, f32)> {
+        // normal equations
+        let mut m = [[0.0f32; 3]; 3];
+        let mut r = [0.0f32; 3];
+        for (t, y) in ts.iter().zip(ys.iter()) {
+            let w = TAU * c * t;
+            let basis = [1.0, w.cos(), w.sin()];
+            for i in 0..3 {
+                for j in 0..3 {
+                    m[i][j] += basis[i] * basis[j];
+                }
+                r[i] += basis[i] * y;
+            }
+        }
+        let det = |m: &[[f32; 3]; 3]| {
+            m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+                - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+                + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+        };
+        let d = det(&m);
+        if d.abs() < 1e-6 {
+            return None;
+        }
+        // Cramer's rule
+        let solve = |column: usize| {
+            let mut mc = m;
+            for (row, v) in mc.iter_mut().zip(r.iter()) {
+                row[column] = *v;
+            }
+            det(&mc) / d
+        };
+        Some((solve(0), solve(1), solve(2)))
+    }
+
+    /// Blends towards `other`, returns `None` if the kinds, or number of stops differ.
+    pub fn lerp(&self, other: &Gradient, t: f32) -> Option<Gradient> {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        match (self, other) {
+            (Self::Cosine { values: a }, Self::Cosine { values: b }) => Some(Self::Cosine {
+                values: core::array::from_fn(|i| mix(a[i], b[i])),
+            }),
+            (
+                Self::Stops {
+                    stops: a,
+                    interpolation,
+                },
+                Self::Stops { stops: b, .. },
+            ) if a.len() == b.len() => Some(Self::Stops {
+                stops: a
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| GradientStop {
+                        position: mix(a.position, b.position),
+                        color: core::array::from_fn(|i| mix(a.color[i], b.color[i])),
+                    })
+                    .collect(),
+                interpolation: *interpolation,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: [f32; 3], b: [f32; 3], epsilon: f32) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < epsilon, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn sample_works() {
+        let mut gradient = Gradient::Stops {
+            stops: vec![
+                GradientStop {
+                    position: 0.25,
+                    color: [1.0, 0.0, 0.0],
+                },
+                GradientStop {
+                    position: 0.75,
+                    color: [0.0, 0.0, 1.0],
+                },
+            ],
+            interpolation: GradientInterpolation::Srgb,
+        };
+        assert_near([1.0, 0.0, 0.0], gradient.sample(0.0), 1e-6);
+        assert_near([0.5, 0.0, 0.5], gradient.sample(0.5), 1e-6);
+        assert_near([0.0, 0.0, 1.0], gradient.sample(1.0), 1e-6);
+        if let Gradient::Stops { interpolation, .. } = &mut gradient {
+            *interpolation = GradientInterpolation::Linear;
+        }
+        assert_near([0.7354, 0.0, 0.7354], gradient.sample(0.5), 1e-3);
+        assert_eq!(5, gradient.bake(5).len());
+    }
+
+    #[test]
+    fn fit_cosine_works() {
+        let cosine = Gradient::Cosine {
+            values: [0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 0.0, 0.1, 0.2],
+        };
+        let fitted = cosine.to_stops(32).fit_cosine();
+        for i in 0..=10 {
+            let t = i as f32 / 10.0;
+            assert_near(cosine.sample(t), fitted.sample(t), 0.05);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+/// A 4x4 matrix property, stored as the parameters it is built from.
+///
+/// Angles are in degrees, the built matrices are column major, like GLSL's `mat4`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum Matrix {
+    /// Scales, then rotates around x, y, and z, then translates, for model matrices.
+    Transform {
+        translation: [f32; 3],
+        rotation: [f32; 3],
+        scale: [f32; 3],
+    },
+    /// A camera at `eye`, looking at `target`, for view matrices.
+    LookAt {
+        eye: [f32; 3],
+        target: [f32; 3],
+        up: [f32; 3],
+    },
+    /// A perspective projection, the aspect ratio is taken from the viewport.
+    Perspective { fov_y: f32, near: f32, far: f32 },
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::default_transform()
+    }
+}
+
+impl Matrix {
+    pub const IDENTITY: [f32; 16] = [
+        1.0, 0.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0, //
+    ];
+    pub const KINDS: [&'static str; 3] = ["Transform", "LookAt", "Perspective"];
+
+    pub fn default_transform() -> Self {
+        Self::Transform {
+            translation: [0.0; 3],
+            rotation: [0.0; 3],
+            scale: [1.0; 3],
+        }
+    }
+    pub fn default_look_at() -> Self {
+        Self::LookAt {
+            eye: [0.0, 0.0, 3.0],
+            target: [0.0; 3],
+            up: [0.0, 1.0, 0.0],
+        }
+    }
+    pub fn default_perspective() -> Self {
+        Self::Perspective {
+            fov_y: 60.0,
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+    /// A default for a uniform, picked by name, e.g. `mProjection` gets a perspective.
+    pub fn default_for(name: &str) -> Self {
+        let lower = name.to_lowercase();
+        if lower.contains("proj") {
+            Self::default_perspective()
+        } else if lower.contains("view") || lower.contains("camera") {
+            Self::default_look_at()
+        } else {
+            Self::default_transform()
+        }
+    }
+    pub fn default_for_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "Transform" => Some(Self::default_transform()),
+            "LookAt" => Some(Self::default_look_at()),
+            "Perspective" => Some(Self::default_perspective()),
+            _ => None,
+        }
+    }
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Transform { .. } => "Transform",
+            Self::LookAt { .. } => "LookAt",
+            Self::Perspective { .. } => "Perspective",
+        }
+    }
+
+    /// The parameters, in declaration order.
+    pub fn values(&self) -> Vec<f32> {
+        match self {
+            Self::Transform {
+                translation,
+                rotation,
+                scale,
+            } => [*translation, *rotation, *scale].concat(),
+            Self::LookAt { eye, target, up } => [*eye, *target, *up].concat(),
+            Self::Perspective { fov_y, near, far } => vec![*fov_y, *near, *far],
+        }
+    }
+    /// Sets the parameters, in declaration order, returns `false` if there are too few.
+    pub fn set_values(&mut self, values: &[f32]) -> bool {
+        let v3 = |i: usize| [values[i], values[i + 1], values[i + 2]];
+        match self {
+            Self::Transform {
+                translation,
+                rotation,
+                scale,
+            } if values.len() >= 9 => {
+                (*translation, *rotation, *scale) = (v3(0), v3(3), v3(6));
+            }
+            Self::LookAt { eye, target, up } if values.len() >= 9 => {
+                (*eye, *target, *up) = (v3(0), v3(3), v3(6));
+            }
+            Self::Perspective { fov_y, near, far } if values.len() >= 3 => {
+                (*fov_y, *near, *far) = (values[0], values[1], values[2]);
+            }
+            _ => return false,
+        }
+        true
+    }
+    /// Blends the parameters, returns `None` for different kinds.
+    pub fn lerp(&self, other: &Matrix, t: f32) -> Option<Matrix> {
+        if self.kind() != other.kind() {
+            return None;
+        }
+        let values: Vec<f32> = self
+            .values()
+            .iter()
+            .zip(other.values())
+            .map(|(a, b)| a + (b - a) * t)
+            .collect();
+        let mut m = self.clone();
+        m.set_values(&values);
+        Some(m)
+    }
+
+    /// The matrix, for a viewport with the `aspect` ratio, width over height.
+    pub fn to_mat4(&self, aspect: f32) -> [f32; 16] {
+        match self {
+            Self::Transform {
+                translation,
+                rotation,
+                scale,
+            } => {
+                let [rx, ry, rz] = rotation.map(f32::to_radians);
+                let (sx, cx) = rx.sin_cos();
+                let (sy, cy) = ry.sin_cos();
+                let (sz, cz) = rz.sin_cos();
+                #[rustfmt::skip]
+                let rot_x = [
+                    1.0, 0.0, 0.0, 0.0,
+                    0.0, cx, sx, 0.0,
+                    0.0, -sx, cx, 0.0,
+                    0.0, 0.0, 0.0, 1.0,
+                ];
+                #[rustfmt::skip]
+                let rot_y = [
+                    cy, 0.0, -sy, 0.0,
+                    0.0, 1.0, 0.0, 0.0,
+                    sy, 0.0, cy, 0.0,
+                    0.0, 0.0, 0.0, 1.0,
+                ];
+                #[rustfmt::skip]
+                let rot_z = [
+                    cz, sz, 0.0, 0.0,
+                    -sz, cz, 0.0, 0.0,
+                    0.0, 0.0, 1.0, 0.0,
+                    0.0, 0.0, 0.0, 1.0,
+                ];
+                let mut scale_m = Self::IDENTITY;
+                scale_m[0] = scale[0];
+                scale_m[5] = scale[1];
+                scale_m[10] = scale[2];
+                let mut m = Self::mul(&rot_z, &Self::mul(&rot_y, &Self::mul(&rot_x, &scale_m)));
+                m[12] = translation[0];
+                m[13] = translation[1];
+                m[14] = translation[2];
+                m
+            }
+            Self::LookAt { eye, target, up } => {
+                let f = Self::normalize(Self::sub(*target, *eye));
+                let s = Self::normalize(Self::cross(f, *up));
+                let u = Self::cross(s, f);
+                #[rustfmt::skip]
+                let m = [
+                    s[0], u[0], -f[0], 0.0,
+                    s[1], u[1], -f[1], 0.0,
+                    s[2], u[2], -f[2], 0.0,
+                    -Self::dot(s, *eye), -Self::dot(u, *eye), Self::dot(f, *eye), 1.0,
+                ];
+                m
+            }
+            Self::Perspective { fov_y, near, far } => {
+                let f = 1.0 / (fov_y.to_radians() * 0.5).tan();
+                let aspect = if aspect > 0.0 { aspect } else { 1.0 };
+                let d = near - far;
+                #[rustfmt::skip]
+                let m = [
+                    f / aspect, 0.0, 0.0, 0.0,
+                    0.0, f, 0.0, 0.0,
+                    0.0, 0.0, (far + near) / d, -1.0,
+                    0.0, 0.0, 2.0 * far * near / d, 0.0,
+                ];
+                m
+            }
+        }
+    }
+
+    pub fn mul(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+        core::array::from_fn(|i| {
+            let (col, row) = (i / 4, i % 4);
+            (0..4).map(|k| a[k * 4 + row] * b[col * 4 + k]).sum()
+        })
+    }
+
+    fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+    fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+    fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    }
+    fn normalize(a: [f32; 3]) -> [f32; 3] {
+        let l = Self::dot(a, a).sqrt().max(f32::EPSILON);
+        a.map(|v| v / l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(m: &[f32; 16], p: [f32; 3]) -> [f32; 4] {
+        core::array::from_fn(|row| {
+            m[row] * p[0] + m[4 + row] * p[1] + m[8 + row] * p[2] + m[12 + row]
+        })
+    }
+
+    fn assert_near(expected: &[f32], actual: &[f32]) {
+        for (e, a) in expected.iter().zip(actual) {
+            assert!((e - a).abs() < 1e-5, "{expected:?} != {actual:?}");
+        }
+    }
+
+    #[test]
+    fn to_mat4_works() {
+        assert_near(&Matrix::IDENTITY, &Matrix::default_transform().to_mat4(1.0));
+
+        let m = Matrix::Transform {
+            translation: [1.0, 2.0, 3.0],
+            rotation: [0.0, 0.0, 90.0],
+            scale: [2.0, 2.0, 2.0],
+        };
+        assert_near(
+            &[1.0, 4.0, 3.0, 1.0],
+            &transform(&m.to_mat4(1.0), [1.0, 0.0, 0.0]),
+        );
+
+        // the eye ends up at the origin, looking down -z
+        let view = Matrix::default_look_at().to_mat4(1.0);
+        assert_near(&[0.0, 0.0, 0.0, 1.0], &transform(&view, [0.0, 0.0, 3.0]));
+        assert_near(&[0.0, 0.0, -3.0, 1.0], &transform(&view, [0.0, 0.0, 0.0]));
+
+        // the near plane maps to -1, the far plane to 1
+        let proj = Matrix::default_perspective().to_mat4(2.0);
+        let near = transform(&proj, [0.0, 0.0, -0.1]);
+        let far = transform(&proj, [0.0, 0.0, -100.0]);
+        assert!((near[2] / near[3] + 1.0).abs() < 1e-4, "{near:?}");
+        assert!((far[2] / far[3] - 1.0).abs() < 1e-4, "{far:?}");
+    }
+
+    #[test]
+    fn values_and_lerp_work() {
+        assert!(matches!(
+            Matrix::default_for("mProjection"),
+            Matrix::Perspective { .. }
+        ));
+        assert!(matches!(
+            Matrix::default_for("mView"),
+            Matrix::LookAt { .. }
+        ));
+        assert!(matches!(
+            Matrix::default_for("mModel"),
+            Matrix::Transform { .. }
+        ));
+
+        let a = Matrix::default_transform();
+        let mut b = a.clone();
+        assert!(b.set_values(&[2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 3.0, 1.0, 1.0]));
+        let Some(Matrix::Transform {
+            translation, scale, ..
+        }) = a.lerp(&b, 0.5)
+        else {
+            panic!("Expected a transform");
+        };
+        assert_eq!([1.0, 0.0, 0.0], translation);
+        assert_eq!([2.0, 1.0, 1.0], scale);
+        assert!(a.lerp(&Matrix::default_perspective(), 0.5).is_none());
+        assert!(!b.set_values(&[1.0]));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::project::PropertyConfig;
+use crate::project::PropertyManager;
+use crate::project::PropertyValue;
+
+/// A physical control, channels are `0..16` like on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum MidiSource {
+    ControlChange { channel: u8, controller: u8 },
+    Note { channel: u8, note: u8 },
+}
+
+impl core::fmt::Display for MidiSource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::ControlChange {
+                channel,
+                controller,
+            } => write!(f, "CC {controller} Ch {}", channel + 1),
+            Self::Note { channel, note } => write!(f, "Note {note} Ch {}", channel + 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct MidiBinding {
+    pub source: MidiSource,
+    pub property: String,
+}
+
+/// Maps MIDI controls to properties, each property has at most one control.
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct MidiBindings {
+    bindings: Vec<MidiBinding>,
+}
+
+impl MidiBindings {
+    pub fn bindings(&self) -> &[MidiBinding] {
+        &self.bindings
+    }
+
+    pub fn bind(&mut self, source: MidiSource, property: &str) {
+        self.unbind(property);
+        self.bindings.push(MidiBinding {
+            source,
+            property: property.to_string(),
+        });
+    }
+    pub fn unbind(&mut self, property: &str) {
+        self.bindings.retain(|b| b.property != property);
+    }
+    pub fn source_for(&self, property: &str) -> Option<&MidiSource> {
+        self.bindings
+            .iter()
+            .find(|b| b.property == property)
+            .map(|b| &b.source)
+    }
+
+    /// Sets all properties bound to `source`, `value` is `0..1` and scaled to the property's range.
+    ///
+    /// Returns the number of properties changed.
+    pub fn apply(&self, source: &MidiSource, value: f32, pm: &mut PropertyManager) -> usize {
+        let mut count = 0;
+        for b in self.bindings.iter().filter(|b| b.source == *source) {
+            let Some(p) = pm.get_mut(&b.property) else {
+                continue;
+            };
+            let (min_value, max_value) = match p.config() {
+                PropertyConfig::F32 {
+                    min_value,
+                    max_value,
+                    ..
+                } => (*min_value, *max_value),
+                _ => (0.0, 1.0),
+            };
+            let scaled = min_value + (max_value - min_value) * value;
+            match p.value_mut() {
+                PropertyValue::F32 { value } => *value = scaled,
+                PropertyValue::F64 { value } => *value = scaled as f64,
+                PropertyValue::Bool { value: b } => *b = value >= 0.5,
+                _ => continue,
+            }
+            count += 1;
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Property;
+
+    #[test]
+    fn apply_scales_to_config() {
+        let mut pm = PropertyManager::default();
+        pm.add_entry("fSpeed", Property::default_f32(0.0));
+        let mut p = Property::default_f32(0.0);
+        p.config = PropertyConfig::F32 {
+            min_value: -1.0,
+            max_value: 1.0,
+            step_size: 0.1,
+        };
+        pm.add_entry("fPan", p);
+
+        let knob = MidiSource::ControlChange {
+            channel: 0,
+            controller: 7,
+        };
+        let mut mb = MidiBindings::default();
+        mb.bind(knob, "fSpeed");
+        mb.bind(knob, "fPan");
+        mb.bind(
+            MidiSource::Note {
+                channel: 0,
+                note: 60,
+            },
+            "fSpeed",
+        );
+        assert_eq!(2, mb.bindings().len());
+        assert_eq!(Some(&knob), mb.source_for("fPan"));
+
+        assert_eq!(1, mb.apply(&knob, 0.25, &mut pm));
+        match pm.entries().get("fPan").map(|p| p.value()) {
+            Some(PropertyValue::F32 { value }) => assert_eq!(-0.5, *value),
+            o => panic!("unexpected {o:?}"),
+        }
+
+        mb.unbind("fPan");
+        assert_eq!(0, mb.apply(&knob, 0.25, &mut pm));
+    }
+}
@@ -1,11 +1,25 @@
 mod block;
 pub use block::Block;
+mod color;
+pub use color::linear_to_srgb;
+pub use color::oklab_to_srgb;
+pub use color::srgb_to_linear;
+pub use color::srgb_to_oklab;
+pub use color::ColorOutput;
+pub use color::ColorSpace;
+pub use color::Tonemap;
+mod feedback;
+pub use feedback::Feedback;
+pub use feedback::FeedbackFilter;
+pub use feedback::FeedbackWrap;
 mod flow;
 pub use flow::Flow;
 mod steps;
 pub use steps::Step;
 mod project;
 pub use project::Project;
+mod project_bundle;
+use project_bundle::ProjectBundle;
 mod program;
 use program::Program;
 mod property_manager;
@@ -13,14 +27,44 @@ pub use property_manager::Property;
 pub use property_manager::PropertyConfig;
 pub use property_manager::PropertyManager;
 pub use property_manager::PropertyValue;
+mod property_presets;
+pub use property_presets::PresetTransition;
+pub use property_presets::PropertyPresets;
+mod property_randomizer;
+pub use property_randomizer::PropertyRandomizer;
 mod resource;
+pub use resource::ChannelInput;
 pub use resource::Resource;
+pub use resource::ResourceAudio;
+pub use resource::ResourceBuffer;
 pub use resource::ResourceId;
+pub use resource::ResourceMesh;
 pub use resource::ResourceProgram;
 pub use resource::ResourceText;
 pub use resource::ShaderType;
+mod render_state;
+pub use render_state::BlendMode;
+pub use render_state::CullMode;
+pub use render_state::DepthTest;
+mod matrix;
+pub use matrix::Matrix;
+mod midi_bindings;
+pub use midi_bindings::MidiBindings;
+pub use midi_bindings::MidiSource;
+mod shadertoy_import;
+pub use shadertoy_import::ShadertoyImport;
+mod sync_tracks;
+pub use sync_tracks::SyncInterpolation;
+pub use sync_tracks::SyncKey;
+pub use sync_tracks::SyncTracks;
 mod resource_manager;
 pub use resource_manager::ResourceManager;
+mod gizmo;
+pub use gizmo::Gizmo;
+mod gradient;
+pub use gradient::Gradient;
+pub use gradient::GradientInterpolation;
+pub use gradient::GradientStop;
 mod grid_pos;
 pub use grid_pos::GridPos;
 mod grid_rect;
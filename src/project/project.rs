@@ -1,13 +1,25 @@
+use crate::engine::FlowTime;
 use crate::project::Block;
+use crate::project::ColorOutput;
 use crate::project::Flow;
 use crate::project::GridPos;
+use crate::project::MidiBindings;
+use crate::project::MidiSource;
+use crate::project::ProjectBundle;
 use crate::project::PropertyManager;
+use crate::project::PropertyPresets;
+use crate::project::Resource;
+use crate::project::ResourceId;
 use crate::project::ResourceManager;
+use crate::project::ShaderType;
 use crate::project::Step;
+use crate::project::SyncTracks;
 use color_eyre::Result;
 use std::path::Path;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
 
-#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
 pub struct Project {
     name: String,
     property_manager: PropertyManager,
@@ -15,15 +27,141 @@ pub struct Project {
     //programs: HashMap<String, Program>,
     #[serde(default)]
     flow: Flow,
+    #[serde(default = "Project::default_time_wrap_period")]
+    time_wrap_period: f64,
+    #[serde(default)]
+    soundtrack: Option<ResourceId>,
+    #[serde(default)]
+    sync_tracks: SyncTracks,
+    #[serde(default)]
+    midi_bindings: MidiBindings,
+    #[serde(default)]
+    presets: PropertyPresets,
+    #[serde(default)]
+    color_output: ColorOutput,
 
     #[serde(skip)]
     version: u32,
+    /// Unique for each new or loaded project, kept by clones.
+    #[serde(skip, default = "Project::next_load_generation")]
+    load_generation: u32,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            property_manager: PropertyManager::default(),
+            resource_manager: ResourceManager::default(),
+            flow: Flow::default(),
+            time_wrap_period: Self::default_time_wrap_period(),
+            soundtrack: None,
+            sync_tracks: SyncTracks::default(),
+            midi_bindings: MidiBindings::default(),
+            presets: PropertyPresets::default(),
+            color_output: ColorOutput::default(),
+            version: 0,
+            load_generation: Self::next_load_generation(),
+        }
+    }
 }
 
 impl Project {
     pub fn version(&self) -> u32 {
         self.version
     }
+    fn next_load_generation() -> u32 {
+        static NEXT: AtomicU32 = AtomicU32::new(1);
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    }
+    /// Tells a project apart from the one it replaced, since versions start over on load.
+    pub fn load_generation(&self) -> u32 {
+        self.load_generation
+    }
+    fn default_time_wrap_period() -> f64 {
+        FlowTime::DEFAULT_WRAP_PERIOD
+    }
+    /// The period `${TIME}` wraps at for `f32` uniforms.
+    pub fn time_wrap_period(&self) -> f64 {
+        self.time_wrap_period
+    }
+    pub fn set_time_wrap_period(&mut self, time_wrap_period: f64) {
+        if time_wrap_period != self.time_wrap_period && time_wrap_period > 0.0 {
+            self.time_wrap_period = time_wrap_period;
+            self.version += 1;
+        }
+    }
+    /// How the output of the flow gets to the screen.
+    ///
+    /// Changes don't bump the version, the flow doesn't need a rebuild for them.
+    pub fn color_output(&self) -> &ColorOutput {
+        &self.color_output
+    }
+    pub fn set_color_output(&mut self, color_output: ColorOutput) {
+        self.color_output = color_output;
+    }
+    /// The audio resource the flow is synced to.
+    pub fn soundtrack(&self) -> Option<&ResourceId> {
+        self.soundtrack.as_ref()
+    }
+    pub fn set_soundtrack(&mut self, soundtrack: Option<ResourceId>) {
+        if soundtrack != self.soundtrack {
+            self.soundtrack = soundtrack;
+            self.version += 1;
+        }
+    }
+    pub fn sync_tracks(&self) -> &SyncTracks {
+        &self.sync_tracks
+    }
+    /// Changes to the sync tracks don't change the project version, so live editing doesn't rebuild the flow.
+    pub fn with_sync_tracks_mut<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut SyncTracks) -> R,
+    {
+        f(&mut self.sync_tracks)
+    }
+    pub fn midi_bindings(&self) -> &MidiBindings {
+        &self.midi_bindings
+    }
+    pub fn midi_bindings_mut(&mut self) -> &mut MidiBindings {
+        &mut self.midi_bindings
+    }
+    /// Applies a MIDI control to all properties bound to it.
+    pub fn apply_midi(&mut self, source: &MidiSource, value: f32) -> usize {
+        self.midi_bindings
+            .apply(source, value, &mut self.property_manager)
+    }
+    pub fn presets(&self) -> &PropertyPresets {
+        &self.presets
+    }
+    /// Captures the current property values, of all properties, or only of the ones in `subset`.
+    pub fn capture_preset(&mut self, name: &str, subset: Option<&[String]>) {
+        self.presets.capture(name, &self.property_manager, subset);
+    }
+    pub fn remove_preset(&mut self, name: &str) -> bool {
+        self.presets.remove(name)
+    }
+    pub fn recall_preset(&mut self, name: &str) -> bool {
+        self.presets.recall(name, &mut self.property_manager)
+    }
+    /// Blends the property values between two presets at `t`, `0..1`.
+    pub fn morph_presets(&mut self, from: &str, to: &str, t: f32) -> bool {
+        self.presets.morph(from, to, t, &mut self.property_manager)
+    }
+    /// The names of all sync tracks, and of the ones used via `${SYNC:<name>}` in the flow.
+    pub fn sync_track_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.sync_tracks.tracks().keys().cloned().collect();
+        for (s, _gp) in self.flow.steps().iter() {
+            for v in s.values() {
+                if let Some(name) = v.strip_prefix("${SYNC:").and_then(|v| v.strip_suffix('}')) {
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+        names
+    }
     /*
     pub fn create_simple_flow(&mut self) {
         let mut flow = Flow::default();
@@ -106,6 +244,12 @@ impl Project {
         }
         result
     }
+    /// Replaces the whole project, e.g. with an edited copy, and marks it as changed.
+    pub fn replace(&mut self, project: Project) {
+        let version = self.version.max(project.version) + 1;
+        *self = project;
+        self.version = version;
+    }
     pub fn with_flow<F, R>(&self, mut f: F) -> R
     where
         F: FnMut(&Flow) -> R,
@@ -146,6 +290,19 @@ impl Project {
         Ok(project)
     }
 
+    /// Loads a project saved with [`Project::to_bundle`], all resources are already included.
+    pub fn try_load_from_bundle(data: &str) -> Result<Self> {
+        let mut project = ProjectBundle::from_ron(data)?.into_project();
+        project.flow.fixup_blocks(GridPos::default());
+        project.version = 1;
+        Ok(project)
+    }
+
+    /// Serializes the project including the texts of all resources into a single string.
+    pub fn to_bundle(&self) -> Result<String> {
+        ProjectBundle::from_project(self).to_ron()
+    }
+
     pub fn save(&mut self, filename: &Path) -> Result<()> {
         let mut filename = filename.to_path_buf();
         filename.push("sfrs.ron");
@@ -157,6 +314,149 @@ impl Project {
         Ok(())
     }
 
+    /// Returns a list of problems, an empty list means the project is valid.
+    pub fn validate(&self, start_label: &str) -> Vec<String> {
+        let mut problems = Vec::default();
+
+        let has_start = self.flow.steps().iter().any(|(s, _gp)| match s {
+            Step::Label { name, .. } => name == start_label,
+            _ => false,
+        });
+        if !has_start {
+            problems.push(format!("Flow has no label `{start_label}`"));
+        }
+
+        for (s, gp) in self.flow.steps().iter() {
+            if let Step::Program { resource_id, .. } = s {
+                match self.resource_manager.get(resource_id) {
+                    Some(Resource::Program(rp)) if rp.is_compute() => problems.push(format!(
+                        "Step at {gp:?} uses compute program {resource_id}, which needs a Dispatch step"
+                    )),
+                    Some(Resource::Program(_)) => {}
+                    Some(_) => problems.push(format!(
+                        "Step at {gp:?} uses {resource_id} which is not a program"
+                    )),
+                    None => {
+                        problems.push(format!("Step at {gp:?} uses missing program {resource_id}"))
+                    }
+                }
+            }
+            if let Step::Dispatch {
+                resource_id,
+                groups,
+                ..
+            } = s
+            {
+                match self.resource_manager.get(resource_id) {
+                    Some(Resource::Program(rp)) if rp.is_compute() => {}
+                    Some(_) => problems.push(format!(
+                        "Step at {gp:?} uses {resource_id} which is not a compute program"
+                    )),
+                    None => {
+                        problems.push(format!("Step at {gp:?} uses missing program {resource_id}"))
+                    }
+                }
+                if groups.contains(&0) {
+                    problems.push(format!("Step at {gp:?} dispatches no work groups"));
+                }
+            }
+            if let Step::DrawMesh { resource_id, .. } = s {
+                match self.resource_manager.get(resource_id) {
+                    Some(Resource::Mesh(_)) => {}
+                    Some(_) => problems.push(format!(
+                        "Step at {gp:?} uses {resource_id} which is not a mesh"
+                    )),
+                    None => {
+                        problems.push(format!("Step at {gp:?} uses missing mesh {resource_id}"))
+                    }
+                }
+            }
+        }
+
+        if let Some(soundtrack) = &self.soundtrack {
+            match self.resource_manager.get(soundtrack) {
+                Some(Resource::Audio(_)) => {}
+                _ => problems.push(format!("Soundtrack {soundtrack} is not an audio resource")),
+            }
+        }
+
+        for (id, r) in self.resource_manager.resources().iter() {
+            match r {
+                Resource::Program(rp) => {
+                    if rp.is_compute() {
+                        for s in rp.shaders() {
+                            match s.shader_type() {
+                                ShaderType::Compute | ShaderType::Common => {}
+                                shader_type => problems.push(format!(
+                                    "Compute program {id} also has a {shader_type} shader"
+                                )),
+                            }
+                        }
+                    } else {
+                        for shader_type in [ShaderType::Vertex, ShaderType::Fragment] {
+                            if !rp.shaders().iter().any(|s| s.shader_type() == shader_type) {
+                                problems.push(format!("Program {id} has no {shader_type} shader"));
+                            }
+                        }
+                    }
+                    for s in rp.shaders() {
+                        match self.resource_manager.get(s.resource_id()) {
+                            Some(Resource::Text(rt)) if rt.text().is_empty() => problems.push(
+                                format!("Shader {} of program {id} is empty", s.resource_id()),
+                            ),
+                            Some(Resource::Text(_)) => {}
+                            _ => problems.push(format!(
+                                "Shader {} of program {id} is missing",
+                                s.resource_id()
+                            )),
+                        }
+                    }
+                }
+                Resource::Text(rt) => {
+                    if rt.file().is_none() {
+                        problems.push(format!("Text {id} has no file"));
+                    }
+                }
+                Resource::Audio(ra) => {
+                    if ra.file().is_none() {
+                        problems.push(format!("Audio {id} has no file"));
+                    } else if ra.clip().is_none() {
+                        problems.push(format!("Audio {id} could not be loaded"));
+                    }
+                }
+                Resource::Mesh(rm) => {
+                    if rm.file().is_none() {
+                        problems.push(format!("Mesh {id} has no file"));
+                    } else if rm.mesh().is_none() {
+                        problems.push(format!("Mesh {id} could not be loaded"));
+                    }
+                }
+                Resource::Buffer(rb) => {
+                    if rb.size() == 0 {
+                        problems.push(format!("Buffer {id} is empty"));
+                    }
+                    if rb.stride() % 4 != 0 {
+                        problems.push(format!(
+                            "Buffer {id} has a stride that is not a multiple of 4"
+                        ));
+                    }
+                    let shared = self.resource_manager.resources().iter().any(|(other_id, r)| {
+                        matches!(r, Resource::Buffer(o) if other_id != id && o.binding() == rb.binding())
+                    });
+                    if shared {
+                        problems.push(format!(
+                            "Buffer {id} shares binding {} with another buffer",
+                            rb.binding()
+                        ));
+                    }
+                }
+                Resource::None => {}
+            }
+        }
+
+        problems
+    }
+
     pub fn save_all_resources(&mut self, project_folder: &Path) -> Result<()> {
         self.resource_manager.save_all(Some(project_folder))
     }
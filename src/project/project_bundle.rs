@@ -0,0 +1,74 @@
+use crate::project::Project;
+use crate::project::Resource;
+use crate::project::ResourceId;
+use color_eyre::Result;
+use std::collections::BTreeMap;
+
+/// A project including the texts of all resources in a single file,
+/// e.g. for browser storage or uploads where there is no project folder.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ProjectBundle {
+    project: Project,
+    texts: BTreeMap<ResourceId, String>,
+}
+
+impl ProjectBundle {
+    pub fn from_project(project: &Project) -> Self {
+        let texts = project
+            .resource_manager()
+            .resources()
+            .iter()
+            .filter_map(|(id, r)| match r {
+                Resource::Text(rt) => Some((id.clone(), rt.text().to_string())),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            project: project.clone(),
+            texts,
+        }
+    }
+
+    pub fn into_project(self) -> Project {
+        let mut project = self.project;
+        for (id, text) in self.texts {
+            if let Some(Resource::Text(rt)) = project.resource_manager.get_mut(&id) {
+                *rt.text_mut() = text;
+            }
+        }
+        project
+    }
+
+    pub fn to_ron(&self) -> Result<String> {
+        let data = ron::ser::to_string_pretty(&self, ron::ser::PrettyConfig::default())?;
+        Ok(data)
+    }
+
+    pub fn from_ron(data: &str) -> Result<Self> {
+        let bundle = ron::from_str(data)?;
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::ResourceText;
+
+    #[test]
+    fn round_trip_keeps_texts() {
+        let mut project = Project::default();
+        let mut rt = ResourceText::default();
+        *rt.text_mut() = String::from("void main() {}");
+        let id = project.resource_manager.add(Resource::Text(rt));
+
+        let data = ProjectBundle::from_project(&project).to_ron().unwrap();
+        let project = ProjectBundle::from_ron(&data).unwrap().into_project();
+
+        match project.resource_manager().get(&id) {
+            Some(Resource::Text(rt)) => assert_eq!("void main() {}", rt.text()),
+            o => panic!("Expected text resource, got {o:?}"),
+        }
+    }
+}
@@ -1,12 +1,22 @@
+use crate::engine::LinkedUniform;
+use crate::engine::ShaderAnnotation;
 use crate::engine::UniformManager;
 use crate::engine::UniformType;
+use crate::project::ColorSpace;
+use crate::project::Gizmo;
+use crate::project::Gradient;
+use crate::project::Matrix;
+use crate::project::ResourceId;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 
 #[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Property {
     pub value: PropertyValue,
     pub config: PropertyConfig,
+    #[serde(default)]
+    pub gizmo: Gizmo,
 }
 
 impl Property {
@@ -14,30 +24,74 @@ impl Property {
         Self {
             value: PropertyValue::F32 { value },
             config: PropertyConfig::default_f32(),
+            gizmo: Gizmo::default(),
+        }
+    }
+    pub fn default_f64(value: f64) -> Self {
+        Self {
+            value: PropertyValue::F64 { value },
+            config: PropertyConfig::default_f32(),
+            gizmo: Gizmo::default(),
         }
     }
     pub fn default_vec2_f32(values: &[f32; 2]) -> Self {
         Self {
             value: PropertyValue::Vec2F32 { values: *values },
             config: PropertyConfig::default_f32(),
+            gizmo: Gizmo::default(),
         }
     }
     pub fn default_vec3_f32(values: &[f32; 3]) -> Self {
         Self {
             value: PropertyValue::Vec3F32 { values: *values },
             config: PropertyConfig::default_f32(),
+            gizmo: Gizmo::default(),
         }
     }
     pub fn default_vec3_f32_size4(values: &[f32; 3 * 4]) -> Self {
         Self {
             value: PropertyValue::Vec3F32Size4 { values: *values },
             config: PropertyConfig::default_f32(),
+            gizmo: Gizmo::default(),
+        }
+    }
+    pub fn default_gradient(gradient: Gradient) -> Self {
+        Self {
+            value: PropertyValue::Gradient { gradient },
+            config: PropertyConfig::Gradient {
+                space: ColorSpace::default(),
+            },
+            gizmo: Gizmo::default(),
+        }
+    }
+    pub fn default_matrix(matrix: Matrix) -> Self {
+        Self {
+            value: PropertyValue::Matrix { matrix },
+            config: PropertyConfig::None,
+            gizmo: Gizmo::default(),
         }
     }
     pub fn value(&self) -> &PropertyValue {
         &self.value
     }
 
+    /// The value as uploaded to shaders, colours converted to the space they are tagged with.
+    pub fn shader_value(&self) -> PropertyValue {
+        match (&self.value, &self.config) {
+            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb { space }) => {
+                PropertyValue::Vec3F32 {
+                    values: space.convert(*values),
+                }
+            }
+            (PropertyValue::Gradient { gradient }, PropertyConfig::Gradient { space }) => {
+                PropertyValue::Gradient {
+                    gradient: gradient.in_space(*space),
+                }
+            }
+            (value, _) => value.clone(),
+        }
+    }
+
     pub fn value_mut(&mut self) -> &mut PropertyValue {
         &mut self.value
     }
@@ -56,6 +110,9 @@ pub enum PropertyValue {
     F32 {
         value: f32,
     },
+    F64 {
+        value: f64,
+    },
     Vec2F32 {
         values: [f32; 2],
     },
@@ -69,20 +126,109 @@ pub enum PropertyValue {
     Bool {
         value: bool,
     },
+    Gradient {
+        gradient: Gradient,
+    },
+    Matrix {
+        matrix: Matrix,
+    },
     #[default]
     None,
 }
 
-#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+impl PropertyValue {
+    /// Blends towards `other` at `t`, `0..1`, returns `None` for different types.
+    ///
+    /// Bools switch half way.
+    pub fn lerp(&self, other: &PropertyValue, t: f32) -> Option<PropertyValue> {
+        fn mix<const N: usize>(a: &[f32; N], b: &[f32; N], t: f32) -> [f32; N] {
+            core::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+        }
+        let v = match (self, other) {
+            (Self::F32 { value: a }, Self::F32 { value: b }) => Self::F32 {
+                value: a + (b - a) * t,
+            },
+            (Self::F64 { value: a }, Self::F64 { value: b }) => Self::F64 {
+                value: a + (b - a) * t as f64,
+            },
+            (Self::Vec2F32 { values: a }, Self::Vec2F32 { values: b }) => Self::Vec2F32 {
+                values: mix(a, b, t),
+            },
+            (Self::Vec3F32 { values: a }, Self::Vec3F32 { values: b }) => Self::Vec3F32 {
+                values: mix(a, b, t),
+            },
+            (Self::Vec3F32Size4 { values: a }, Self::Vec3F32Size4 { values: b }) => {
+                Self::Vec3F32Size4 {
+                    values: mix(a, b, t),
+                }
+            }
+            (Self::Bool { value: a }, Self::Bool { value: b }) => Self::Bool {
+                value: if t < 0.5 { *a } else { *b },
+            },
+            (Self::Gradient { gradient: a }, Self::Gradient { gradient: b }) => Self::Gradient {
+                gradient: a.lerp(b, t)?,
+            },
+            (Self::Matrix { matrix: a }, Self::Matrix { matrix: b }) => Self::Matrix {
+                matrix: a.lerp(b, t)?,
+            },
+            _ => return None,
+        };
+        Some(v)
+    }
+    /// Sets the value from plain numbers, e.g. from remote control, returns `false` if they don't fit.
+    pub fn set_from_f64s(&mut self, values: &[f64]) -> bool {
+        fn fill<const N: usize>(target: &mut [f32; N], values: &[f64]) -> bool {
+            if values.len() < N {
+                return false;
+            }
+            for (t, v) in target.iter_mut().zip(values) {
+                *t = *v as f32;
+            }
+            true
+        }
+        match (self, values.first()) {
+            (_, None) => false,
+            (Self::F32 { value }, Some(v)) => {
+                *value = *v as f32;
+                true
+            }
+            (Self::F64 { value }, Some(v)) => {
+                *value = *v;
+                true
+            }
+            (Self::Bool { value }, Some(v)) => {
+                *value = *v != 0.0;
+                true
+            }
+            (Self::Vec2F32 { values: target }, _) => fill(target, values),
+            (Self::Vec3F32 { values: target }, _) => fill(target, values),
+            (Self::Vec3F32Size4 { values: target }, _) => fill(target, values),
+            (Self::Matrix { matrix }, _) => {
+                let values: Vec<f32> = values.iter().map(|v| *v as f32).collect();
+                matrix.set_values(&values)
+            }
+            (Self::Gradient { .. }, _) | (Self::None, _) => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum PropertyConfig {
     F32 {
         min_value: f32,
         max_value: f32,
         step_size: f32,
     },
-    ColorRgb {},
+    ColorRgb {
+        #[serde(default)]
+        space: ColorSpace,
+    },
     ColorPal {},
     Bool {},
+    Gradient {
+        #[serde(default)]
+        space: ColorSpace,
+    },
     #[default]
     None,
 }
@@ -97,11 +243,26 @@ impl PropertyConfig {
     }
 }
 
+/// A user defined folder of properties, in display order.
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PropertyGroup {
+    pub name: String,
+    pub properties: Vec<String>,
+}
+
 #[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
 pub struct PropertyManager {
     //    entries: HashMap<String, Property>,
     entries: BTreeMap<String, Property>,
+    #[serde(default)]
+    groups: Vec<PropertyGroup>,
+    /// Program scoped names, and the property they use instead of their own.
+    #[serde(default)]
+    links: BTreeMap<String, String>,
 
+    /// The active uniforms of each linked program.
+    #[serde(skip)]
+    program_usage: HashMap<ResourceId, BTreeSet<String>>,
     #[serde(skip)]
     version: u32,
 }
@@ -120,6 +281,9 @@ impl PropertyManager {
 
     pub fn delete_entry(&mut self, name: &str) -> Option<Property> {
         self.version += 1;
+        for g in self.groups.iter_mut() {
+            g.properties.retain(|p| p != name);
+        }
         self.entries.remove(name)
     }
 
@@ -138,6 +302,69 @@ impl PropertyManager {
     pub fn wipe_all(&mut self) {
         self.version += 1;
         self.entries.clear();
+        self.links.clear();
+    }
+
+    /// The name of the property for a uniform of a program, each program has its own properties.
+    pub fn scoped_name(program: &ResourceId, uniform: &str) -> String {
+        format!("{program}/{uniform}")
+    }
+    /// Splits a scoped name into the program, and the uniform.
+    pub fn split_scoped_name(name: &str) -> Option<(&str, &str)> {
+        name.split_once('/')
+    }
+    /// The name of the property the uniform of the program is set from.
+    pub fn resolve(&self, program: &ResourceId, uniform: &str) -> String {
+        let scoped = Self::scoped_name(program, uniform);
+        match self.links.get(&scoped) {
+            Some(target) => target.clone(),
+            None => scoped,
+        }
+    }
+    /// Sets the uniform of the program from `target` instead, e.g. the plain uniform name to share one value between programs.
+    ///
+    /// The program's own property becomes `target`, if there is no such property yet.
+    pub fn link(&mut self, program: &ResourceId, uniform: &str, target: &str) {
+        let scoped = Self::scoped_name(program, uniform);
+        if scoped == target {
+            self.unlink(program, uniform);
+            return;
+        }
+        if let Some(p) = self.entries.remove(&scoped) {
+            self.entries.entry(target.to_string()).or_insert(p);
+        }
+        self.links.insert(scoped, target.to_string());
+        self.version += 1;
+    }
+    /// Gives the uniform of the program its own property again, starting with the linked value.
+    pub fn unlink(&mut self, program: &ResourceId, uniform: &str) {
+        let scoped = Self::scoped_name(program, uniform);
+        if let Some(target) = self.links.remove(&scoped) {
+            if let Some(p) = self.entries.get(&target).cloned() {
+                self.entries.entry(scoped).or_insert(p);
+            }
+            self.version += 1;
+        }
+    }
+    /// The properties of the uniform in all programs, e.g. for values set by the app.
+    pub fn uniform_entries_mut<'a>(
+        &'a mut self,
+        uniform: &'a str,
+    ) -> impl Iterator<Item = &'a mut Property> + 'a {
+        self.entries
+            .iter_mut()
+            .filter(move |(k, _)| {
+                *k == uniform || Self::split_scoped_name(k).is_some_and(|(_, u)| u == uniform)
+            })
+            .map(|(_, p)| p)
+    }
+    /// The scoped names linked to `target`.
+    pub fn linked_to(&self, target: &str) -> Vec<&str> {
+        self.links
+            .iter()
+            .filter(|(_, t)| *t == target)
+            .map(|(s, _)| s.as_str())
+            .collect()
     }
     pub fn ensure_property_f32(&mut self, name: &str, default_value: f32) {
         if !self.entries.contains_key(name) {
@@ -151,6 +378,14 @@ impl PropertyManager {
         }
     }
 
+    pub fn ensure_property_f64(&mut self, name: &str, default_value: f64) {
+        if !self.entries.contains_key(name) {
+            self.add_entry(name, Property::default_f64(default_value));
+        } else {
+            // :TODO: ensure type is correct
+        }
+    }
+
     pub fn ensure_property_vec2_f32(&mut self, name: &str, default_values: &[f32; 2]) {
         if !self.entries.contains_key(name) {
             self.add_entry(name, Property::default_vec2_f32(default_values));
@@ -190,9 +425,11 @@ impl PropertyManager {
         if let Some(p) = self.entries.get_mut(name) {
             if name.ends_with("_rgb") {
                 match p.config {
-                    PropertyConfig::ColorRgb {} => {}
+                    PropertyConfig::ColorRgb { .. } => {}
                     _ => {
-                        p.config = PropertyConfig::ColorRgb {};
+                        p.config = PropertyConfig::ColorRgb {
+                            space: ColorSpace::default(),
+                        };
                     }
                 }
             }
@@ -226,10 +463,256 @@ impl PropertyManager {
         }
     }
 
+    /// Creates the missing properties for the active uniforms of a program, returns the number of created properties.
+    ///
+    /// Uniforms in `skip`, e.g. set by the flow, get no property.
+    /// Uniforms with an unscoped property, e.g. from older projects, are linked to it.
+    pub fn ensure_program_properties(
+        &mut self,
+        program: &ResourceId,
+        uniforms: &[LinkedUniform],
+        skip: &[String],
+    ) -> usize {
+        let mut created = 0;
+        for u in uniforms {
+            if skip.contains(&u.name) {
+                continue;
+            }
+            if self.link_unscoped(program, &u.name) {
+                continue;
+            }
+            let name = self.resolve(program, &u.name);
+            if self.entries.contains_key(&name) {
+                continue;
+            }
+            match (u.ttype, u.size) {
+                (UniformType::Float, 1) => self.ensure_property_f32(&name, 1.0),
+                (UniformType::Double, 1) => self.ensure_property_f64(&name, 1.0),
+                (UniformType::Vec2Float, 1) => self.ensure_property_vec2_f32(&name, &[1.0, 1.0]),
+                (UniformType::Vec3Float, 1) => {
+                    self.ensure_property_vec3_f32(&name, &[1.0, 1.0, 1.0])
+                }
+                (UniformType::Vec3Float, 4) => {
+                    self.ensure_property_vec3_f32_size4(&name, &[1.0; 3 * 4])
+                }
+                (UniformType::Vec3Float, _) => {
+                    self.add_entry(&name, Property::default_gradient(Gradient::default()));
+                }
+                (UniformType::Mat4Float, 1) => {
+                    self.add_entry(
+                        &name,
+                        Property::default_matrix(Matrix::default_for(&u.name)),
+                    );
+                }
+                (t, size) => {
+                    eprintln!("No matching property for {t:?}[{size}] {}", u.name);
+                    continue;
+                }
+            }
+            created += 1;
+        }
+        created
+    }
+
+    /// Links the uniform to its unscoped property, e.g. from older projects, unless it has its own, returns if it was linked.
+    fn link_unscoped(&mut self, program: &ResourceId, uniform: &str) -> bool {
+        let scoped = Self::scoped_name(program, uniform);
+        if self.links.contains_key(&scoped)
+            || self.entries.contains_key(&scoped)
+            || !self.entries.contains_key(uniform)
+        {
+            return false;
+        }
+        self.link(program, uniform, uniform);
+        true
+    }
+
+    /// Creates the annotated properties of a program, and configures them, returns the number of changed properties.
+    ///
+    /// Values are only set for new properties, so tweaked values survive recompiles.
+    pub fn apply_shader_annotations(
+        &mut self,
+        program: &ResourceId,
+        annotations: &[ShaderAnnotation],
+    ) -> usize {
+        let mut changed = 0;
+        for a in annotations {
+            // before the annotation creates a scoped property hiding the unscoped one
+            self.link_unscoped(program, &a.reflected_name());
+            let name = self.resolve(program, &a.reflected_name());
+            let d = a.default.clone().unwrap_or_default();
+            let scalar = d.first().copied().unwrap_or(1.0);
+            let vector = |i: usize| d.get(i).copied().unwrap_or(scalar);
+            let property = match (a.glsl_type.as_str(), a.size) {
+                ("vec3", _) if a.gradient => Property::default_gradient(Gradient::default()),
+                ("float", 1) => Property::default_f32(scalar),
+                ("double", 1) => Property::default_f64(scalar as f64),
+                ("vec2", 1) => Property::default_vec2_f32(&[vector(0), vector(1)]),
+                ("vec3", 1) => Property::default_vec3_f32(&[vector(0), vector(1), vector(2)]),
+                ("vec3", 4) => {
+                    let values: [f32; 3 * 4] = core::array::from_fn(vector);
+                    Property::default_vec3_f32_size4(&values)
+                }
+                (t, size) => {
+                    eprintln!("Annotations on {t}[{size}] {name} are not supported");
+                    continue;
+                }
+            };
+            if !self.entries.contains_key(&name) {
+                self.add_entry(name.clone(), property);
+            }
+            let Some(p) = self.entries.get_mut(&name) else {
+                continue;
+            };
+            if let Some(gizmo) = a.gizmo {
+                if p.gizmo != gizmo {
+                    p.gizmo = gizmo;
+                    changed += 1;
+                }
+            }
+            let space = if a.linear {
+                ColorSpace::Linear
+            } else {
+                ColorSpace::Srgb
+            };
+            let config = if a.gradient {
+                PropertyConfig::Gradient { space }
+            } else if a.color {
+                match a.size {
+                    1 => PropertyConfig::ColorRgb { space },
+                    _ => PropertyConfig::ColorPal {},
+                }
+            } else if a.range.is_some() || a.step.is_some() {
+                let mut config = match &p.config {
+                    PropertyConfig::F32 { .. } => p.config.clone(),
+                    _ => PropertyConfig::default_f32(),
+                };
+                if let PropertyConfig::F32 {
+                    min_value,
+                    max_value,
+                    step_size,
+                } = &mut config
+                {
+                    if let Some((min, max)) = a.range {
+                        (*min_value, *max_value) = (min, max);
+                    }
+                    if let Some(step) = a.step {
+                        *step_size = step;
+                    }
+                }
+                config
+            } else {
+                continue;
+            };
+            if p.config != config {
+                p.config = config;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    pub fn groups(&self) -> &[PropertyGroup] {
+        &self.groups
+    }
+    pub fn add_group(&mut self, name: &str) -> bool {
+        if name.is_empty() || self.groups.iter().any(|g| g.name == name) {
+            return false;
+        }
+        self.groups.push(PropertyGroup {
+            name: name.to_string(),
+            properties: Vec::default(),
+        });
+        true
+    }
+    /// Removes the group, its properties become ungrouped.
+    pub fn remove_group(&mut self, name: &str) -> bool {
+        let l = self.groups.len();
+        self.groups.retain(|g| g.name != name);
+        l != self.groups.len()
+    }
+    pub fn move_group(&mut self, name: &str, delta: isize) {
+        if let Some(i) = self.groups.iter().position(|g| g.name == name) {
+            let j = i.saturating_add_signed(delta).min(self.groups.len() - 1);
+            self.groups.swap(i, j);
+        }
+    }
+    pub fn group_of(&self, property: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|g| g.properties.iter().any(|p| p == property))
+            .map(|g| g.name.as_str())
+    }
+    /// Moves the property to the end of `group`, or out of all groups for `None`.
+    pub fn set_group(&mut self, property: &str, group: Option<&str>) {
+        for g in self.groups.iter_mut() {
+            g.properties.retain(|p| p != property);
+        }
+        if let Some(g) = self
+            .groups
+            .iter_mut()
+            .find(|g| Some(g.name.as_str()) == group)
+        {
+            g.properties.push(property.to_string());
+        }
+    }
+    /// Moves the property within its group, ungrouped properties are always sorted by name.
+    pub fn move_property(&mut self, property: &str, delta: isize) {
+        for g in self.groups.iter_mut() {
+            if let Some(i) = g.properties.iter().position(|p| p == property) {
+                let j = i.saturating_add_signed(delta).min(g.properties.len() - 1);
+                g.properties.swap(i, j);
+            }
+        }
+    }
+    /// The existing properties in display order, the groups first, followed by the ungrouped ones as `None`.
+    pub fn layout(&self) -> Vec<(Option<&str>, Vec<&str>)> {
+        let mut layout: Vec<(Option<&str>, Vec<&str>)> = self
+            .groups
+            .iter()
+            .map(|g| {
+                let names = g
+                    .properties
+                    .iter()
+                    .filter(|p| self.entries.contains_key(*p))
+                    .map(|p| p.as_str())
+                    .collect();
+                (Some(g.name.as_str()), names)
+            })
+            .collect();
+        let ungrouped = self
+            .entries
+            .keys()
+            .filter(|k| self.group_of(k).is_none())
+            .map(|k| k.as_str())
+            .collect();
+        layout.push((None, ungrouped));
+        layout
+    }
+
+    pub fn set_program_usage(&mut self, resource_id: &ResourceId, uniforms: &[LinkedUniform]) {
+        self.program_usage.insert(
+            resource_id.clone(),
+            uniforms.iter().map(|u| u.name.clone()).collect(),
+        );
+    }
+    /// The linked programs using the property.
+    pub fn programs_using(&self, property: &str) -> Vec<&ResourceId> {
+        let mut programs: Vec<&ResourceId> = self
+            .program_usage
+            .iter()
+            .filter(|(id, uniforms)| uniforms.iter().any(|u| self.resolve(id, u) == property))
+            .map(|(id, _)| id)
+            .collect();
+        programs.sort();
+        programs
+    }
+
     pub fn ensure_all_properties_from_uniforms(&mut self, uniform_manager: &UniformManager) {
         for (k, v) in uniform_manager.entries().iter() {
             match v.ttype() {
                 UniformType::Float => self.ensure_property_f32(k, 1.0),
+                UniformType::Double => self.ensure_property_f64(k, 1.0),
                 UniformType::Vec2Float => self.ensure_property_vec2_f32(k, &[1.0, 1.0]),
                 UniformType::Vec3Float => match v.size() {
                     1 => self.ensure_property_vec3_f32(k, &[1.0, 1.0, 1.0]),
@@ -243,3 +726,173 @@ impl PropertyManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::srgb_to_linear;
+
+    #[test]
+    fn program_scopes_work() {
+        let uniforms = [
+            LinkedUniform {
+                name: "fSpeed".into(),
+                ttype: UniformType::Float,
+                size: 1,
+            },
+            LinkedUniform {
+                name: "fTime".into(),
+                ttype: UniformType::Float,
+                size: 1,
+            },
+        ];
+        let (a, b) = (ResourceId::from("a"), ResourceId::from("b"));
+        let skip = ["fTime".to_string()];
+        let mut pm = PropertyManager::default();
+        assert_eq!(1, pm.ensure_program_properties(&a, &uniforms, &skip));
+        assert_eq!(1, pm.ensure_program_properties(&b, &uniforms, &skip));
+        assert_eq!(
+            vec!["a/fSpeed", "b/fSpeed"],
+            pm.entries().keys().collect::<Vec<_>>()
+        );
+
+        pm.get_mut("b/fSpeed")
+            .unwrap()
+            .value_mut()
+            .set_from_f64s(&[3.0]);
+        pm.link(&b, "fSpeed", "fSpeed");
+        pm.link(&a, "fSpeed", "fSpeed");
+        assert_eq!("fSpeed", pm.resolve(&a, "fSpeed"));
+        assert_eq!(vec!["fSpeed"], pm.entries().keys().collect::<Vec<_>>());
+        assert_eq!(vec!["a/fSpeed", "b/fSpeed"], pm.linked_to("fSpeed"));
+
+        pm.unlink(&a, "fSpeed");
+        assert_eq!("a/fSpeed", pm.resolve(&a, "fSpeed"));
+        let speed = pm.entries().get("a/fSpeed").unwrap();
+        assert!(matches!(speed.value(), PropertyValue::F32 { value } if *value == 3.0));
+
+        // unscoped properties from older projects are shared
+        let mut pm = PropertyManager::default();
+        pm.add_entry("fSpeed", Property::default_f32(5.0));
+        assert_eq!(0, pm.ensure_program_properties(&a, &uniforms, &skip));
+        assert_eq!("fSpeed", pm.resolve(&a, "fSpeed"));
+    }
+
+    #[test]
+    fn shader_value_converts_colors() {
+        let mut color = Property::default_vec3_f32(&[0.5, 0.5, 0.5]);
+        color.config = PropertyConfig::ColorRgb {
+            space: ColorSpace::Srgb,
+        };
+        let uploaded = |p: &Property| match p.shader_value() {
+            PropertyValue::Vec3F32 { values } => values,
+            o => panic!("should stay a vec3, got {o:?}"),
+        };
+        assert_eq!([0.5, 0.5, 0.5], uploaded(&color));
+        color.config = PropertyConfig::ColorRgb {
+            space: ColorSpace::Linear,
+        };
+        let values = uploaded(&color);
+        assert!((values[0] - 0.2140).abs() < 1e-3, "{values:?}");
+
+        let mut gradient = Property::default_gradient(Gradient::default());
+        if let PropertyConfig::Gradient { space } = &mut gradient.config {
+            *space = ColorSpace::Linear;
+        }
+        let PropertyValue::Gradient { gradient } = gradient.shader_value() else {
+            panic!("should stay a gradient");
+        };
+        let mid = gradient.sample(0.5);
+        let expected = Gradient::default().sample(0.5).map(srgb_to_linear);
+        assert!((mid[0] - expected[0]).abs() < 1e-2, "{mid:?} {expected:?}");
+    }
+
+    #[test]
+    fn groups_work() {
+        let mut pm = PropertyManager::default();
+        for name in ["a", "b", "c", "d"] {
+            pm.add_entry(name, Property::default_f32(0.0));
+        }
+        assert!(pm.add_group("colors"));
+        assert!(pm.add_group("motion"));
+        assert!(!pm.add_group("motion"));
+        pm.set_group("d", Some("colors"));
+        pm.set_group("b", Some("colors"));
+        pm.set_group("c", Some("motion"));
+        pm.set_group("ghost", Some("motion"));
+        pm.move_property("b", -1);
+        pm.move_group("motion", -1);
+        assert_eq!(
+            vec![
+                (Some("motion"), vec!["c"]),
+                (Some("colors"), vec!["b", "d"]),
+                (None, vec!["a"]),
+            ],
+            pm.layout()
+        );
+
+        pm.delete_entry("b");
+        assert!(pm.remove_group("colors"));
+        assert_eq!(None, pm.group_of("d"));
+        assert_eq!(
+            vec![(Some("motion"), vec!["c"]), (None, vec!["a", "d"])],
+            pm.layout()
+        );
+    }
+
+    #[test]
+    fn apply_shader_annotations_works() {
+        let annotations = ShaderAnnotation::parse(
+            "uniform float fSpeed; // @range(0,10) @step(0.5) @default(2)\n\
+             uniform vec3 vPal[4]; // @color\n",
+        );
+        let program = ResourceId::from("prog");
+        let mut pm = PropertyManager::default();
+        pm.add_entry(
+            "prog/vPal[0]",
+            Property::default_vec3_f32_size4(&[0.5; 3 * 4]),
+        );
+
+        assert_eq!(2, pm.apply_shader_annotations(&program, &annotations));
+        let speed = pm.entries().get("prog/fSpeed").unwrap();
+        assert!(matches!(speed.value(), PropertyValue::F32 { value } if *value == 2.0));
+        assert_eq!(
+            &PropertyConfig::F32 {
+                min_value: 0.0,
+                max_value: 10.0,
+                step_size: 0.5,
+            },
+            speed.config()
+        );
+        let pal = pm.entries().get("prog/vPal[0]").unwrap();
+        assert_eq!(&PropertyConfig::ColorPal {}, pal.config());
+        assert!(matches!(pal.value(), PropertyValue::Vec3F32Size4 { values } if values[0] == 0.5));
+
+        // tweaked values survive
+        pm.get_mut("prog/fSpeed")
+            .unwrap()
+            .value_mut()
+            .set_from_f64s(&[7.0]);
+        assert_eq!(0, pm.apply_shader_annotations(&program, &annotations));
+        let speed = pm.entries().get("prog/fSpeed").unwrap();
+        assert!(matches!(speed.value(), PropertyValue::F32 { value } if *value == 7.0));
+
+        // unscoped properties from older projects keep their values
+        let mut pm = PropertyManager::default();
+        pm.add_entry("fSpeed", Property::default_f32(5.0));
+        pm.apply_shader_annotations(&program, &annotations);
+        let uniforms = [LinkedUniform {
+            name: "fSpeed".into(),
+            ttype: UniformType::Float,
+            size: 1,
+        }];
+        assert_eq!(0, pm.ensure_program_properties(&program, &uniforms, &[]));
+        assert_eq!("fSpeed", pm.resolve(&program, "fSpeed"));
+        assert!(!pm.entries().contains_key("prog/fSpeed"));
+        let speed = pm.entries().get("fSpeed").unwrap();
+        assert!(matches!(speed.value(), PropertyValue::F32 { value } if *value == 5.0));
+        assert!(
+            matches!(speed.config(), PropertyConfig::F32 { max_value, .. } if *max_value == 10.0)
+        );
+    }
+}
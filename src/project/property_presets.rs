@@ -0,0 +1,171 @@
+use crate::project::PropertyManager;
+use crate::project::PropertyValue;
+use std::collections::BTreeMap;
+use web_time::Duration;
+use web_time::Instant;
+
+/// A named snapshot of property values.
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PropertyPreset {
+    pub name: String,
+    pub values: BTreeMap<String, PropertyValue>,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PropertyPresets {
+    presets: Vec<PropertyPreset>,
+}
+
+impl PropertyPresets {
+    pub fn presets(&self) -> &[PropertyPreset] {
+        &self.presets
+    }
+    pub fn get(&self, name: &str) -> Option<&PropertyPreset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    /// Captures the current values, of all properties, or only of the ones in `subset`.
+    ///
+    /// An existing preset with the same name is replaced.
+    pub fn capture(&mut self, name: &str, pm: &PropertyManager, subset: Option<&[String]>) {
+        let values = pm
+            .entries()
+            .iter()
+            .filter(|(k, _)| subset.is_none_or(|s| s.contains(k)))
+            .map(|(k, p)| (k.clone(), p.value().clone()))
+            .collect();
+        let preset = PropertyPreset {
+            name: name.to_string(),
+            values,
+        };
+        match self.presets.iter_mut().find(|p| p.name == name) {
+            Some(p) => *p = preset,
+            None => self.presets.push(preset),
+        }
+    }
+    pub fn remove(&mut self, name: &str) -> bool {
+        let l = self.presets.len();
+        self.presets.retain(|p| p.name != name);
+        l != self.presets.len()
+    }
+
+    /// Sets all properties stored in the preset, returns `false` if there is no such preset.
+    pub fn recall(&self, name: &str, pm: &mut PropertyManager) -> bool {
+        self.morph(name, name, 0.0, pm)
+    }
+
+    /// Sets the properties to the blend of the presets `from` and `to` at `t`, `0..1`.
+    ///
+    /// Properties only stored in one of the presets are taken from that one,
+    /// stored values whose type no longer matches the property are skipped.
+    pub fn morph(&self, from: &str, to: &str, t: f32, pm: &mut PropertyManager) -> bool {
+        let (Some(from), Some(to)) = (self.get(from), self.get(to)) else {
+            return false;
+        };
+        for (k, p) in pm.entries_mut().iter_mut() {
+            let kind = core::mem::discriminant(p.value());
+            let matches = |v: &&PropertyValue| core::mem::discriminant(*v) == kind;
+            let value = match (
+                from.values.get(k).filter(matches),
+                to.values.get(k).filter(matches),
+            ) {
+                (Some(a), Some(b)) => a.lerp(b, t),
+                (Some(v), None) | (None, Some(v)) => Some(v.clone()),
+                (None, None) => None,
+            };
+            if let Some(value) = value {
+                *p.value_mut() = value;
+            }
+        }
+        true
+    }
+}
+
+/// A timed morph from one preset to another.
+#[derive(Debug, Clone)]
+pub struct PresetTransition {
+    pub from: String,
+    pub to: String,
+    start: Instant,
+    duration: Duration,
+}
+
+impl PresetTransition {
+    pub fn new(from: &str, to: &str, duration: Duration) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            start: Instant::now(),
+            duration,
+        }
+    }
+
+    /// The progress, `0..1`, with a smoothstep ease.
+    pub fn t(&self) -> f32 {
+        let linear = if self.duration.is_zero() {
+            1.0
+        } else {
+            (self.start.elapsed().as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        };
+        linear * linear * (3.0 - 2.0 * linear)
+    }
+    pub fn is_done(&self) -> bool {
+        self.start.elapsed() >= self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Property;
+
+    #[test]
+    fn morph_works() {
+        let mut pm = PropertyManager::default();
+        pm.add_entry("fSpeed", Property::default_f32(0.0));
+        pm.add_entry("vColor_rgb", Property::default_vec3_f32(&[0.0, 0.0, 0.0]));
+        pm.add_entry("fKeep", Property::default_f32(7.0));
+
+        let mut presets = PropertyPresets::default();
+        presets.capture("dark", &pm, None);
+        for (k, p) in pm.entries_mut().iter_mut() {
+            if k != "fKeep" {
+                p.value_mut().set_from_f64s(&[2.0, 4.0, 8.0]);
+            }
+        }
+        presets.capture("bright", &pm, Some(&["vColor_rgb".to_string()]));
+        assert_eq!(1, presets.get("bright").unwrap().values.len());
+
+        assert!(presets.morph("dark", "bright", 0.25, &mut pm));
+        match pm.entries().get("vColor_rgb").map(|p| p.value()) {
+            Some(PropertyValue::Vec3F32 { values }) => assert_eq!([0.5, 1.0, 2.0], *values),
+            o => panic!("unexpected {o:?}"),
+        }
+        // only in `dark`
+        match pm.entries().get("fSpeed").map(|p| p.value()) {
+            Some(PropertyValue::F32 { value }) => assert_eq!(0.0, *value),
+            o => panic!("unexpected {o:?}"),
+        }
+
+        assert!(presets.recall("bright", &mut pm));
+        assert!(!presets.recall("missing", &mut pm));
+        assert!(presets.remove("dark"));
+        assert_eq!(1, presets.presets().len());
+    }
+
+    #[test]
+    fn changed_types_are_skipped() {
+        let mut pm = PropertyManager::default();
+        pm.add_entry("counter", Property::default_vec3_f32(&[1.0, 2.0, 3.0]));
+        let mut presets = PropertyPresets::default();
+        presets.capture("old", &pm, None);
+
+        // the shader now declares it a `float`
+        pm.add_entry("counter", Property::default_f32(5.0));
+        assert!(presets.recall("old", &mut pm));
+        match pm.entries().get("counter").map(|p| p.value()) {
+            Some(PropertyValue::F32 { value }) => assert_eq!(5.0, *value),
+            o => panic!("unexpected {o:?}"),
+        }
+    }
+}
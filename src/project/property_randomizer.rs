@@ -0,0 +1,347 @@
+use crate::project::oklab_to_srgb;
+use crate::project::srgb_to_oklab;
+use crate::project::Gradient;
+use crate::project::Property;
+use crate::project::PropertyConfig;
+use crate::project::PropertyManager;
+use crate::project::PropertyValue;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+type Snapshot = BTreeMap<String, PropertyValue>;
+
+/// Randomizes and mutates properties within their configured ranges.
+///
+/// Colours are randomized in OKLCH, so random colours have similar lightness and saturation.
+/// Every variation is recorded, and can be stepped back and forth through.
+#[derive(Debug)]
+pub struct PropertyRandomizer {
+    seed: u64,
+    rng: StdRng,
+    locked: BTreeSet<String>,
+    history: Vec<Snapshot>,
+    history_pos: usize,
+}
+
+impl Default for PropertyRandomizer {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+impl PropertyRandomizer {
+    const MAX_HISTORY: usize = 256;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            locked: BTreeSet::default(),
+            history: Vec::default(),
+            history_pos: 0,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// Restarts the random sequence, the same seed gives the same variations.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn is_locked(&self, name: &str) -> bool {
+        self.locked.contains(name)
+    }
+    pub fn set_locked(&mut self, name: &str, locked: bool) {
+        if locked {
+            self.locked.insert(name.to_string());
+        } else {
+            self.locked.remove(name);
+        }
+    }
+
+    /// Randomizes all unlocked properties accepted by `filter`.
+    pub fn randomize<F>(&mut self, pm: &mut PropertyManager, filter: F)
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.vary(pm, filter, None);
+    }
+
+    /// Nudges all unlocked properties accepted by `filter` by up to `amount`, `0..1` of their range.
+    pub fn mutate<F>(&mut self, pm: &mut PropertyManager, filter: F, amount: f32)
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.vary(pm, filter, Some(amount));
+    }
+
+    fn vary<F>(&mut self, pm: &mut PropertyManager, filter: F, amount: Option<f32>)
+    where
+        F: Fn(&str) -> bool,
+    {
+        if self.history.is_empty() {
+            self.history.push(Self::snapshot(pm));
+        }
+        for (k, p) in pm.entries_mut().iter_mut() {
+            if self.locked.contains(k) || !filter(k) {
+                continue;
+            }
+            match amount {
+                None => Self::randomize_property(&mut self.rng, p),
+                Some(amount) => Self::mutate_property(&mut self.rng, p, amount),
+            }
+        }
+        self.history.truncate(self.history_pos + 1);
+        self.history.push(Self::snapshot(pm));
+        if self.history.len() > Self::MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history_pos = self.history.len() - 1;
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.history_pos > 0
+    }
+    pub fn can_go_forward(&self) -> bool {
+        self.history_pos + 1 < self.history.len()
+    }
+    /// Restores the previous variation.
+    pub fn back(&mut self, pm: &mut PropertyManager) {
+        if self.can_go_back() {
+            self.history_pos -= 1;
+            self.restore(pm);
+        }
+    }
+    pub fn forward(&mut self, pm: &mut PropertyManager) {
+        if self.can_go_forward() {
+            self.history_pos += 1;
+            self.restore(pm);
+        }
+    }
+    /// The position in, and the length of the history.
+    pub fn history_position(&self) -> (usize, usize) {
+        (self.history_pos, self.history.len())
+    }
+
+    fn snapshot(pm: &PropertyManager) -> Snapshot {
+        pm.entries()
+            .iter()
+            .map(|(k, p)| (k.clone(), p.value().clone()))
+            .collect()
+    }
+    fn restore(&self, pm: &mut PropertyManager) {
+        let Some(snapshot) = self.history.get(self.history_pos) else {
+            return;
+        };
+        for (k, v) in snapshot.iter() {
+            if let Some(p) = pm.get_mut(k) {
+                *p.value_mut() = v.clone();
+            }
+        }
+    }
+
+    fn range(config: &PropertyConfig) -> (f32, f32, f32) {
+        match config {
+            PropertyConfig::F32 {
+                min_value,
+                max_value,
+                step_size,
+            } => (*min_value, *max_value, *step_size),
+            _ => (0.0, 1.0, 0.0),
+        }
+    }
+
+    fn snap(v: f32, (min_value, max_value, step_size): (f32, f32, f32)) -> f32 {
+        let v = if step_size > 0.0 {
+            min_value + ((v - min_value) / step_size).round() * step_size
+        } else {
+            v
+        };
+        v.clamp(min_value.min(max_value), max_value.max(min_value))
+    }
+
+    fn randomize_property(rng: &mut StdRng, p: &mut Property) {
+        let range = Self::range(&p.config);
+        let (min_value, max_value, _) = range;
+        let random = |rng: &mut StdRng| {
+            Self::snap(
+                rng.gen_range(0.0..=1.0) * (max_value - min_value) + min_value,
+                range,
+            )
+        };
+        match (&mut p.value, &p.config) {
+            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb { .. }) => {
+                *values = random_color(rng);
+            }
+            (PropertyValue::Vec3F32Size4 { values }, PropertyConfig::ColorPal {}) => {
+                *values = random_palette(rng);
+            }
+            (PropertyValue::F32 { value }, _) => *value = random(rng),
+            (PropertyValue::F64 { value }, _) => *value = random(rng) as f64,
+            (PropertyValue::Vec2F32 { values }, _) => {
+                values.iter_mut().for_each(|v| *v = random(rng))
+            }
+            (PropertyValue::Vec3F32 { values }, _) => {
+                values.iter_mut().for_each(|v| *v = random(rng))
+            }
+            (PropertyValue::Vec3F32Size4 { values }, _) => {
+                values.iter_mut().for_each(|v| *v = random(rng))
+            }
+            (PropertyValue::Bool { value }, _) => *value = rng.gen_bool(0.5),
+            (PropertyValue::Gradient { gradient }, _) => match gradient {
+                Gradient::Stops { stops, .. } => {
+                    stops.iter_mut().for_each(|s| s.color = random_color(rng))
+                }
+                Gradient::Cosine { values } => *values = random_palette(rng),
+            },
+            // cameras and transforms would mostly end up off screen
+            (PropertyValue::Matrix { .. }, _) | (PropertyValue::None, _) => {}
+        }
+    }
+
+    fn mutate_property(rng: &mut StdRng, p: &mut Property, amount: f32) {
+        let range = Self::range(&p.config);
+        let (min_value, max_value, _) = range;
+        let nudge = (max_value - min_value).abs() * amount;
+        let mutate =
+            |rng: &mut StdRng, v: f32| Self::snap(v + rng.gen_range(-1.0..=1.0) * nudge, range);
+        match (&mut p.value, &p.config) {
+            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb { .. }) => {
+                *values = mutate_color(rng, values, amount);
+            }
+            (PropertyValue::Vec3F32Size4 { values }, PropertyConfig::ColorPal {}) => {
+                for v in values.iter_mut() {
+                    *v = (*v + rng.gen_range(-1.0..=1.0) * amount).clamp(0.0, 2.0);
+                }
+            }
+            (PropertyValue::F32 { value }, _) => *value = mutate(rng, *value),
+            (PropertyValue::F64 { value }, _) => *value = mutate(rng, *value as f32) as f64,
+            (PropertyValue::Vec2F32 { values }, _) => {
+                values.iter_mut().for_each(|v| *v = mutate(rng, *v))
+            }
+            (PropertyValue::Vec3F32 { values }, _) => {
+                values.iter_mut().for_each(|v| *v = mutate(rng, *v))
+            }
+            (PropertyValue::Vec3F32Size4 { values }, _) => {
+                values.iter_mut().for_each(|v| *v = mutate(rng, *v))
+            }
+            (PropertyValue::Bool { value }, _) => {
+                if rng.gen_bool((amount as f64).clamp(0.0, 1.0)) {
+                    *value = !*value;
+                }
+            }
+            (PropertyValue::Gradient { gradient }, _) => match gradient {
+                Gradient::Stops { stops, .. } => stops
+                    .iter_mut()
+                    .for_each(|s| s.color = mutate_color(rng, &s.color, amount)),
+                Gradient::Cosine { values } => {
+                    for v in values.iter_mut() {
+                        *v = (*v + rng.gen_range(-1.0..=1.0) * amount).clamp(0.0, 2.0);
+                    }
+                }
+            },
+            // cameras and transforms would mostly end up off screen
+            (PropertyValue::Matrix { .. }, _) | (PropertyValue::None, _) => {}
+        }
+    }
+}
+
+/// A colour with moderate lightness and chroma, and a random hue, as sRGB.
+fn random_color(rng: &mut StdRng) -> [f32; 3] {
+    let l = rng.gen_range(0.45..=0.85);
+    let c = rng.gen_range(0.05..=0.18);
+    let h = rng.gen_range(0.0..core::f32::consts::TAU);
+    oklab_to_srgb([l, c * h.cos(), c * h.sin()])
+}
+
+fn mutate_color(rng: &mut StdRng, srgb: &[f32; 3], amount: f32) -> [f32; 3] {
+    let [l, a, b] = srgb_to_oklab(*srgb);
+    oklab_to_srgb([
+        (l + rng.gen_range(-1.0..=1.0) * amount).clamp(0.0, 1.0),
+        a + rng.gen_range(-1.0..=1.0) * amount * 0.4,
+        b + rng.gen_range(-1.0..=1.0) * amount * 0.4,
+    ])
+}
+
+/// A cosine palette `a + b * cos(2pi * (c * t + d))`, with `a` a mid colour, and `b` keeping it in range.
+fn random_palette(rng: &mut StdRng) -> [f32; 3 * 4] {
+    let a = oklab_to_srgb({
+        let h = rng.gen_range(0.0..core::f32::consts::TAU);
+        let c = rng.gen_range(0.0..=0.08);
+        [rng.gen_range(0.55..=0.75), c * h.cos(), c * h.sin()]
+    });
+    let mut values = [0.0; 3 * 4];
+    let frequency = rng.gen_range(0.5..=1.5);
+    for i in 0..3 {
+        values[i] = a[i];
+        values[3 + i] = a[i].min(1.0 - a[i]) * rng.gen_range(0.5..=1.0);
+        values[2 * 3 + i] = frequency * rng.gen_range(0.8..=1.2);
+        values[3 * 3 + i] = rng.gen_range(0.0..1.0);
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::ColorSpace;
+
+    fn f32_of(pm: &PropertyManager, name: &str) -> f32 {
+        match pm.entries().get(name).map(|p| p.value()) {
+            Some(PropertyValue::F32 { value }) => *value,
+            o => panic!("unexpected {o:?}"),
+        }
+    }
+
+    #[test]
+    fn randomize_respects_config_locks_and_history() {
+        let mut pm = PropertyManager::default();
+        let mut p = Property::default_f32(0.0);
+        p.config = PropertyConfig::F32 {
+            min_value: 1.0,
+            max_value: 2.0,
+            step_size: 0.25,
+        };
+        pm.add_entry("fSpeed", p);
+        pm.add_entry("fLocked", Property::default_f32(3.0));
+        let mut color = Property::default_vec3_f32(&[0.0, 0.0, 0.0]);
+        color.config = PropertyConfig::ColorRgb {
+            space: ColorSpace::default(),
+        };
+        pm.add_entry("vColor_rgb", color);
+
+        let mut r = PropertyRandomizer::new(1);
+        r.set_locked("fLocked", true);
+        r.randomize(&mut pm, |_| true);
+        let speed = f32_of(&pm, "fSpeed");
+        assert!((1.0..=2.0).contains(&speed));
+        assert_eq!(0.0, (speed * 4.0).fract());
+        assert_eq!(3.0, f32_of(&pm, "fLocked"));
+        match pm.entries().get("vColor_rgb").map(|p| p.value()) {
+            Some(PropertyValue::Vec3F32 { values }) => {
+                assert!(values.iter().all(|v| (0.0..=1.0).contains(v)));
+                assert!(values.iter().any(|v| *v > 0.0));
+            }
+            o => panic!("unexpected {o:?}"),
+        }
+
+        let mut again = pm.clone();
+        PropertyRandomizer::new(1).randomize(&mut again, |n| n != "fLocked");
+        assert_eq!(speed, f32_of(&again, "fSpeed"));
+
+        r.mutate(&mut pm, |n| n == "fSpeed", 0.25);
+        assert_eq!((2, 3), r.history_position());
+        r.back(&mut pm);
+        r.back(&mut pm);
+        assert_eq!(0.0, f32_of(&pm, "fSpeed"));
+        assert!(!r.can_go_back());
+        r.forward(&mut pm);
+        assert_eq!(speed, f32_of(&pm, "fSpeed"));
+    }
+}
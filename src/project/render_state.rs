@@ -0,0 +1,99 @@
+//! The fixed function state set by the render state steps.
+
+/// How the fragments are combined with the framebuffer, see [`crate::project::Step::Blend`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum BlendMode {
+    /// Replaces.
+    #[default]
+    Off,
+    /// Mixes by the source alpha.
+    Alpha,
+    /// For colours already multiplied by their alpha.
+    Premultiplied,
+    /// Adds, scaled by the source alpha.
+    Additive,
+    /// Multiplies.
+    Multiply,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 5] = [
+        Self::Off,
+        Self::Alpha,
+        Self::Premultiplied,
+        Self::Additive,
+        Self::Multiply,
+    ];
+}
+
+impl core::fmt::Display for BlendMode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::Alpha => write!(f, "Alpha"),
+            Self::Premultiplied => write!(f, "Premultiplied"),
+            Self::Additive => write!(f, "Additive"),
+            Self::Multiply => write!(f, "Multiply"),
+        }
+    }
+}
+
+/// Which fragments pass the depth test, see [`crate::project::Step::Depth`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum DepthTest {
+    /// All pass, the depth is still written if enabled.
+    Off,
+    #[default]
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+}
+
+impl DepthTest {
+    pub const ALL: [DepthTest; 6] = [
+        Self::Off,
+        Self::Less,
+        Self::LessEqual,
+        Self::Greater,
+        Self::GreaterEqual,
+        Self::Equal,
+    ];
+}
+
+impl core::fmt::Display for DepthTest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::Less => write!(f, "<"),
+            Self::LessEqual => write!(f, "<="),
+            Self::Greater => write!(f, ">"),
+            Self::GreaterEqual => write!(f, ">="),
+            Self::Equal => write!(f, "=="),
+        }
+    }
+}
+
+/// Which triangles are dropped, counter clockwise ones face the front, see [`crate::project::Step::Cull`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum CullMode {
+    #[default]
+    Off,
+    Back,
+    Front,
+}
+
+impl CullMode {
+    pub const ALL: [CullMode; 3] = [Self::Off, Self::Back, Self::Front];
+}
+
+impl core::fmt::Display for CullMode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::Back => write!(f, "Back"),
+            Self::Front => write!(f, "Front"),
+        }
+    }
+}
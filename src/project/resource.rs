@@ -1,9 +1,13 @@
+use crate::engine::AudioClip;
+use crate::engine::Mesh;
+use crate::engine::Tempo;
 use crate::path_helper::PathHelper;
 use color_eyre::eyre::eyre;
 use color_eyre::Result;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 pub type ResourceId = String;
 
@@ -11,6 +15,9 @@ pub type ResourceId = String;
 pub enum Resource {
     Text(ResourceText),
     Program(ResourceProgram),
+    Audio(ResourceAudio),
+    Mesh(ResourceMesh),
+    Buffer(ResourceBuffer),
     #[default]
     None,
 }
@@ -19,12 +26,30 @@ impl Resource {
     pub fn version(&mut self) -> u32 {
         match self {
             Resource::Text(rt) => rt.version(),
-            _ => 0,
+            Resource::Audio(ra) => ra.version(),
+            Resource::Mesh(rm) => rm.version(),
+            Resource::Buffer(rb) => rb.version(),
+            Resource::Program(rp) => rp.version(),
+            Resource::None => 0,
         }
     }
     pub fn reload(&mut self, parent: Option<&Path>) -> bool {
         match self {
             Resource::Text(rt) => rt.reload(parent).is_ok(),
+            Resource::Audio(ra) => match ra.reload(parent) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("Failed loading audio {:?} -> {e:?}", ra.file());
+                    false
+                }
+            },
+            Resource::Mesh(rm) => match rm.reload(parent) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("Failed loading mesh {:?} -> {e:?}", rm.file());
+                    false
+                }
+            },
             _ => false,
         }
     }
@@ -39,6 +64,9 @@ impl Resource {
         match self {
             Resource::Text(rt) => rt.name(),
             Resource::Program(rp) => rp.name(),
+            Resource::Audio(ra) => ra.name(),
+            Resource::Mesh(rm) => rm.name(),
+            Resource::Buffer(rb) => rb.name(),
             _ => "",
         }
     }
@@ -134,11 +162,221 @@ impl ResourceText {
     }
 }
 
+/// A soundtrack, WAV or OGG/Vorbis, with its tempo.
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
+pub struct ResourceAudio {
+    #[serde(default)]
+    name: String,
+    file: Option<PathBuf>,
+    #[serde(default = "ResourceAudio::default_bpm")]
+    bpm: f64,
+    #[serde(default = "ResourceAudio::default_beats_per_bar")]
+    beats_per_bar: u32,
+    #[serde(default)]
+    offset: f64,
+
+    #[serde(skip)]
+    version: u32,
+    #[serde(skip)]
+    clip: Option<Arc<AudioClip>>,
+}
+
+impl Default for ResourceAudio {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            file: None,
+            bpm: Self::default_bpm(),
+            beats_per_bar: Self::default_beats_per_bar(),
+            offset: 0.0,
+            version: 0,
+            clip: None,
+        }
+    }
+}
+
+impl ResourceAudio {
+    fn default_bpm() -> f64 {
+        Tempo::default().bpm
+    }
+    fn default_beats_per_bar() -> u32 {
+        Tempo::default().beats_per_bar
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+    pub fn set_file(&mut self, filename: PathBuf) {
+        self.file = Some(filename);
+    }
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    pub fn clip(&self) -> Option<&Arc<AudioClip>> {
+        self.clip.as_ref()
+    }
+
+    pub fn tempo(&self) -> Tempo {
+        Tempo {
+            bpm: self.bpm,
+            beats_per_bar: self.beats_per_bar,
+            offset: self.offset,
+        }
+    }
+    pub fn set_tempo(&mut self, tempo: Tempo) {
+        if tempo != self.tempo() {
+            self.bpm = tempo.bpm;
+            self.beats_per_bar = tempo.beats_per_bar.max(1);
+            self.offset = tempo.offset;
+            self.version += 1;
+        }
+    }
+
+    pub fn reload(&mut self, parent: Option<&Path>) -> Result<()> {
+        if let Some(path) = &self.file {
+            let path = PathHelper::prefix_with(path, parent);
+            eprintln!("Loading from {path:?}");
+            let data = std::fs::read(path)?;
+            self.clip = Some(Arc::new(AudioClip::from_bytes(&data)?));
+            self.version += 1;
+            Ok(())
+        } else {
+            Err(eyre!("No load path set"))
+        }
+    }
+}
+
+/// Triangles for [`crate::project::Step::DrawMesh`], from an OBJ or glTF file.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
+pub struct ResourceMesh {
+    #[serde(default)]
+    name: String,
+    file: Option<PathBuf>,
+
+    #[serde(skip)]
+    version: u32,
+    #[serde(skip)]
+    mesh: Option<Arc<Mesh>>,
+}
+
+impl ResourceMesh {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+    pub fn set_file(&mut self, filename: PathBuf) {
+        self.file = Some(filename);
+    }
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    pub fn mesh(&self) -> Option<&Arc<Mesh>> {
+        self.mesh.as_ref()
+    }
+
+    pub fn reload(&mut self, parent: Option<&Path>) -> Result<()> {
+        if let Some(path) = &self.file {
+            let path = PathHelper::prefix_with(path, parent);
+            eprintln!("Loading from {path:?}");
+            self.mesh = Some(Arc::new(Mesh::from_file(&path)?));
+            self.version += 1;
+            Ok(())
+        } else {
+            Err(eyre!("No load path set"))
+        }
+    }
+}
+
+/// A GPU buffer kept across frames, e.g. the state of a particle simulation.
+///
+/// It is bound as the shader storage block with `layout(std430, binding = N)`,
+/// for compute shaders run by [`crate::project::Step::Dispatch`], and to be read by vertex shaders.
+/// It starts zeroed, and is cleared again when the layout changes, or it is reset.
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
+pub struct ResourceBuffer {
+    #[serde(default)]
+    name: String,
+    binding: u32,
+    elements: u32,
+    /// In bytes, `16` for a `vec4`.
+    stride: u32,
+
+    #[serde(skip)]
+    version: u32,
+}
+
+impl Default for ResourceBuffer {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            binding: 0,
+            elements: 1024,
+            stride: 16,
+            version: 0,
+        }
+    }
+}
+
+impl ResourceBuffer {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+    pub fn binding(&self) -> u32 {
+        self.binding
+    }
+    pub fn elements(&self) -> u32 {
+        self.elements
+    }
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+    /// The size in bytes.
+    pub fn size(&self) -> usize {
+        self.elements as usize * self.stride as usize
+    }
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn set_layout(&mut self, binding: u32, elements: u32, stride: u32) {
+        if (binding, elements, stride) != (self.binding, self.elements, self.stride) {
+            self.binding = binding;
+            self.elements = elements;
+            self.stride = stride;
+            self.version += 1;
+        }
+    }
+    /// Clears the contents on the GPU.
+    pub fn reset(&mut self) {
+        self.version += 1;
+    }
+}
+
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
 pub struct ResourceProgram {
     #[serde(default)]
     name: String,
     shaders: Vec<Shader>,
+    /// What the `iChannel0..3` samplers of Shadertoy shaders read.
+    #[serde(default)]
+    channels: [ChannelInput; 4],
+
+    #[serde(skip)]
+    version: u32,
 }
 
 impl ResourceProgram {
@@ -164,6 +402,52 @@ impl ResourceProgram {
         self.shaders.retain(|s| *s.resource_id() != resource_id);
         l != self.shaders.len()
     }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    /// Compute programs are run by [`crate::project::Step::Dispatch`], and have no other shaders.
+    pub fn is_compute(&self) -> bool {
+        self.shaders
+            .iter()
+            .any(|s| s.shader_type() == ShaderType::Compute)
+    }
+    pub fn channels(&self) -> &[ChannelInput; 4] {
+        &self.channels
+    }
+    pub fn set_channel(&mut self, channel: usize, input: ChannelInput) {
+        if let Some(c) = self.channels.get_mut(channel) {
+            if *c != input {
+                *c = input;
+                self.version += 1;
+            }
+        }
+    }
+}
+
+/// The input bound to one of the `iChannelN` samplers.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ChannelInput {
+    #[default]
+    None,
+    /// The keyboard state, like Shadertoy's keyboard input.
+    Keyboard,
+    /// The flow's previous frame, like a Shadertoy buffer reading itself, see [`crate::project::Feedback`].
+    Feedback,
+}
+
+impl ChannelInput {
+    pub const ALL: [ChannelInput; 3] = [Self::None, Self::Keyboard, Self::Feedback];
+}
+
+impl core::fmt::Display for ChannelInput {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Keyboard => write!(f, "Keyboard"),
+            Self::Feedback => write!(f, "Feedback"),
+        }
+    }
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Default, Clone)]
@@ -192,6 +476,22 @@ pub enum ShaderType {
     #[default]
     Fragment,
     Vertex,
+    /// Code shared by the passes, prepended to the fragment shader, like Shadertoy's common tab.
+    Common,
+    /// Optional, between the vertex and the fragment shader, not on WebGL.
+    Geometry,
+    /// Needs GL 4.3, so not on macOS, or WebGL.
+    Compute,
+}
+
+impl ShaderType {
+    pub const ALL: [ShaderType; 5] = [
+        Self::Fragment,
+        Self::Vertex,
+        Self::Common,
+        Self::Geometry,
+        Self::Compute,
+    ];
 }
 
 impl core::fmt::Display for ShaderType {
@@ -199,6 +499,9 @@ impl core::fmt::Display for ShaderType {
         match self {
             Self::Fragment => write!(f, "Fragment"),
             Self::Vertex => write!(f, "Vertex"),
+            Self::Common => write!(f, "Common"),
+            Self::Geometry => write!(f, "Geometry"),
+            Self::Compute => write!(f, "Compute"),
         }
     }
 }
@@ -208,6 +511,9 @@ impl From<&ShaderType> for &str {
         match st {
             ShaderType::Fragment => "Fragment",
             ShaderType::Vertex => "Vertex",
+            ShaderType::Common => "Common",
+            ShaderType::Geometry => "Geometry",
+            ShaderType::Compute => "Compute",
         }
     }
 }
@@ -0,0 +1,291 @@
+use crate::project::ChannelInput;
+use crate::project::GridPos;
+use crate::project::Project;
+use crate::project::Resource;
+use crate::project::ResourceId;
+use crate::project::ResourceProgram;
+use crate::project::ResourceText;
+use crate::project::ShaderType;
+use crate::project::Step;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::path::PathBuf;
+
+/// Draws the fullscreen quad, the passes are `mainImage` shaders, see [`crate::engine::GlslProfile`].
+const VERTEX_SHADER: &str = r#"#version 410
+layout(location=0)in vec2 v;
+void main() {
+    gl_Position = vec4(v, 0.0, 1.0);
+}
+"#;
+
+/// A shader exported from Shadertoy as JSON, as returned by its API.
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct ShadertoyImport {
+    #[serde(default)]
+    info: ShadertoyInfo,
+    #[serde(default)]
+    renderpass: Vec<ShadertoyPass>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ShadertoyInfo {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ShadertoyPass {
+    #[serde(default)]
+    inputs: Vec<ShadertoyInput>,
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    ttype: String,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ShadertoyInput {
+    #[serde(default)]
+    ctype: String,
+    #[serde(default)]
+    channel: usize,
+}
+
+impl ShadertoyImport {
+    /// Parses a single shader, also when wrapped in `{"Shader": ...}` or an array, then the first is used.
+    pub fn from_json(data: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(data)?;
+        let value = match value {
+            serde_json::Value::Array(mut shaders) if !shaders.is_empty() => {
+                if shaders.len() > 1 {
+                    eprintln!("Found {} shaders, importing the first", shaders.len());
+                }
+                shaders.swap_remove(0)
+            }
+            value => value,
+        };
+        let value = match value {
+            serde_json::Value::Object(mut o) if o.contains_key("Shader") => {
+                o.remove("Shader").unwrap_or_default()
+            }
+            value => value,
+        };
+        let import: Self = serde_json::from_value(value)?;
+        if import.renderpass.is_empty() {
+            return Err(eyre!("No render passes found"));
+        }
+        Ok(import)
+    }
+
+    /// Builds a project drawing the image pass, returns it with the parts that could not be imported.
+    ///
+    /// Every pass becomes a text and a program, the common code is added to each program.
+    /// Buffer passes need offscreen targets the flow does not have, so they are not run.
+    pub fn into_project(self) -> Result<(Project, Vec<String>)> {
+        let mut warnings = Vec::default();
+        let mut project = Project::default();
+        let name = if self.info.name.is_empty() {
+            format!("Shadertoy {}", self.info.id)
+        } else {
+            self.info.name.clone()
+        };
+        *project.name_mut() = name.clone();
+        let prefix = Self::file_name(&format!("shadertoy {}", self.info.id));
+
+        let add_text = |project: &mut Project, name: &str, file: String, text: &str| {
+            let mut rt = ResourceText::default();
+            *rt.name_mut() = name.to_string();
+            rt.set_file(PathBuf::from(file));
+            *rt.text_mut() = text.to_string();
+            project.resource_manager.add(Resource::Text(rt))
+        };
+        let vertex = add_text(
+            &mut project,
+            &format!("{name} Vertex"),
+            format!("{prefix}.vert.glsl"),
+            VERTEX_SHADER,
+        );
+        let common: Vec<ResourceId> = self
+            .renderpass
+            .iter()
+            .filter(|p| p.ttype == "common")
+            .map(|p| {
+                add_text(
+                    &mut project,
+                    &format!("{name} Common"),
+                    format!("{prefix}_common.glsl"),
+                    &p.code,
+                )
+            })
+            .collect();
+
+        let mut image = None;
+        for pass in self.renderpass.iter() {
+            match pass.ttype.as_str() {
+                "image" | "buffer" => {}
+                "common" => continue,
+                o => {
+                    warnings.push(format!("Skipped {} pass '{}'", o, pass.name));
+                    continue;
+                }
+            }
+            let pass_name = if pass.name.is_empty() {
+                String::from("Image")
+            } else {
+                pass.name.clone()
+            };
+            let fragment = add_text(
+                &mut project,
+                &pass_name,
+                format!("{prefix}_{}.frag.glsl", Self::file_name(&pass_name)),
+                &pass.code,
+            );
+
+            let mut rp = ResourceProgram::default();
+            *rp.name_mut() = pass_name.clone();
+            rp.add_shader(ShaderType::Vertex, vertex.clone());
+            rp.add_shader(ShaderType::Fragment, fragment);
+            for c in common.iter() {
+                rp.add_shader(ShaderType::Common, c.clone());
+            }
+            for input in pass.inputs.iter() {
+                match input.ctype.as_str() {
+                    "keyboard" => rp.set_channel(input.channel, ChannelInput::Keyboard),
+                    o => warnings.push(format!(
+                        "iChannel{} of '{pass_name}' reads a {o}, which is not supported, it stays empty",
+                        input.channel
+                    )),
+                }
+            }
+            let program = project.resource_manager.add(Resource::Program(rp));
+
+            if pass.ttype == "image" && image.is_none() {
+                image = Some(program);
+            } else {
+                warnings.push(format!(
+                    "'{pass_name}' is imported as program {program}, but not run, there are no offscreen targets"
+                ));
+            }
+        }
+
+        let image = image.ok_or_else(|| eyre!("No image pass found"))?;
+        project.with_flow_mut(|flow| {
+            for (y, step) in [
+                Step::Label {
+                    name: String::from("start"),
+                    version: 0,
+                },
+                Step::Program {
+                    resource_id: image.clone(),
+                    version: 0,
+                },
+                Step::FullscreenQuad,
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                flow.add_step(&GridPos::new(0, y as u16), step);
+            }
+        });
+
+        Ok((project, warnings))
+    }
+
+    /// Lower case, with anything but letters and digits replaced by `_`.
+    fn file_name(name: &str) -> String {
+        name.trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{"Shader": {
+        "ver": "0.1",
+        "info": {"id": "XsBXWt", "name": "Keys & Buffers"},
+        "renderpass": [
+            {
+                "inputs": [
+                    {"id": 33, "src": "/presets/tex00.jpg", "ctype": "keyboard", "channel": 1},
+                    {"id": 257, "src": "/media/previz/buffer00.png", "ctype": "buffer", "channel": 0}
+                ],
+                "outputs": [{"id": 37, "channel": 0}],
+                "code": "void mainImage(out vec4 c, in vec2 p) { c = vec4(shade(p), 1.0); }",
+                "name": "Image",
+                "type": "image"
+            },
+            {
+                "inputs": [],
+                "outputs": [{"id": 257, "channel": 0}],
+                "code": "void mainImage(out vec4 c, in vec2 p) { c = vec4(0.0); }",
+                "name": "Buffer A",
+                "type": "buffer"
+            },
+            {
+                "inputs": [],
+                "outputs": [],
+                "code": "vec3 shade(vec2 p) { return vec3(p, 0.0); }",
+                "name": "Common",
+                "type": "common"
+            }
+        ]
+    }}"#;
+
+    #[test]
+    fn import_works() {
+        let (project, warnings) = ShadertoyImport::from_json(EXPORT)
+            .unwrap()
+            .into_project()
+            .unwrap();
+        assert_eq!("Keys & Buffers", project.name());
+        assert_eq!(2, warnings.len(), "{warnings:?}");
+
+        let steps: Vec<_> = project.flow().steps().iter().map(|(s, _)| s).collect();
+        assert_eq!(3, steps.len());
+        let Step::Program { resource_id, .. } = steps[1] else {
+            panic!("Expected program, got {:?}", steps[1]);
+        };
+        let Some(Resource::Program(rp)) = project.resource_manager().get(resource_id) else {
+            panic!("Expected program resource {resource_id}");
+        };
+        assert_eq!("Image", rp.name());
+        assert_eq!(
+            vec![ShaderType::Vertex, ShaderType::Fragment, ShaderType::Common],
+            rp.shaders()
+                .iter()
+                .map(|s| s.shader_type())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            [
+                ChannelInput::None,
+                ChannelInput::Keyboard,
+                ChannelInput::None,
+                ChannelInput::None
+            ],
+            *rp.channels()
+        );
+        assert!(project.validate("start").is_empty());
+    }
+
+    #[test]
+    fn from_json_needs_passes() {
+        assert!(ShadertoyImport::from_json(r#"{"info": {"id": "x"}}"#).is_err());
+        assert!(ShadertoyImport::from_json("not json").is_err());
+    }
+}
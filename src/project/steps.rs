@@ -1,6 +1,9 @@
+use crate::project::BlendMode;
+use crate::project::CullMode;
+use crate::project::DepthTest;
 use crate::project::ResourceId;
 
-#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone, PartialEq)]
 pub enum Step {
     FullscreenQuad,
     Program {
@@ -27,6 +30,80 @@ pub enum Step {
         name: String,
         version: u32,
     },
+    /// Draws the triangles of a mesh resource, with the bound program.
+    DrawMesh {
+        resource_id: ResourceId,
+        instances: u32,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// A plane from `-1..1`, facing z, with `subdivisions` quads along each side.
+    Plane {
+        subdivisions: u32,
+        instances: u32,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// A unit sphere, with `subdivisions` rings.
+    Sphere {
+        subdivisions: u32,
+        instances: u32,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// A line strip of `points` from `-1` to `1` along x.
+    LineStrip {
+        points: u32,
+        instances: u32,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// `count` points, or quads, at the origin, to be placed by the vertex shader with `gl_InstanceID`.
+    Particles {
+        count: u32,
+        quads: bool,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// Runs a compute program on `groups` work groups, then waits for its writes, e.g. to buffer resources.
+    Dispatch {
+        resource_id: ResourceId,
+        groups: [u32; 3],
+        #[serde(skip)]
+        version: u32,
+    },
+    /// Clears the colour to `color`, and the depth to `depth`, each if set, in the current viewport.
+    Clear {
+        color: Option<[f32; 4]>,
+        depth: Option<f32>,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// Blends the following draws.
+    Blend {
+        mode: BlendMode,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// Tests the following draws against the depth buffer, and writes their depth if `write`.
+    Depth {
+        test: DepthTest,
+        write: bool,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// Drops the following triangles facing away, or towards the camera.
+    Cull {
+        mode: CullMode,
+        #[serde(skip)]
+        version: u32,
+    },
+    /// Draws the following into `[x, y, width, height]` of the output, as fractions from the bottom left.
+    Viewport {
+        rect: [f32; 4],
+        #[serde(skip)]
+        version: u32,
+    },
     #[default]
     Nop,
 }
@@ -40,9 +117,29 @@ impl Step {
             Self::SetUniformF64 { version, .. } => *version,
             Self::SetUniformVec3F32 { version, .. } => *version,
             Self::Label { version, .. } => *version,
+            Self::DrawMesh { version, .. } => *version,
+            Self::Plane { version, .. } => *version,
+            Self::Sphere { version, .. } => *version,
+            Self::LineStrip { version, .. } => *version,
+            Self::Particles { version, .. } => *version,
+            Self::Dispatch { version, .. } => *version,
+            Self::Clear { version, .. } => *version,
+            Self::Blend { version, .. } => *version,
+            Self::Depth { version, .. } => *version,
+            Self::Cull { version, .. } => *version,
+            Self::Viewport { version, .. } => *version,
             Self::Nop => 0,
         }
     }
+    /// The values of the step, which can be numbers or `${...}` values.
+    pub fn values(&self) -> Vec<&str> {
+        match self {
+            Self::SetUniformF32 { value, .. } => vec![value],
+            Self::SetUniformF64 { value, .. } => vec![value],
+            Self::SetUniformVec3F32 { values, .. } => values.iter().map(|v| v.as_str()).collect(),
+            _ => Vec::default(),
+        }
+    }
     pub fn types() -> &'static [&'static str] {
         &[
             "FullscreenQuad",
@@ -51,6 +148,17 @@ impl Step {
             "SetUniformF64",
             "SetUniformVec3F32",
             "Label",
+            "DrawMesh",
+            "Plane",
+            "Sphere",
+            "LineStrip",
+            "Particles",
+            "Dispatch",
+            "Clear",
+            "Blend",
+            "Depth",
+            "Cull",
+            "Viewport",
             "Nop",
         ]
     }
@@ -64,6 +172,17 @@ impl From<&Step> for String {
             Step::SetUniformF64 { .. } => format!("SetUniformF64"),
             Step::SetUniformVec3F32 { .. } => format!("SetUniformVecF32"),
             Step::Label { .. } => format!("Label"),
+            Step::DrawMesh { .. } => String::from("DrawMesh"),
+            Step::Plane { .. } => String::from("Plane"),
+            Step::Sphere { .. } => String::from("Sphere"),
+            Step::LineStrip { .. } => String::from("LineStrip"),
+            Step::Particles { .. } => String::from("Particles"),
+            Step::Dispatch { .. } => String::from("Dispatch"),
+            Step::Clear { .. } => String::from("Clear"),
+            Step::Blend { .. } => String::from("Blend"),
+            Step::Depth { .. } => String::from("Depth"),
+            Step::Cull { .. } => String::from("Cull"),
+            Step::Viewport { .. } => String::from("Viewport"),
             //Step::Program{ resource_id } => format!("Program {resource_id}"),
             Step::Nop => format!("Nop"),
         }
@@ -97,6 +216,58 @@ impl From<&str> for Step {
                 name: Default::default(),
                 version: 1,
             },
+            "DrawMesh" => Step::DrawMesh {
+                resource_id: Default::default(),
+                instances: 1,
+                version: 1,
+            },
+            "Plane" => Step::Plane {
+                subdivisions: 16,
+                instances: 1,
+                version: 1,
+            },
+            "Sphere" => Step::Sphere {
+                subdivisions: 16,
+                instances: 1,
+                version: 1,
+            },
+            "LineStrip" => Step::LineStrip {
+                points: 256,
+                instances: 1,
+                version: 1,
+            },
+            "Particles" => Step::Particles {
+                count: 1024,
+                quads: true,
+                version: 1,
+            },
+            "Dispatch" => Step::Dispatch {
+                resource_id: Default::default(),
+                groups: [1, 1, 1],
+                version: 1,
+            },
+            "Clear" => Step::Clear {
+                color: Some([0.0, 0.0, 0.0, 1.0]),
+                depth: Some(1.0),
+                version: 1,
+            },
+            "Blend" => Step::Blend {
+                mode: BlendMode::Alpha,
+                version: 1,
+            },
+            "Depth" => Step::Depth {
+                test: DepthTest::Less,
+                write: true,
+                version: 1,
+            },
+            "Cull" => Step::Cull {
+                mode: CullMode::Back,
+                version: 1,
+            },
+            "Viewport" => Step::Viewport {
+                rect: [0.0, 0.0, 1.0, 1.0],
+                version: 1,
+            },
             "Nop" => Step::Nop,
             _ => Step::Nop,
         }
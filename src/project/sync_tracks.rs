@@ -0,0 +1,354 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// How the value moves from a key to the next one, matches the GNU Rocket key types.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum SyncInterpolation {
+    #[default]
+    Step,
+    Linear,
+    Smooth,
+    Ramp,
+}
+
+impl SyncInterpolation {
+    pub const ALL: [SyncInterpolation; 4] = [Self::Step, Self::Linear, Self::Smooth, Self::Ramp];
+
+    pub fn from_u8(v: u8) -> Self {
+        match v {
+            1 => Self::Linear,
+            2 => Self::Smooth,
+            3 => Self::Ramp,
+            _ => Self::Step,
+        }
+    }
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Self::Step => 0,
+            Self::Linear => 1,
+            Self::Smooth => 2,
+            Self::Ramp => 3,
+        }
+    }
+}
+
+impl core::fmt::Display for SyncInterpolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::Step => write!(f, "Step"),
+            Self::Linear => write!(f, "Linear"),
+            Self::Smooth => write!(f, "Smooth"),
+            Self::Ramp => write!(f, "Ramp"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SyncKey {
+    pub row: u32,
+    pub value: f32,
+    pub interpolation: SyncInterpolation,
+}
+
+/// A named float track, with keys sorted by row.
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SyncTrack {
+    keys: Vec<SyncKey>,
+}
+
+impl SyncTrack {
+    pub fn keys(&self) -> &[SyncKey] {
+        &self.keys
+    }
+
+    /// Adds the key, or replaces the one on the same row.
+    pub fn set_key(&mut self, key: SyncKey) {
+        match self.keys.binary_search_by_key(&key.row, |k| k.row) {
+            Ok(i) => self.keys[i] = key,
+            Err(i) => self.keys.insert(i, key),
+        }
+    }
+    pub fn delete_key(&mut self, row: u32) -> Option<SyncKey> {
+        self.keys
+            .binary_search_by_key(&row, |k| k.row)
+            .ok()
+            .map(|i| self.keys.remove(i))
+    }
+
+    /// The value at a fractional row, before the first key it's the first key's value.
+    pub fn value(&self, row: f64) -> f32 {
+        let next = self.keys.partition_point(|k| (k.row as f64) <= row);
+        if next == 0 {
+            return self.keys.first().map(|k| k.value).unwrap_or_default();
+        }
+        let k0 = &self.keys[next - 1];
+        let Some(k1) = self.keys.get(next) else {
+            return k0.value;
+        };
+
+        let t = ((row - k0.row as f64) / (k1.row - k0.row) as f64) as f32;
+        let t = match k0.interpolation {
+            SyncInterpolation::Step => 0.0,
+            SyncInterpolation::Linear => t,
+            SyncInterpolation::Smooth => t * t * (3.0 - 2.0 * t),
+            SyncInterpolation::Ramp => t * t,
+        };
+        k0.value + (k1.value - k0.value) * t
+    }
+
+    /// Writes the track in the binary format of GNU Rocket's `.track` files.
+    pub fn to_rocket_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(4 + self.keys.len() * 9);
+        data.extend_from_slice(&(self.keys.len() as u32).to_le_bytes());
+        for k in self.keys.iter() {
+            data.extend_from_slice(&k.row.to_le_bytes());
+            data.extend_from_slice(&k.value.to_le_bytes());
+            data.push(k.interpolation.as_u8());
+        }
+        data
+    }
+    pub fn from_rocket_bytes(data: &[u8]) -> Result<Self> {
+        let read_u32 = |o: usize| -> Result<u32> {
+            data.get(o..o + 4)
+                .and_then(|b| b.try_into().ok())
+                .map(u32::from_le_bytes)
+                .ok_or_else(|| eyre!("Track data too short"))
+        };
+        let count = read_u32(0)? as usize;
+        let mut track = Self::default();
+        for i in 0..count {
+            let o = 4 + i * 9;
+            track.set_key(SyncKey {
+                row: read_u32(o)?,
+                value: f32::from_bits(read_u32(o + 4)?),
+                interpolation: SyncInterpolation::from_u8(
+                    *data
+                        .get(o + 8)
+                        .ok_or_else(|| eyre!("Track data too short"))?,
+                ),
+            });
+        }
+        Ok(track)
+    }
+}
+
+/// The sync tracks of a project, addressable from step values as `${SYNC:<name>}`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SyncTracks {
+    #[serde(default = "SyncTracks::default_bpm")]
+    bpm: f64,
+    #[serde(default = "SyncTracks::default_rows_per_beat")]
+    rows_per_beat: u32,
+    #[serde(default)]
+    tracks: BTreeMap<String, SyncTrack>,
+
+    #[serde(skip)]
+    version: u32,
+}
+
+impl Default for SyncTracks {
+    fn default() -> Self {
+        Self {
+            bpm: Self::default_bpm(),
+            rows_per_beat: Self::default_rows_per_beat(),
+            tracks: BTreeMap::default(),
+            version: 0,
+        }
+    }
+}
+
+impl SyncTracks {
+    fn default_bpm() -> f64 {
+        120.0
+    }
+    fn default_rows_per_beat() -> u32 {
+        8
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    pub fn bpm(&self) -> f64 {
+        self.bpm
+    }
+    pub fn rows_per_beat(&self) -> u32 {
+        self.rows_per_beat
+    }
+    pub fn set_timing(&mut self, bpm: f64, rows_per_beat: u32) {
+        if bpm > 0.0 && rows_per_beat > 0 && (bpm, rows_per_beat) != (self.bpm, self.rows_per_beat)
+        {
+            self.bpm = bpm;
+            self.rows_per_beat = rows_per_beat;
+            self.version += 1;
+        }
+    }
+
+    pub fn rows_per_second(&self) -> f64 {
+        self.bpm / 60.0 * self.rows_per_beat as f64
+    }
+    pub fn time_to_row(&self, time: f64) -> f64 {
+        time * self.rows_per_second()
+    }
+    /// The row the tracker shows for `time`, rounded, since `row_to_time` can land just below the row.
+    pub fn nearest_row(&self, time: f64) -> u32 {
+        self.time_to_row(time).round() as u32
+    }
+    pub fn row_to_time(&self, row: u32) -> f64 {
+        row as f64 / self.rows_per_second()
+    }
+
+    pub fn tracks(&self) -> &BTreeMap<String, SyncTrack> {
+        &self.tracks
+    }
+    pub fn get(&self, name: &str) -> Option<&SyncTrack> {
+        self.tracks.get(name)
+    }
+    pub fn add_track(&mut self, name: &str) {
+        if !self.tracks.contains_key(name) {
+            self.tracks.insert(name.to_string(), SyncTrack::default());
+            self.version += 1;
+        }
+    }
+    pub fn remove_track(&mut self, name: &str) -> Option<SyncTrack> {
+        self.version += 1;
+        self.tracks.remove(name)
+    }
+    pub fn set_key(&mut self, name: &str, key: SyncKey) {
+        self.tracks
+            .entry(name.to_string())
+            .or_default()
+            .set_key(key);
+        self.version += 1;
+    }
+    pub fn delete_key(&mut self, name: &str, row: u32) -> Option<SyncKey> {
+        let key = self.tracks.get_mut(name).and_then(|t| t.delete_key(row));
+        if key.is_some() {
+            self.version += 1;
+        }
+        key
+    }
+
+    /// Resolves `${SYNC:<name>}` at the given time.
+    pub fn resolve(&self, value: &str, time: f64) -> Option<f32> {
+        let name = value.strip_prefix("${SYNC:")?.strip_suffix('}')?;
+        let track = self.tracks.get(name)?;
+        Some(track.value(self.time_to_row(time)))
+    }
+
+    /// The file GNU Rocket uses for a track, e.g. `sync_cam#x.track` for `cam:x`.
+    pub fn rocket_file_name(name: &str) -> String {
+        let encoded: String = name
+            .chars()
+            .map(|c| match c {
+                ':' => '#',
+                c if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' => c,
+                _ => '_',
+            })
+            .collect();
+        format!("sync_{encoded}.track")
+    }
+
+    /// Writes all tracks as GNU Rocket `.track` files, for players without a tracker.
+    pub fn export_rocket(&self, folder: &Path) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(folder)?;
+        let mut files = Vec::default();
+        for (name, track) in self.tracks.iter() {
+            let file = folder.join(Self::rocket_file_name(name));
+            std::fs::write(&file, track.to_rocket_bytes())?;
+            files.push(file);
+        }
+        Ok(files)
+    }
+    /// Reads the `.track` files for the given track names, missing files are skipped.
+    pub fn import_rocket(&mut self, folder: &Path, names: &[String]) -> Result<usize> {
+        let mut count = 0;
+        for name in names {
+            let file = folder.join(Self::rocket_file_name(name));
+            if let Ok(data) = std::fs::read(file) {
+                self.tracks
+                    .insert(name.clone(), SyncTrack::from_rocket_bytes(&data)?);
+                count += 1;
+            }
+        }
+        if count > 0 {
+            self.version += 1;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(row: u32, value: f32, interpolation: SyncInterpolation) -> SyncKey {
+        SyncKey {
+            row,
+            value,
+            interpolation,
+        }
+    }
+
+    #[test]
+    fn track_value_works() {
+        let mut t = SyncTrack::default();
+        assert_eq!(0.0, t.value(5.0));
+
+        t.set_key(key(10, 2.0, SyncInterpolation::Linear));
+        t.set_key(key(0, 1.0, SyncInterpolation::Step));
+        t.set_key(key(20, 0.0, SyncInterpolation::Step));
+        assert_eq!(1.0, t.value(-1.0));
+        assert_eq!(1.0, t.value(9.9));
+        assert_eq!(2.0, t.value(10.0));
+        assert_eq!(1.0, t.value(15.0));
+        assert_eq!(0.0, t.value(100.0));
+
+        t.set_key(key(10, 2.0, SyncInterpolation::Smooth));
+        assert_eq!(1.0, t.value(15.0));
+        t.set_key(key(10, 2.0, SyncInterpolation::Ramp));
+        assert_eq!(1.5, t.value(15.0));
+
+        assert!(t.delete_key(10).is_some());
+        assert!(t.delete_key(10).is_none());
+        assert_eq!(1.0, t.value(15.0));
+    }
+
+    #[test]
+    fn resolve_uses_rows() {
+        let mut st = SyncTracks::default();
+        st.set_timing(60.0, 4);
+        st.set_key("cam:x", key(0, 0.0, SyncInterpolation::Linear));
+        st.set_key("cam:x", key(4, 1.0, SyncInterpolation::Step));
+
+        assert_eq!(Some(0.5), st.resolve("${SYNC:cam:x}", 0.5));
+        assert_eq!(None, st.resolve("${SYNC:cam:y}", 0.5));
+        assert_eq!(None, st.resolve("${TIME}", 0.5));
+        assert_eq!(1.0, st.row_to_time(4));
+
+        st.set_timing(125.0, 8);
+        assert_eq!(29, st.nearest_row(st.row_to_time(29)));
+    }
+
+    #[test]
+    fn rocket_files_roundtrip() {
+        let mut st = SyncTracks::default();
+        st.set_key("cam:x", key(3, 0.25, SyncInterpolation::Smooth));
+        st.set_key("cam:x", key(9, -1.0, SyncInterpolation::Ramp));
+        st.add_track("empty");
+
+        let folder = std::env::temp_dir().join(format!("sfrs_sync_{}", std::process::id()));
+        let files = st.export_rocket(&folder).unwrap();
+        assert_eq!(2, files.len());
+        assert!(folder.join("sync_cam#x.track").exists());
+
+        let mut loaded = SyncTracks::default();
+        let names = vec!["cam:x".to_string(), "missing".to_string()];
+        assert_eq!(1, loaded.import_rocket(&folder, &names).unwrap());
+        assert_eq!(st.get("cam:x"), loaded.get("cam:x"));
+
+        let _ = std::fs::remove_dir_all(folder);
+    }
+}
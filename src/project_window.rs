@@ -1,5 +1,9 @@
+use crate::command_queue::COMMAND_QUEUE;
+use crate::project::ColorOutput;
+use crate::project::Tonemap;
 use crate::state::State;
 use crate::window::Window;
+use crate::Command;
 
 #[derive(Debug, Default)]
 pub struct ProjectWindow {
@@ -35,6 +39,7 @@ impl Window for ProjectWindow {
 
                 ui.label(pp);
                 ui.horizontal_wrapped(|ui| {
+                    #[cfg(not(target_arch = "wasm32"))]
                     if ui.button("Pick...").clicked() {
                         let current_dir = std::env::current_dir().unwrap_or_else(|_| "/".into());
                         if let Some(path) = rfd::FileDialog::new()
@@ -45,6 +50,7 @@ impl Window for ProjectWindow {
                             state.set_project_path(path);
                         }
                     }
+                    #[cfg(not(target_arch = "wasm32"))]
                     if ui.button("Load...").clicked() {
                         let current_dir = std::env::current_dir().unwrap_or_else(|_| "/".into());
                         if let Some(path) = rfd::FileDialog::new()
@@ -66,6 +72,93 @@ impl Window for ProjectWindow {
                         state.clear_project();
                     }
                 });
+                ui.horizontal_wrapped(|ui| {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        if ui.button("Import Bundle...").clicked() {
+                            Self::import_bundle(state);
+                        }
+                        if ui.button("Export Bundle...").clicked() {
+                            Self::export_bundle(state);
+                        }
+                        if ui
+                            .button("Import Shadertoy...")
+                            .on_hover_text("Replaces the project with a shader exported from Shadertoy as JSON")
+                            .clicked()
+                        {
+                            Self::import_shadertoy(state);
+                        }
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    if ui.button("Upload...").clicked() {
+                        Self::upload_bundle(ctx);
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    if ui
+                        .button("Upload Shadertoy...")
+                        .on_hover_text("Replaces the project with a shader exported from Shadertoy as JSON")
+                        .clicked()
+                    {
+                        Self::upload_shadertoy(ctx);
+                    }
+                    // on web this is the browser's local storage
+                    if ui.button("Save to Storage").clicked() {
+                        let _ = COMMAND_QUEUE.send(Command::SaveProjectToStorage);
+                    }
+                    if ui.button("Load from Storage").clicked() {
+                        let _ = COMMAND_QUEUE.send(Command::LoadProjectFromStorage);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Time Wrap Period");
+                    let mut time_wrap_period = state.project.time_wrap_period();
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut time_wrap_period)
+                                .clamp_range(1.0..=f64::MAX)
+                                .suffix(" s"),
+                        )
+                        .on_hover_text(
+                            "`${TIME}` wraps at this period, shaders can use `${TIME_WRAP}`",
+                        )
+                        .changed()
+                    {
+                        state.project.set_time_wrap_period(time_wrap_period);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let mut color_output = *state.project.color_output();
+                    let mut linear = matches!(color_output, ColorOutput::Linear { .. });
+                    if ui
+                        .checkbox(&mut linear, "Linear Output")
+                        .on_hover_text("Renders to a linear, high dynamic range target, which is tonemapped and encoded as sRGB for the screen")
+                        .changed()
+                    {
+                        color_output = if linear {
+                            ColorOutput::default_linear()
+                        } else {
+                            ColorOutput::Direct
+                        };
+                    }
+                    if let ColorOutput::Linear { tonemap, exposure } = &mut color_output {
+                        egui::ComboBox::from_label("Tonemap")
+                            .selected_text(format!("{tonemap}"))
+                            .show_ui(ui, |ui| {
+                                for t in Tonemap::ALL {
+                                    ui.selectable_value(tonemap, t, format!("{t}"));
+                                }
+                            });
+                        ui.add(
+                            egui::DragValue::new(exposure)
+                                .speed(0.01)
+                                .clamp_range(0.0..=f32::MAX)
+                                .prefix("Exposure "),
+                        );
+                    }
+                    if color_output != *state.project.color_output() {
+                        state.project.set_color_output(color_output);
+                    }
+                });
                 ui.label("Recent Projects");
                 let mut picked = None;
                 let mut load = false;
@@ -92,4 +185,97 @@ impl Window for ProjectWindow {
     }
 }
 
-impl ProjectWindow {}
+impl ProjectWindow {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_bundle(state: &State) {
+        let current_dir = std::env::current_dir().unwrap_or_else(|_| "/".into());
+        if let Some(path) = rfd::FileDialog::new()
+            .set_directory(state.project_path.as_ref().unwrap_or(&current_dir))
+            .add_filter("Project Bundle", &["ron"])
+            .pick_file()
+        {
+            match std::fs::read_to_string(&path) {
+                Ok(data) => {
+                    let _ = COMMAND_QUEUE.send(Command::LoadProjectBundle { data });
+                }
+                Err(e) => {
+                    // :TODO: report
+                    eprintln!("Failed importing bundle {e:#?}");
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_shadertoy(state: &State) {
+        let current_dir = std::env::current_dir().unwrap_or_else(|_| "/".into());
+        if let Some(path) = rfd::FileDialog::new()
+            .set_directory(state.project_path.as_ref().unwrap_or(&current_dir))
+            .add_filter("Shadertoy JSON", &["json"])
+            .pick_file()
+        {
+            match std::fs::read_to_string(&path) {
+                Ok(data) => {
+                    let _ = COMMAND_QUEUE.send(Command::ImportShadertoy { data });
+                }
+                Err(e) => {
+                    // :TODO: report
+                    eprintln!("Failed importing Shadertoy shader {e:#?}");
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_bundle(state: &State) {
+        let current_dir = std::env::current_dir().unwrap_or_else(|_| "/".into());
+        if let Some(path) = rfd::FileDialog::new()
+            .set_directory(state.project_path.as_ref().unwrap_or(&current_dir))
+            .set_file_name("sfrs-bundle.ron")
+            .save_file()
+        {
+            let result = state
+                .project
+                .to_bundle()
+                .and_then(|data| Ok(std::fs::write(&path, data)?));
+            if let Err(e) = result {
+                // :TODO: report
+                eprintln!("Failed exporting bundle {e:#?}");
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn upload_bundle(ctx: &egui::Context) {
+        let ctx = ctx.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("Project Bundle", &["ron"])
+                .pick_file()
+                .await
+            {
+                let data = file.read().await;
+                let data = String::from_utf8_lossy(&data).to_string();
+                let _ = COMMAND_QUEUE.send(Command::LoadProjectBundle { data });
+                ctx.request_repaint();
+            }
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn upload_shadertoy(ctx: &egui::Context) {
+        let ctx = ctx.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("Shadertoy JSON", &["json"])
+                .pick_file()
+                .await
+            {
+                let data = file.read().await;
+                let data = String::from_utf8_lossy(&data).to_string();
+                let _ = COMMAND_QUEUE.send(Command::ImportShadertoy { data });
+                ctx.request_repaint();
+            }
+        });
+    }
+}
@@ -1,23 +1,93 @@
+use crate::project::MidiSource;
+use crate::project::PropertyManager;
+use crate::project::PropertyRandomizer;
+use crate::project::Resource;
+use crate::project::ResourceId;
+use crate::project::Step;
 use crate::property_ui::PropertyUi;
 use crate::state::State;
 use crate::window::Window;
+use std::collections::HashMap;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
+enum PropertyAction {
+    /// Links the uniform of the scoped property to the unscoped one.
+    Share(String),
+    /// Gives all programs sharing the property their own again.
+    Unshare(String),
+    MoveGroup(String, isize),
+    RemoveGroup(String),
+    SetGroup(String, Option<String>),
+    MoveProperty(String, isize),
+    Randomize(Vec<String>),
+    /// Binds the next MIDI control to the property, or stops learning.
+    MidiLearn(Option<String>),
+    MidiUnbind(String),
+    Lock(String, bool),
+}
+
+/// What the property rows show besides the property itself,
+/// collected before the property manager is borrowed.
+struct PropertyRowsContext<'a> {
+    group_names: Vec<String>,
+    midi_sources: Vec<(String, MidiSource)>,
+    program_names: HashMap<ResourceId, String>,
+    midi_learn: Option<&'a str>,
+    randomizer: &'a PropertyRandomizer,
+}
+
+#[derive(Debug)]
 pub struct PropertiesWindow {
     property_ui: PropertyUi,
     is_open: bool,
+    #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+    midi_port_names: Vec<String>,
+    /// The ports are only enumerated once, and then on "Refresh MIDI".
+    #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+    midi_ports_scanned: bool,
+    /// Properties containing this are randomized by "Randomize Matching".
+    randomize_filter: String,
+    mutate_amount: f32,
+    search: String,
+    /// Only show properties of programs in the flow.
+    used_by_flow_only: bool,
+    new_group_name: String,
+    /// Opens, or closes, all groups and properties for one frame.
+    open_all: Option<bool>,
+}
+
+impl Default for PropertiesWindow {
+    fn default() -> Self {
+        Self {
+            property_ui: PropertyUi::default(),
+            is_open: false,
+            #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+            midi_port_names: Vec::default(),
+            #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+            midi_ports_scanned: false,
+            randomize_filter: String::default(),
+            mutate_amount: 0.05,
+            search: String::default(),
+            used_by_flow_only: false,
+            new_group_name: String::default(),
+            open_all: None,
+        }
+    }
 }
 
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
 struct PropertiesWindowSave {
     #[serde(default)]
     is_open: bool,
+    #[serde(default)]
+    used_by_flow_only: bool,
 }
 
 impl From<&PropertiesWindow> for PropertiesWindowSave {
     fn from(pw: &PropertiesWindow) -> Self {
         Self {
             is_open: pw.is_open,
+            used_by_flow_only: pw.used_by_flow_only,
         }
     }
 }
@@ -34,6 +104,7 @@ impl Window for PropertiesWindow {
     }
 
     fn update(&mut self, ctx: &egui::Context, state: &mut State) {
+        let mut is_open = self.is_open;
         egui::Window::new("Properties")
             .resizable(true)
             //.min_width( 1000.0 )
@@ -41,7 +112,7 @@ impl Window for PropertiesWindow {
             .vscroll(true)
             .collapsible(false)
             //.title_bar(false)
-            .open(&mut self.is_open)
+            .open(&mut is_open)
             .show(ctx, |ui| {
                 let enabled = true;
                 if ui
@@ -53,13 +124,19 @@ impl Window for PropertiesWindow {
                     //state.project.property_manager.wipe_all();
                 }
 
-                state.project.with_property_manager_mut(|pm| {
-                    for (k, p) in pm.entries_mut().iter_mut() {
-                        self.property_ui.property(ctx, ui, k, p);
-                    }
-                });
+                self.update_midi_input(ui, state);
+                self.update_randomizer(ui, state);
+
+                self.update_filters(ui, state);
+                self.property_ui.set_open(self.open_all);
+                ui.separator();
+
+                self.update_properties(ctx, ui, state);
+                self.open_all = None;
+                self.property_ui.set_open(None);
                 ui.allocate_space(egui::vec2(333.0, 0.0))
             });
+        self.is_open = is_open;
         self.property_ui.update(ctx);
     }
     fn serialize(&self) -> String {
@@ -71,7 +148,416 @@ impl Window for PropertiesWindow {
         let save: PropertiesWindowSave = ron::from_str(&data).unwrap_or_default();
 
         self.is_open = save.is_open;
+        self.used_by_flow_only = save.used_by_flow_only;
     }
 }
 
-impl PropertiesWindow {}
+impl PropertiesWindow {
+    fn update_properties(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, state: &mut State) {
+        let flow_programs: Vec<ResourceId> = state
+            .project
+            .flow()
+            .steps()
+            .iter()
+            .filter_map(|(s, _)| match s {
+                Step::Program { resource_id, .. } => Some(resource_id.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut rows = PropertyRowsContext {
+            group_names: Vec::default(),
+            midi_sources: state
+                .project
+                .midi_bindings()
+                .bindings()
+                .iter()
+                .map(|b| (b.property.clone(), b.source))
+                .collect(),
+            program_names: state
+                .project
+                .resource_manager()
+                .resources()
+                .iter()
+                .filter_map(|(id, r)| match r {
+                    Resource::Program(rp) => Some((id.clone(), rp.name().to_string())),
+                    _ => None,
+                })
+                .collect(),
+            midi_learn: state.midi_learn.as_deref(),
+            randomizer: &state.randomizer,
+        };
+        let search = self.search.to_lowercase();
+        let filtering = !search.is_empty() || self.used_by_flow_only;
+        let mut actions = Vec::default();
+        state.project.with_property_manager_mut(|pm| {
+            rows.group_names = pm.groups().iter().map(|g| g.name.clone()).collect();
+            let layout: Vec<(Option<String>, Vec<String>)> = pm
+                .layout()
+                .into_iter()
+                .map(|(g, names)| {
+                    let names = names
+                        .into_iter()
+                        .filter(|n| search.is_empty() || n.to_lowercase().contains(&search))
+                        .filter(|n| {
+                            !self.used_by_flow_only
+                                || pm
+                                    .programs_using(n)
+                                    .iter()
+                                    .any(|id| flow_programs.contains(id))
+                        })
+                        .map(String::from)
+                        .collect();
+                    (g.map(String::from), names)
+                })
+                .collect();
+            for (group, names) in layout {
+                if names.is_empty() && (group.is_none() || filtering) {
+                    continue;
+                }
+                self.update_group(ctx, ui, pm, group.as_deref(), &names, &rows, &mut actions);
+            }
+        });
+        Self::apply_property_actions(state, actions);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn update_group(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        pm: &mut PropertyManager,
+        group: Option<&str>,
+        names: &[String],
+        rows: &PropertyRowsContext<'_>,
+        actions: &mut Vec<PropertyAction>,
+    ) {
+        let title = group.unwrap_or("Ungrouped");
+        egui::CollapsingHeader::new(format!("{title} ({})", names.len()))
+            .id_source(("property_group", title))
+            .default_open(true)
+            .open(self.open_all)
+            .show(ui, |ui| {
+                if let Some(group) = group {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("⬆").on_hover_text("Move up").clicked() {
+                            actions.push(PropertyAction::MoveGroup(group.to_string(), -1));
+                        }
+                        if ui.small_button("⬇").on_hover_text("Move down").clicked() {
+                            actions.push(PropertyAction::MoveGroup(group.to_string(), 1));
+                        }
+                        if ui.small_button("Randomize").clicked() {
+                            actions.push(PropertyAction::Randomize(names.to_vec()));
+                        }
+                        if ui
+                            .small_button("Remove Group")
+                            .on_hover_text("The properties become ungrouped")
+                            .clicked()
+                        {
+                            actions.push(PropertyAction::RemoveGroup(group.to_string()));
+                        }
+                    });
+                }
+                for k in names.iter() {
+                    self.update_property_row(ctx, ui, pm, k, group, rows, actions);
+                }
+            });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn update_property_row(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        pm: &mut PropertyManager,
+        k: &String,
+        group: Option<&str>,
+        rows: &PropertyRowsContext<'_>,
+        actions: &mut Vec<PropertyAction>,
+    ) {
+        let programs: Vec<&str> = pm
+            .programs_using(k)
+            .into_iter()
+            .map(|id| rows.program_names.get(id).map(|n| n.as_str()).unwrap_or(id))
+            .collect();
+        let programs = programs.join(", ");
+        let scope = PropertyManager::split_scoped_name(k);
+        let label = match scope {
+            Some((id, uniform)) => match rows.program_names.get(id) {
+                Some(program) => format!("{program}/{uniform}"),
+                None => k.clone(),
+            },
+            None => k.clone(),
+        };
+        let shared = !pm.linked_to(k).is_empty();
+        let Some(p) = pm.get_mut(k) else {
+            return;
+        };
+        self.property_ui.property(ctx, ui, k, &label, p);
+        ui.horizontal(|ui| {
+            if group.is_some() {
+                if ui.small_button("⬆").clicked() {
+                    actions.push(PropertyAction::MoveProperty(k.clone(), -1));
+                }
+                if ui.small_button("⬇").clicked() {
+                    actions.push(PropertyAction::MoveProperty(k.clone(), 1));
+                }
+            }
+            let mut selected = group.map(String::from);
+            egui::ComboBox::from_id_source(("property_group_of", k))
+                .selected_text(selected.as_deref().unwrap_or("-"))
+                .width(80.0)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "-");
+                    for g in rows.group_names.iter() {
+                        ui.selectable_value(&mut selected, Some(g.clone()), g);
+                    }
+                })
+                .response
+                .on_hover_text("Group");
+            if selected.as_deref() != group {
+                actions.push(PropertyAction::SetGroup(k.clone(), selected));
+            }
+            if rows.midi_learn == Some(k.as_str()) {
+                if ui
+                    .button("Learning...")
+                    .on_hover_text("Move a control, or click to cancel")
+                    .clicked()
+                {
+                    actions.push(PropertyAction::MidiLearn(None));
+                }
+            } else if ui
+                .small_button("MIDI Learn")
+                .on_hover_text("Bind the next MIDI control to this property")
+                .clicked()
+            {
+                actions.push(PropertyAction::MidiLearn(Some(k.clone())));
+            }
+            if let Some((_, source)) = rows.midi_sources.iter().find(|(n, _)| n == k) {
+                ui.label(format!("{source}"));
+                if ui.small_button("Unbind").clicked() {
+                    actions.push(PropertyAction::MidiUnbind(k.clone()));
+                }
+            }
+            let mut locked = rows.randomizer.is_locked(k);
+            if ui
+                .add_enabled(!locked, egui::Button::new("Randomize").small())
+                .clicked()
+            {
+                actions.push(PropertyAction::Randomize(vec![k.clone()]));
+            }
+            if ui
+                .checkbox(&mut locked, "Lock")
+                .on_hover_text("Keep the value when randomizing")
+                .changed()
+            {
+                actions.push(PropertyAction::Lock(k.clone(), locked));
+            }
+            if scope.is_some() {
+                if ui
+                    .small_button("Share")
+                    .on_hover_text("Use one value for all programs sharing this uniform")
+                    .clicked()
+                {
+                    actions.push(PropertyAction::Share(k.clone()));
+                }
+            } else if shared
+                && ui
+                    .small_button("Unshare")
+                    .on_hover_text("Give each program its own value")
+                    .clicked()
+            {
+                actions.push(PropertyAction::Unshare(k.clone()));
+            }
+            if !programs.is_empty() {
+                ui.weak("used").on_hover_text(programs);
+            }
+        });
+    }
+
+    fn apply_property_actions(state: &mut State, actions: Vec<PropertyAction>) {
+        for action in actions {
+            match action {
+                PropertyAction::MidiLearn(property) => state.midi_learn = property,
+                PropertyAction::MidiUnbind(property) => {
+                    state.project.midi_bindings_mut().unbind(&property);
+                }
+                PropertyAction::Lock(property, locked) => {
+                    state.randomizer.set_locked(&property, locked);
+                }
+                action => state.project.with_property_manager_mut(|pm| match &action {
+                    PropertyAction::Share(name) => {
+                        if let Some((program, uniform)) = PropertyManager::split_scoped_name(name) {
+                            pm.link(&program.to_string(), uniform, uniform);
+                        }
+                    }
+                    PropertyAction::Unshare(name) => {
+                        let scoped: Vec<String> =
+                            pm.linked_to(name).into_iter().map(String::from).collect();
+                        for s in scoped {
+                            if let Some((program, uniform)) = PropertyManager::split_scoped_name(&s)
+                            {
+                                pm.unlink(&program.to_string(), uniform);
+                            }
+                        }
+                    }
+                    PropertyAction::MoveGroup(name, delta) => pm.move_group(name, *delta),
+                    PropertyAction::RemoveGroup(name) => {
+                        pm.remove_group(name);
+                    }
+                    PropertyAction::SetGroup(property, group) => {
+                        pm.set_group(property, group.as_deref())
+                    }
+                    PropertyAction::MoveProperty(property, delta) => {
+                        pm.move_property(property, *delta)
+                    }
+                    PropertyAction::Randomize(names) => {
+                        state
+                            .randomizer
+                            .randomize(pm, |n| names.iter().any(|k| k == n));
+                    }
+                    PropertyAction::MidiLearn(_)
+                    | PropertyAction::MidiUnbind(_)
+                    | PropertyAction::Lock(..) => {}
+                }),
+            }
+        }
+    }
+
+    fn update_filters(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("search")
+                    .desired_width(120.0),
+            );
+            if ui
+                .add_enabled(!self.search.is_empty(), egui::Button::new("x").small())
+                .clicked()
+            {
+                self.search.clear();
+            }
+            ui.checkbox(&mut self.used_by_flow_only, "Used by flow")
+                .on_hover_text("Only show properties used by the programs in the flow");
+            if ui.button("Expand All").clicked() {
+                self.open_all = Some(true);
+            }
+            if ui.button("Collapse All").clicked() {
+                self.open_all = Some(false);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_group_name)
+                    .hint_text("group name")
+                    .desired_width(120.0),
+            );
+            if ui
+                .add_enabled(
+                    !self.new_group_name.is_empty(),
+                    egui::Button::new("Add Group"),
+                )
+                .clicked()
+            {
+                let name = core::mem::take(&mut self.new_group_name);
+                state.project.with_property_manager_mut(|pm| {
+                    pm.add_group(&name);
+                });
+            }
+        });
+    }
+
+    fn update_randomizer(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            let mut seed = state.randomizer.seed();
+            ui.label("Seed");
+            if ui.add(egui::DragValue::new(&mut seed)).changed() {
+                state.randomizer.set_seed(seed);
+            }
+            if ui.button("Randomize All").clicked() {
+                state
+                    .project
+                    .with_property_manager_mut(|pm| state.randomizer.randomize(pm, |_| true));
+            }
+            if ui.button("Mutate All").clicked() {
+                let amount = self.mutate_amount;
+                state.project.with_property_manager_mut(|pm| {
+                    state.randomizer.mutate(pm, |_| true, amount);
+                });
+            }
+            ui.add(
+                egui::DragValue::new(&mut self.mutate_amount)
+                    .speed(0.01)
+                    .clamp_range(0.0..=1.0),
+            )
+            .on_hover_text("Mutate amount, relative to the range of each property");
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.randomize_filter)
+                    .hint_text("name contains")
+                    .desired_width(120.0),
+            );
+            if ui
+                .add_enabled(
+                    !self.randomize_filter.is_empty(),
+                    egui::Button::new("Randomize Matching"),
+                )
+                .clicked()
+            {
+                let filter = self.randomize_filter.as_str();
+                state.project.with_property_manager_mut(|pm| {
+                    state.randomizer.randomize(pm, |n| n.contains(filter));
+                });
+            }
+            if ui
+                .add_enabled(state.randomizer.can_go_back(), egui::Button::new("<"))
+                .on_hover_text("Previous variation")
+                .clicked()
+            {
+                state
+                    .project
+                    .with_property_manager_mut(|pm| state.randomizer.back(pm));
+            }
+            let (position, len) = state.randomizer.history_position();
+            ui.label(format!("{}/{}", position + 1, len.max(1)));
+            if ui
+                .add_enabled(state.randomizer.can_go_forward(), egui::Button::new(">"))
+                .on_hover_text("Next variation")
+                .clicked()
+            {
+                state
+                    .project
+                    .with_property_manager_mut(|pm| state.randomizer.forward(pm));
+            }
+        });
+    }
+
+    fn update_midi_input(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            if let Some(midi_input) = &state.midi_input {
+                ui.label(format!("MIDI: {}", midi_input.name()));
+                if ui.button("Disconnect").clicked() {
+                    state.midi_input = None;
+                }
+                return;
+            }
+            #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+            {
+                if ui.button("Refresh MIDI").clicked() || !self.midi_ports_scanned {
+                    self.midi_port_names = crate::midi_input::MidiInput::port_names();
+                    self.midi_ports_scanned = true;
+                }
+                for port_name in self.midi_port_names.iter() {
+                    if ui.button(port_name).on_hover_text("Connect").clicked() {
+                        match crate::midi_input::MidiInput::connect(port_name) {
+                            Ok(midi_input) => state.midi_input = Some(midi_input),
+                            Err(e) => eprintln!("Failed connecting MIDI {e:?}"),
+                        }
+                    }
+                }
+            }
+            #[cfg(not(all(feature = "midi", not(target_arch = "wasm32"))))]
+            ui.label("MIDI input needs the `midi` feature, on native");
+        });
+    }
+}
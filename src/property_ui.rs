@@ -1,41 +1,63 @@
 use crate::command_queue::COMMAND_QUEUE;
+use crate::project::ColorSpace;
+use crate::project::Gizmo;
 use crate::project::Property;
 use crate::project::PropertyConfig;
+use crate::property_ui_value_gradient::PropertyUiValueGradient;
+use crate::property_ui_value_matrix::PropertyUiValueMatrix;
 use crate::property_ui_value_vec2_f32::PropertyUiValueVec2F32;
 use crate::property_ui_value_vec3_f32::PropertyUiValueVec3F32;
 use crate::property_ui_value_vec3_f32_size4::PropertyUiValueVec3F32Size4;
 use crate::Command;
 use egui::WidgetText;
-use std::collections::HashMap;
 
 use crate::property_ui_value_f32::PropertyUiValueF32;
+use crate::property_ui_value_f64::PropertyUiValueF64;
 use crate::PropertyUiValue;
 
 #[derive(Debug)]
 pub struct PropertyUi {
-    configuring: Option<(String, PropertyConfig)>,
-    applying: Option<(String, PropertyConfig)>,
+    configuring: Option<(String, PropertyConfig, Gizmo)>,
+    applying: Option<(String, PropertyConfig, Gizmo)>,
     property_ui_values: Vec<Box<dyn PropertyUiValue>>,
-    fully_closed: HashMap<String, bool>,
+    /// Forces all properties open, or closed, while set.
+    open: Option<bool>,
 }
 
 impl Default for PropertyUi {
     fn default() -> Self {
         let mut property_ui_values: Vec<Box<dyn PropertyUiValue>> = Vec::default();
         property_ui_values.push(Box::new(PropertyUiValueF32::default()));
+        property_ui_values.push(Box::new(PropertyUiValueF64::default()));
         property_ui_values.push(Box::new(PropertyUiValueVec2F32::default()));
         property_ui_values.push(Box::new(PropertyUiValueVec3F32::default()));
         property_ui_values.push(Box::new(PropertyUiValueVec3F32Size4::default()));
+        property_ui_values.push(Box::new(PropertyUiValueGradient::default()));
+        property_ui_values.push(Box::new(PropertyUiValueMatrix::default()));
 
         Self {
             configuring: Default::default(),
             applying: Default::default(),
-            fully_closed: Default::default(),
+            open: None,
             property_ui_values,
         }
     }
 }
 impl PropertyUi {
+    pub fn set_open(&mut self, open: Option<bool>) {
+        self.open = open;
+    }
+    fn gizmo_combo(ui: &mut egui::Ui, gizmo: &mut Gizmo) {
+        egui::ComboBox::from_label("Gizmo")
+            .selected_text(format!("{gizmo}"))
+            .show_ui(ui, |ui| {
+                for g in Gizmo::ALL {
+                    ui.selectable_value(gizmo, g, format!("{g}"));
+                }
+            })
+            .response
+            .on_hover_text("The overlay used to edit the property on the McGuffin preview");
+    }
     pub fn update(&mut self, ctx: &egui::Context) {
         if let Some(c) = &mut self.configuring {
             let mut close = false;
@@ -74,6 +96,34 @@ impl PropertyUi {
                                 .clamp_to_range(false)
                                 .text("Step Size"),
                         );
+                        Self::gizmo_combo(ui, &mut c.2);
+                        ui.horizontal_wrapped(|ui| {
+                            if ui.add(egui::Button::new("Cancel")).clicked() {
+                                cancel = true;
+                            }
+                            if ui.add(egui::Button::new("Apply")).clicked() {
+                                close = true;
+                            }
+                        });
+                    }
+                    PropertyConfig::ColorRgb { space } | PropertyConfig::Gradient { space } => {
+                        ui.with_layout(egui::Layout::top_down(egui::Align::RIGHT), |ui| {
+                            if ui.add(egui::Button::new("Delete!!!")).clicked() {
+                                delete = true;
+                            }
+                        });
+                        egui::ComboBox::from_label("Colour Space")
+                            .selected_text(format!("{space}"))
+                            .show_ui(ui, |ui| {
+                                for s in ColorSpace::ALL {
+                                    ui.selectable_value(space, s, format!("{s}"));
+                                }
+                            })
+                            .response
+                            .on_hover_text(
+                                "The space the colour is uploaded in, use Linear for shaders working in linear light",
+                            );
+                        Self::gizmo_combo(ui, &mut c.2);
                         ui.horizontal_wrapped(|ui| {
                             if ui.add(egui::Button::new("Cancel")).clicked() {
                                 cancel = true;
@@ -91,9 +141,15 @@ impl PropertyUi {
                         });
                         let value = format!("Unhandled {:?}", c.1);
                         ui.label(value);
-                        if ui.add(egui::Button::new("Cancel")).clicked() {
-                            cancel = true;
-                        }
+                        Self::gizmo_combo(ui, &mut c.2);
+                        ui.horizontal_wrapped(|ui| {
+                            if ui.add(egui::Button::new("Cancel")).clicked() {
+                                cancel = true;
+                            }
+                            if ui.add(egui::Button::new("Apply")).clicked() {
+                                close = true;
+                            }
+                        });
                     }
                 });
             if delete {
@@ -115,6 +171,7 @@ impl PropertyUi {
         _ctx: &egui::Context,
         ui: &mut egui::Ui,
         name: &str,
+        label: &str,
         property: &mut Property,
     ) {
         let mut edit_clicked = false;
@@ -122,28 +179,24 @@ impl PropertyUi {
             //eprintln!("Trying to apply {applying:?}");
             if applying.0 == name {
                 property.config = applying.1.clone();
+                property.gizmo = applying.2;
                 self.applying = None;
             }
         }
 
-        let n = if true || *self.fully_closed.get(name).unwrap_or(&false) {
-            let mut n = WidgetText::default();
-            for v in self.property_ui_values.iter() {
-                if let Some(l) = v.label(name, property) {
-                    n = l;
-                    break;
-                }
+        let mut n = WidgetText::from(label);
+        for v in self.property_ui_values.iter() {
+            if let Some(l) = v.label(label, property) {
+                n = l;
+                break;
             }
-            //format!("{name} {n}")
-            n
-        } else {
-            format!("{name}").into()
-        };
+        }
 
         let n = n.monospace();
 
-        let r = egui::CollapsingHeader::new(n)
+        egui::CollapsingHeader::new(n)
             .id_source(name)
+            .open(self.open)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
                     let mut handled = false;
@@ -166,14 +219,8 @@ impl PropertyUi {
                         .clicked();
                 });
             });
-        if r.fully_closed() {
-            self.fully_closed.insert(name.to_string(), true);
-        } else {
-            self.fully_closed.insert(name.to_string(), false);
-        }
-
         if edit_clicked {
-            self.configuring = Some((name.into(), property.config.clone()));
+            self.configuring = Some((name.into(), property.config.clone(), property.gizmo));
         }
     }
 }
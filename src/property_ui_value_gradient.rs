@@ -0,0 +1,150 @@
+use crate::project::Gradient;
+use crate::project::GradientInterpolation;
+use crate::project::GradientStop;
+use crate::project::Property;
+use crate::project::PropertyValue;
+use crate::property_ui_value_vec3_f32::PropertyUiValueVec3F32;
+use crate::PropertyUiValue;
+use egui::Color32;
+use egui::WidgetText;
+
+#[derive(Debug, Default)]
+pub struct PropertyUiValueGradient {}
+
+impl PropertyUiValueGradient {
+    fn color32([r, g, b]: [f32; 3]) -> Color32 {
+        Color32::from_rgb(
+            (r.clamp(0.0, 1.0) * 255.0).floor() as u8,
+            (g.clamp(0.0, 1.0) * 255.0).floor() as u8,
+            (b.clamp(0.0, 1.0) * 255.0).floor() as u8,
+        )
+    }
+
+    fn preview(ui: &mut egui::Ui, gradient: &Gradient) {
+        const STEPS: usize = 64;
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(256.0, 20.0), egui::Sense::hover());
+        let w = rect.width() / STEPS as f32;
+        for (i, c) in gradient.bake(STEPS).into_iter().enumerate() {
+            let min = rect.min + egui::vec2(i as f32 * w, 0.0);
+            let r = egui::Rect::from_min_size(min, egui::vec2(w + 0.5, rect.height()));
+            ui.painter().rect_filled(r, 0.0, Self::color32(c));
+        }
+    }
+
+    fn update_stops(
+        ui: &mut egui::Ui,
+        stops: &mut Vec<GradientStop>,
+        interpolation: &mut GradientInterpolation,
+    ) {
+        egui::ComboBox::from_label("Interpolation")
+            .selected_text(format!("{interpolation}"))
+            .show_ui(ui, |ui| {
+                for i in GradientInterpolation::ALL {
+                    ui.selectable_value(interpolation, i, format!("{i}"));
+                }
+            });
+        let mut remove = None;
+        let can_remove = stops.len() > 2;
+        for (i, stop) in stops.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut stop.position)
+                        .speed(0.005)
+                        .clamp_range(0.0..=1.0),
+                );
+                PropertyUiValueVec3F32::color_edit_button_srgb(ui, &mut stop.color);
+                if ui
+                    .add_enabled(can_remove, egui::Button::new("[X]").small())
+                    .clicked()
+                {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            stops.remove(i);
+        }
+        if ui
+            .button("Add Stop")
+            .on_hover_text("Adds a stop in the middle of the widest gap")
+            .clicked()
+        {
+            let (start, end) = stops
+                .windows(2)
+                .map(|w| (w[0].position, w[1].position))
+                .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+                .unwrap_or((0.0, 1.0));
+            let position = (start + end) * 0.5;
+            let color = Gradient::Stops {
+                stops: stops.clone(),
+                interpolation: *interpolation,
+            }
+            .sample(position);
+            stops.push(GradientStop { position, color });
+        }
+    }
+}
+
+impl PropertyUiValue for PropertyUiValueGradient {
+    fn label(&self, name: &str, property: &mut Property) -> Option<WidgetText> {
+        match &property.value {
+            PropertyValue::Gradient { gradient } => match gradient {
+                Gradient::Stops { stops, .. } => {
+                    Some(format!("{name} gradient, {} stops", stops.len()).into())
+                }
+                Gradient::Cosine { .. } => Some(format!("{name} gradient, cosine").into()),
+            },
+            _ => None,
+        }
+    }
+    fn update(&mut self, ui: &mut egui::Ui, name: &str, property: &mut Property) -> bool {
+        let PropertyValue::Gradient { gradient } = &mut property.value else {
+            return false;
+        };
+        ui.vertical(|ui| {
+            Self::preview(ui, gradient);
+            let mut convert = None;
+            match gradient {
+                Gradient::Stops {
+                    stops,
+                    interpolation,
+                } => {
+                    Self::update_stops(ui, stops, interpolation);
+                    if ui
+                        .button("Fit Cosine")
+                        .on_hover_text("Converts to the nearest cosine palette")
+                        .clicked()
+                    {
+                        convert = Some(gradient.fit_cosine());
+                    }
+                }
+                Gradient::Cosine { values } => {
+                    egui::Grid::new(("cosine", name)).show(ui, |ui| {
+                        for (row, label) in ["a", "b", "c", "d"].iter().enumerate() {
+                            ui.label(*label);
+                            for v in values[row * 3..row * 3 + 3].iter_mut() {
+                                ui.add(egui::DragValue::new(v).speed(0.01));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    if ui
+                        .button("To Stops")
+                        .on_hover_text("Converts to 8 editable stops")
+                        .clicked()
+                    {
+                        convert = Some(gradient.to_stops(8));
+                    }
+                }
+            }
+            if let Some(converted) = convert {
+                *gradient = converted;
+            }
+            // not while dragging, the stops would swap under the pointer
+            if !ui.input(|i| i.pointer.any_down()) {
+                gradient.sort_stops();
+            }
+        });
+        true
+    }
+}
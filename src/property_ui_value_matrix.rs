@@ -0,0 +1,82 @@
+use crate::project::Matrix;
+use crate::project::Property;
+use crate::project::PropertyValue;
+use crate::PropertyUiValue;
+use egui::WidgetText;
+
+#[derive(Debug, Default)]
+pub struct PropertyUiValueMatrix {}
+
+impl PropertyUiValueMatrix {
+    fn vec3(ui: &mut egui::Ui, label: &str, values: &mut [f32; 3], speed: f64) {
+        ui.horizontal(|ui| {
+            for v in values.iter_mut() {
+                ui.add(egui::DragValue::new(v).speed(speed));
+            }
+            ui.label(label);
+        });
+    }
+}
+
+impl PropertyUiValue for PropertyUiValueMatrix {
+    fn label(&self, name: &str, property: &mut Property) -> Option<WidgetText> {
+        match &property.value {
+            PropertyValue::Matrix { matrix } => Some(format!("{name} {}", matrix.kind()).into()),
+            _ => None,
+        }
+    }
+    fn update(&mut self, ui: &mut egui::Ui, name: &str, property: &mut Property) -> bool {
+        let PropertyValue::Matrix { matrix } = &mut property.value else {
+            return false;
+        };
+        ui.vertical(|ui| {
+            let mut kind = matrix.kind();
+            egui::ComboBox::from_id_source(("matrix kind", name))
+                .selected_text(kind)
+                .show_ui(ui, |ui| {
+                    for k in Matrix::KINDS {
+                        ui.selectable_value(&mut kind, k, k);
+                    }
+                });
+            if kind != matrix.kind() {
+                if let Some(m) = Matrix::default_for_kind(kind) {
+                    *matrix = m;
+                }
+            }
+            match matrix {
+                Matrix::Transform {
+                    translation,
+                    rotation,
+                    scale,
+                } => {
+                    Self::vec3(ui, "Translation", translation, 0.01);
+                    Self::vec3(ui, "Rotation", rotation, 1.0);
+                    Self::vec3(ui, "Scale", scale, 0.01);
+                }
+                Matrix::LookAt { eye, target, up } => {
+                    Self::vec3(ui, "Eye", eye, 0.01);
+                    Self::vec3(ui, "Target", target, 0.01);
+                    Self::vec3(ui, "Up", up, 0.01);
+                }
+                Matrix::Perspective { fov_y, near, far } => {
+                    ui.add(
+                        egui::Slider::new(fov_y, 1.0..=179.0)
+                            .suffix("°")
+                            .text("Field of View"),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(near)
+                                .speed(0.01)
+                                .clamp_range(0.001..=f32::MAX),
+                        );
+                        ui.label("Near");
+                        ui.add(egui::DragValue::new(far).speed(1.0));
+                        ui.label("Far");
+                    });
+                }
+            }
+        });
+        true
+    }
+}
@@ -1,6 +1,7 @@
 use crate::project::Property;
 use crate::project::PropertyConfig;
 use crate::project::PropertyValue;
+use crate::property_ui_value_vec3_f32::PropertyUiValueVec3F32;
 use crate::PropertyUiValue;
 use egui::WidgetText;
 
@@ -50,10 +51,10 @@ impl PropertyUiValue for PropertyUiValueVec2F32 {
                 });
                 true
             }
-            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb {}) => {
+            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb { .. }) => {
                 ui.vertical(|ui| {
                     //ui.label(name);
-                    egui::widgets::color_picker::color_edit_button_rgb(ui, &mut *values);
+                    PropertyUiValueVec3F32::color_edit_button_srgb(ui, values);
                 });
                 true
             }
@@ -1,3 +1,5 @@
+use crate::project::linear_to_srgb;
+use crate::project::srgb_to_linear;
 use crate::project::Property;
 use crate::project::PropertyConfig;
 use crate::project::PropertyValue;
@@ -9,7 +11,18 @@ use egui::WidgetText;
 #[derive(Debug, Default)]
 pub struct PropertyUiValueVec3F32 {}
 
-impl PropertyUiValueVec3F32 {}
+impl PropertyUiValueVec3F32 {
+    /// Like [`egui::widgets::color_picker::color_edit_button_rgb`], but for sRGB values,
+    /// so the picked colour matches the stored one.
+    pub fn color_edit_button_srgb(ui: &mut egui::Ui, srgb: &mut [f32; 3]) -> egui::Response {
+        let mut linear = srgb.map(srgb_to_linear);
+        let response = egui::widgets::color_picker::color_edit_button_rgb(ui, &mut linear);
+        if response.changed() {
+            *srgb = linear.map(linear_to_srgb);
+        }
+        response
+    }
+}
 
 impl PropertyUiValue for PropertyUiValueVec3F32 {
     fn label(&self, name: &str, property: &mut Property) -> Option<WidgetText> {
@@ -28,7 +41,7 @@ impl PropertyUiValue for PropertyUiValueVec3F32 {
                 )
                 .into(),
             ),
-            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb {}) => {
+            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb { .. }) => {
                 let c = Color32::from_rgb(
                     (values[0] * 255.0).floor() as u8,
                     (values[1] * 255.0).floor() as u8,
@@ -80,10 +93,10 @@ impl PropertyUiValue for PropertyUiValueVec3F32 {
                 });
                 true
             }
-            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb {}) => {
+            (PropertyValue::Vec3F32 { values }, PropertyConfig::ColorRgb { .. }) => {
                 ui.vertical(|ui| {
                     //ui.label(name);
-                    egui::widgets::color_picker::color_edit_button_rgb(ui, &mut *values);
+                    Self::color_edit_button_srgb(ui, values);
                 });
                 true
             }
@@ -0,0 +1,113 @@
+use crate::osc_server::OscServer;
+use crate::state::State;
+use crate::window::Window;
+
+#[derive(Debug)]
+pub struct RemoteWindow {
+    is_open: bool,
+    port: u16,
+    last_error: Option<String>,
+}
+
+impl Default for RemoteWindow {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            port: OscServer::DEFAULT_PORT,
+            last_error: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct RemoteWindowSave {
+    #[serde(default)]
+    is_open: bool,
+    #[serde(default)]
+    port: u16,
+}
+
+impl From<&RemoteWindow> for RemoteWindowSave {
+    fn from(rw: &RemoteWindow) -> Self {
+        Self {
+            is_open: rw.is_open,
+            port: rw.port,
+        }
+    }
+}
+
+impl Window for RemoteWindow {
+    fn name(&self) -> &str {
+        "Remote"
+    }
+    fn is_open(&self) -> bool {
+        self.is_open
+    }
+    fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    fn update(&mut self, ctx: &egui::Context, state: &mut State) {
+        let mut is_open = self.is_open;
+        egui::Window::new("Remote")
+            .resizable(false)
+            .collapsible(false)
+            .open(&mut is_open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(osc_server) = &state.osc_server {
+                        ui.label(format!(
+                            "OSC listening on {} ({} messages)",
+                            osc_server.local_addr(),
+                            osc_server.message_count()
+                        ));
+                        if ui.button("Stop").clicked() {
+                            state.osc_server = None;
+                        }
+                    } else {
+                        ui.label("OSC UDP Port");
+                        ui.add(egui::DragValue::new(&mut self.port).clamp_range(1..=65535));
+                        if ui.button("Start").clicked() {
+                            self.last_error = state
+                                .start_osc_server(self.port)
+                                .err()
+                                .map(|e| format!("{e}"));
+                        }
+                    }
+                });
+                if let Some(last_error) = &self.last_error {
+                    ui.colored_label(egui::Color32::RED, last_error);
+                }
+                ui.separator();
+                for (address, help) in [
+                    ("/property/<name> <values...>", "set a property"),
+                    ("/flow/goto <label>", "start the flow at a label"),
+                    ("/transport/play|pause|toggle", "control playback"),
+                    ("/transport/step <frames>", "step frames"),
+                    ("/transport/speed <speed>", "set the playback speed"),
+                    ("/transport/seek <seconds>", "seek"),
+                    ("/fullscreen [0|1]", "toggle, leave, or enter fullscreen"),
+                    ("/ping", "answered with /pong"),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.monospace(address);
+                        ui.label(help);
+                    });
+                }
+            });
+        self.is_open = is_open;
+    }
+    fn serialize(&self) -> String {
+        let save: RemoteWindowSave = self.into();
+
+        ron::ser::to_string(&save).unwrap_or_default()
+    }
+    fn deserialize(&mut self, data: &str) {
+        let save: RemoteWindowSave = ron::from_str(data).unwrap_or_default();
+
+        self.is_open = save.is_open;
+        if save.port != 0 {
+            self.port = save.port;
+        }
+    }
+}
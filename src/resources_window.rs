@@ -1,10 +1,18 @@
 use crate::command_queue::COMMAND_QUEUE;
+use crate::engine::Tempo;
+use crate::path_helper::PathHelper;
+use crate::project::ChannelInput;
 use crate::project::Resource;
+use crate::project::ResourceAudio;
+use crate::project::ResourceBuffer;
+use crate::project::ResourceId;
+use crate::project::ResourceMesh;
 use crate::project::ResourceProgram;
 use crate::project::ResourceText;
 use crate::state::State;
 use crate::window::Window;
 use crate::Command;
+use std::path::PathBuf;
 
 #[derive(Debug, Default)]
 pub struct ResourcesWindow {
@@ -48,6 +56,13 @@ impl Window for ResourcesWindow {
                 let mut selected_program_id = None;
                 let mut deselect_program_id = false;
                 let current_selected_program_id = state.selected_program_id().cloned();
+                let current_soundtrack = state.project.soundtrack().cloned();
+                let mut new_soundtrack = None;
+                let mut changed_tempo: Option<(ResourceId, Tempo)> = None;
+                let mut changed_buffer: Option<(ResourceId, [u32; 3])> = None;
+                let mut reset_buffer: Option<ResourceId> = None;
+                let mut changed_channel: Option<(ResourceId, usize, ChannelInput)> = None;
+                let mut picked_file: Option<(ResourceId, PathBuf)> = None;
                 for (id, r) in state.project.resource_manager.resources_mut() {
                     match r {
                         Resource::Text(rt) => {
@@ -108,12 +123,213 @@ impl Window for ResourcesWindow {
                                 ui.label(t).on_hover_text(id);
                                 let _response = ui.add(egui::TextEdit::singleline(rp.name_mut()));
                             });
+                            ui.horizontal(|ui| {
+                                for (channel, input) in rp.channels().iter().enumerate() {
+                                    let mut selected = *input;
+                                    egui::ComboBox::from_id_source((id, "channel", channel))
+                                        .selected_text(format!("iChannel{channel}: {input}"))
+                                        .show_ui(ui, |ui| {
+                                            for i in ChannelInput::ALL {
+                                                ui.selectable_value(
+                                                    &mut selected,
+                                                    i,
+                                                    i.to_string(),
+                                                );
+                                            }
+                                        });
+                                    if selected != *input {
+                                        changed_channel = Some((id.clone(), channel, selected));
+                                    }
+                                }
+                            });
+                        }
+                        Resource::Audio(ra) => {
+                            ui.horizontal(|ui| {
+                                if ui.button("[X]").clicked() {
+                                    let _ = COMMAND_QUEUE.send(Command::RemoveResource {
+                                        resource_id: id.clone(),
+                                    });
+                                };
+                                let f = ra.file().map(|f| format!("{f:?}")).unwrap_or_default();
+                                let l = format!("    AUD {id} {f:20}");
+                                ui.label(egui::RichText::new(l).monospace());
+                                let _response = ui.add(egui::TextEdit::singleline(ra.name_mut()));
+                            });
+                            ui.horizontal(|ui| {
+                                let mut is_soundtrack = current_soundtrack.as_ref() == Some(id);
+                                if ui.checkbox(&mut is_soundtrack, "Soundtrack").changed() {
+                                    new_soundtrack = Some(is_soundtrack.then(|| id.clone()));
+                                }
+                                let mut tempo = ra.tempo();
+                                ui.label("BPM");
+                                ui.add(egui::DragValue::new(&mut tempo.bpm).speed(0.1));
+                                ui.label("Beats/Bar");
+                                ui.add(egui::DragValue::new(&mut tempo.beats_per_bar));
+                                ui.label("Offset");
+                                ui.add(
+                                    egui::DragValue::new(&mut tempo.offset)
+                                        .speed(0.001)
+                                        .suffix("s"),
+                                );
+                                if tempo != ra.tempo() {
+                                    changed_tempo = Some((id.clone(), tempo));
+                                }
+                                #[cfg(not(target_arch = "wasm32"))]
+                                if ui.button("Load from...").clicked() {
+                                    if let Some(file) = rfd::FileDialog::new()
+                                        .add_filter("Audio", &["wav", "ogg"])
+                                        .set_directory(
+                                            state.project_path.clone().unwrap_or_default(),
+                                        )
+                                        .pick_file()
+                                    {
+                                        picked_file = Some((id.clone(), file));
+                                    }
+                                }
+                                if let Some(clip) = ra.clip() {
+                                    ui.label(format!("{:.1}s", clip.duration()));
+                                }
+                            });
+                        }
+                        Resource::Mesh(rm) => {
+                            ui.horizontal(|ui| {
+                                if ui.button("[X]").clicked() {
+                                    let _ = COMMAND_QUEUE.send(Command::RemoveResource {
+                                        resource_id: id.clone(),
+                                    });
+                                };
+                                let f = rm.file().map(|f| format!("{f:?}")).unwrap_or_default();
+                                let l = format!("    MSH {id} {f:20}");
+                                ui.label(egui::RichText::new(l).monospace());
+                                let _response = ui.add(egui::TextEdit::singleline(rm.name_mut()));
+                            });
+                            ui.horizontal(|ui| {
+                                #[cfg(not(target_arch = "wasm32"))]
+                                if ui.button("Load from...").clicked() {
+                                    if let Some(file) = rfd::FileDialog::new()
+                                        .add_filter("Mesh", &["obj", "gltf", "glb"])
+                                        .set_directory(
+                                            state.project_path.clone().unwrap_or_default(),
+                                        )
+                                        .pick_file()
+                                    {
+                                        picked_file = Some((id.clone(), file));
+                                    }
+                                }
+                                if let Some(mesh) = rm.mesh() {
+                                    ui.label(format!(
+                                        "{} vertices, {} triangles",
+                                        mesh.vertex_count(),
+                                        mesh.indices().len() / 3
+                                    ));
+                                }
+                            });
+                        }
+                        Resource::Buffer(rb) => {
+                            ui.horizontal(|ui| {
+                                if ui.button("[X]").clicked() {
+                                    let _ = COMMAND_QUEUE.send(Command::RemoveResource {
+                                        resource_id: id.clone(),
+                                    });
+                                };
+                                let l = format!("    BUF {id}");
+                                ui.label(egui::RichText::new(l).monospace());
+                                let _response = ui.add(egui::TextEdit::singleline(rb.name_mut()));
+                            });
+                            ui.horizontal(|ui| {
+                                let mut layout = [rb.binding(), rb.elements(), rb.stride()];
+                                ui.label("Binding");
+                                ui.add(egui::DragValue::new(&mut layout[0]).clamp_range(0..=15));
+                                ui.label("Elements");
+                                ui.add(
+                                    egui::DragValue::new(&mut layout[1]).clamp_range(1..=1 << 24),
+                                );
+                                ui.label("Stride");
+                                ui.add(
+                                    egui::DragValue::new(&mut layout[2])
+                                        .clamp_range(4..=256)
+                                        .speed(4)
+                                        .suffix(" bytes"),
+                                );
+                                if layout != [rb.binding(), rb.elements(), rb.stride()] {
+                                    changed_buffer = Some((id.clone(), layout));
+                                }
+                                if ui
+                                    .button("Reset")
+                                    .on_hover_text("Clears the buffer to zero")
+                                    .clicked()
+                                {
+                                    reset_buffer = Some(id.clone());
+                                }
+                                ui.label(format!("{} KiB", rb.size() / 1024));
+                            });
                         }
                         o => {
                             ui.label(format!("Unhandled {o:?}"));
                         }
                     }
                 }
+                if let Some(soundtrack) = new_soundtrack {
+                    state.project.set_soundtrack(soundtrack);
+                }
+                if let Some((id, tempo)) = changed_tempo {
+                    state.project.with_resource_manager_mut(|rm| {
+                        rm.with_resource_mut(&id, |r| {
+                            if let Resource::Audio(ra) = r {
+                                ra.set_tempo(tempo);
+                            }
+                        });
+                    });
+                }
+                if let Some((id, [binding, elements, stride])) = changed_buffer {
+                    state.project.with_resource_manager_mut(|rm| {
+                        rm.with_resource_mut(&id, |r| {
+                            if let Resource::Buffer(rb) = r {
+                                rb.set_layout(binding, elements, stride);
+                            }
+                        });
+                    });
+                }
+                if let Some(id) = reset_buffer {
+                    state.project.with_resource_manager_mut(|rm| {
+                        rm.with_resource_mut(&id, |r| {
+                            if let Resource::Buffer(rb) = r {
+                                rb.reset();
+                            }
+                        });
+                    });
+                }
+                if let Some((id, channel, input)) = changed_channel {
+                    state.project.with_resource_manager_mut(|rm| {
+                        rm.with_resource_mut(&id, |r| {
+                            if let Resource::Program(rp) = r {
+                                rp.set_channel(channel, input);
+                            }
+                        });
+                    });
+                }
+                if let Some((id, file)) = picked_file {
+                    let parent = state.project_path.clone();
+                    let relative = match &parent {
+                        Some(parent) => PathHelper::strip_prefix(&file, parent),
+                        None => Some(file.as_path()),
+                    };
+                    if let Some(relative) = relative {
+                        let relative = relative.to_path_buf();
+                        state.project.with_resource_manager_mut(|rm| {
+                            rm.with_resource_mut(&id, |r| {
+                                match r {
+                                    Resource::Audio(ra) => ra.set_file(relative.clone()),
+                                    Resource::Mesh(rm) => rm.set_file(relative.clone()),
+                                    _ => return,
+                                }
+                                r.reload(parent.as_deref());
+                            });
+                        });
+                    } else {
+                        eprintln!("Warning: Can only load from within project folder");
+                    }
+                }
                 if let Some(selected_program_id) = selected_program_id.take() {
                     state.select_program_id(selected_program_id);
                 }
@@ -128,6 +344,21 @@ impl Window for ResourcesWindow {
                 if ui.button("Add Program").clicked() {
                     let r = Resource::Program(ResourceProgram::default());
 
+                    state.project.resource_manager.add(r);
+                }
+                if ui.button("Add Audio").clicked() {
+                    let r = Resource::Audio(ResourceAudio::default());
+
+                    state.project.resource_manager.add(r);
+                }
+                if ui.button("Add Mesh").clicked() {
+                    let r = Resource::Mesh(ResourceMesh::default());
+
+                    state.project.resource_manager.add(r);
+                }
+                if ui.button("Add Buffer").clicked() {
+                    let r = Resource::Buffer(ResourceBuffer::default());
+
                     state.project.resource_manager.add(r);
                 }
             });
@@ -0,0 +1,292 @@
+use crate::project::SyncInterpolation;
+use crate::project::SyncKey;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+const CLIENT_GREETING: &[u8] = b"hello, synctracker!";
+const SERVER_GREETING: &[u8] = b"hello, demo!";
+
+const SET_KEY: u8 = 0;
+const DELETE_KEY: u8 = 1;
+const GET_TRACK: u8 = 2;
+const SET_ROW: u8 = 3;
+const PAUSE: u8 = 4;
+const SAVE_TRACKS: u8 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RocketEvent {
+    SetKey { track: String, key: SyncKey },
+    DeleteKey { track: String, row: u32 },
+    SetRow { row: u32 },
+    Pause { paused: bool },
+    SaveTracks,
+}
+
+/// The demo side of the GNU Rocket sync-tracker protocol.
+///
+/// Tracks are requested by name, the tracker then sends their keys,
+/// and keeps sending changes, row changes, and pause toggles while connected.
+#[derive(Debug)]
+pub struct RocketClient {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    track_names: Vec<String>,
+    last_row: Option<u32>,
+}
+
+impl RocketClient {
+    pub const DEFAULT_ADDRESS: &'static str = "127.0.0.1:1338";
+
+    pub fn connect(address: &str) -> Result<Self> {
+        let address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| eyre!("Can not resolve {address}"))?;
+        let mut stream = TcpStream::connect_timeout(&address, Duration::from_secs(2))?;
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        stream.write_all(CLIENT_GREETING)?;
+        let mut greeting = [0u8; SERVER_GREETING.len()];
+        stream.read_exact(&mut greeting)?;
+        if greeting != SERVER_GREETING {
+            return Err(eyre!("Unexpected greeting from {address}"));
+        }
+        stream.set_nodelay(true)?;
+        stream.set_nonblocking(true)?;
+
+        Ok(Self {
+            stream,
+            buffer: Vec::default(),
+            track_names: Vec::default(),
+            last_row: None,
+        })
+    }
+
+    pub fn track_names(&self) -> &[String] {
+        &self.track_names
+    }
+
+    /// Asks the tracker for the track, tracks that were already requested are skipped.
+    pub fn request_track(&mut self, name: &str) -> Result<()> {
+        if self.track_names.iter().any(|n| n == name) {
+            return Ok(());
+        }
+        let mut msg = vec![GET_TRACK];
+        msg.extend_from_slice(&(name.len() as u32).to_be_bytes());
+        msg.extend_from_slice(name.as_bytes());
+        self.send(&msg)?;
+        self.track_names.push(name.to_string());
+        Ok(())
+    }
+
+    /// Tells the tracker the current row, if it changed.
+    pub fn send_row(&mut self, row: u32) -> Result<()> {
+        if self.last_row == Some(row) {
+            return Ok(());
+        }
+        let mut msg = vec![SET_ROW];
+        msg.extend_from_slice(&row.to_be_bytes());
+        self.send(&msg)?;
+        self.last_row = Some(row);
+        Ok(())
+    }
+
+    /// Returns the events received since the last poll, an error means the connection is gone.
+    pub fn poll(&mut self) -> Result<Vec<RocketEvent>> {
+        let mut chunk = [0u8; 1024];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err(eyre!("Tracker disconnected")),
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let mut events = Vec::default();
+        let mut pos = 0;
+        while let Some((event, len)) = self.parse(&self.buffer[pos..])? {
+            if let RocketEvent::SetRow { row } = event {
+                // don't echo the row back
+                self.last_row = Some(row);
+            }
+            events.push(event);
+            pos += len;
+        }
+        self.buffer.drain(..pos);
+
+        Ok(events)
+    }
+
+    /// Parses one message, returns `None` if it is not complete yet.
+    fn parse(&self, data: &[u8]) -> Result<Option<(RocketEvent, usize)>> {
+        let u32_at = |o: usize| -> Option<u32> {
+            data.get(o..o + 4)
+                .and_then(|b| b.try_into().ok())
+                .map(u32::from_be_bytes)
+        };
+        let track_at = |o: usize| -> Result<Option<String>> {
+            match u32_at(o) {
+                Some(i) => self
+                    .track_names
+                    .get(i as usize)
+                    .cloned()
+                    .map(Some)
+                    .ok_or_else(|| eyre!("Unknown track index {i}")),
+                None => Ok(None),
+            }
+        };
+
+        let Some(cmd) = data.first() else {
+            return Ok(None);
+        };
+        let event = match *cmd {
+            SET_KEY => {
+                let (Some(track), Some(row), Some(value), Some(interpolation)) =
+                    (track_at(1)?, u32_at(5), u32_at(9), data.get(13))
+                else {
+                    return Ok(None);
+                };
+                let key = SyncKey {
+                    row,
+                    value: f32::from_bits(value),
+                    interpolation: SyncInterpolation::from_u8(*interpolation),
+                };
+                (RocketEvent::SetKey { track, key }, 14)
+            }
+            DELETE_KEY => {
+                let (Some(track), Some(row)) = (track_at(1)?, u32_at(5)) else {
+                    return Ok(None);
+                };
+                (RocketEvent::DeleteKey { track, row }, 9)
+            }
+            SET_ROW => {
+                let Some(row) = u32_at(1) else {
+                    return Ok(None);
+                };
+                (RocketEvent::SetRow { row }, 5)
+            }
+            PAUSE => {
+                let Some(paused) = data.get(1) else {
+                    return Ok(None);
+                };
+                (
+                    RocketEvent::Pause {
+                        paused: *paused != 0,
+                    },
+                    2,
+                )
+            }
+            SAVE_TRACKS => (RocketEvent::SaveTracks, 1),
+            o => return Err(eyre!("Unknown command {o} from tracker")),
+        };
+        Ok(Some(event))
+    }
+
+    fn send(&mut self, msg: &[u8]) -> Result<()> {
+        let mut data = msg;
+        while !data.is_empty() {
+            match self.stream.write(data) {
+                Ok(0) => return Err(eyre!("Tracker disconnected")),
+                Ok(n) => data = &data[n..],
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(1))
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// A minimal tracker, it answers the greeting, reads one track request, and sends `script`.
+    fn mock_tracker(script: Vec<u8>) -> (String, std::thread::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let handle = std::thread::spawn(move || {
+            let (mut s, _) = listener.accept().unwrap();
+            let mut greeting = [0u8; CLIENT_GREETING.len()];
+            s.read_exact(&mut greeting).unwrap();
+            assert_eq!(CLIENT_GREETING, greeting);
+            s.write_all(SERVER_GREETING).unwrap();
+
+            let mut get_track = [0u8; 1 + 4 + 5];
+            s.read_exact(&mut get_track).unwrap();
+            s.write_all(&script).unwrap();
+
+            let mut rest = Vec::default();
+            let _ = s.read_to_end(&mut rest);
+            [get_track.to_vec(), rest].concat()
+        });
+        (address, handle)
+    }
+
+    #[test]
+    fn protocol_works() {
+        let mut script = vec![SET_KEY];
+        script.extend_from_slice(&0u32.to_be_bytes());
+        script.extend_from_slice(&16u32.to_be_bytes());
+        script.extend_from_slice(&1.5f32.to_bits().to_be_bytes());
+        script.push(1);
+        script.extend_from_slice(&[DELETE_KEY, 0, 0, 0, 0, 0, 0, 0, 8]);
+        script.extend_from_slice(&[SET_ROW, 0, 0, 1, 0]);
+        script.extend_from_slice(&[PAUSE, 1, SAVE_TRACKS]);
+        let (address, tracker) = mock_tracker(script);
+
+        let mut client = RocketClient::connect(&address).unwrap();
+        client.request_track("cam:x").unwrap();
+        client.request_track("cam:x").unwrap();
+
+        let mut events = Vec::default();
+        for _ in 0..200 {
+            events.extend(client.poll().unwrap());
+            if events.len() == 5 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(
+            vec![
+                RocketEvent::SetKey {
+                    track: "cam:x".into(),
+                    key: SyncKey {
+                        row: 16,
+                        value: 1.5,
+                        interpolation: SyncInterpolation::Linear
+                    }
+                },
+                RocketEvent::DeleteKey {
+                    track: "cam:x".into(),
+                    row: 8
+                },
+                RocketEvent::SetRow { row: 256 },
+                RocketEvent::Pause { paused: true },
+                RocketEvent::SaveTracks,
+            ],
+            events
+        );
+
+        // the row we just got is not sent back
+        client.send_row(256).unwrap();
+        client.send_row(300).unwrap();
+        drop(client);
+
+        let sent = tracker.join().unwrap();
+        let mut expected = vec![GET_TRACK, 0, 0, 0, 5];
+        expected.extend_from_slice(b"cam:x");
+        expected.extend_from_slice(&[SET_ROW, 0, 0, 1, 44]);
+        assert_eq!(expected, sent);
+    }
+}
@@ -0,0 +1,621 @@
+use crate::project::GridPos;
+use crate::project::Project;
+use crate::project::Property;
+use crate::project::PropertyValue;
+use crate::project::Resource;
+use crate::project::ResourceBuffer;
+use crate::project::ResourceMesh;
+use crate::project::ResourceProgram;
+use crate::project::ResourceText;
+use crate::project::ShaderType;
+use crate::project::Step;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use rhai::Array;
+use rhai::Dynamic;
+use rhai::Engine;
+use rhai::EvalAltResult;
+use rhai::Map;
+use rhai::FLOAT;
+use rhai::INT;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+type ScriptResult<T> = core::result::Result<T, Box<EvalAltResult>>;
+type SharedProject = Rc<RefCell<Project>>;
+
+/// Runs [Rhai](https://rhai.rs) scripts against a project.
+///
+/// Scripts work on a copy of the project, which only replaces the project when the script succeeds.
+///
+/// Flow:
+/// - `steps()` returns `#{x, y, type, values}` for all steps
+/// - `step_at(x, y)` returns the type of the step, or `()`
+/// - `add_step(x, y, type)`, `add_label(x, y, name)`, `add_program_step(x, y, resource_id)`
+/// - `add_set_uniform_f32(x, y, name, value)`, `add_set_uniform_f64(x, y, name, value)`
+/// - `add_set_uniform_vec3_f32(x, y, name, [x, y, z])`
+/// - `add_draw_mesh_step(x, y, resource_id, instances)`
+/// - `add_dispatch_step(x, y, resource_id, [groups_x, groups_y, groups_z])`
+/// - `remove_step(x, y)`
+///
+/// Resources:
+/// - `resources()` returns all ids, `resource_name(id)`, `resource_type(id)`
+/// - `rename_resource(id, name)`, `remove_resource(id)`
+/// - `add_text(name, file)`, `add_program(name)`, `add_mesh(name, file)` return the new id
+/// - `add_buffer(name, binding, elements, stride)` returns the new id
+/// - `add_shader(program_id, "Vertex"|"Fragment"|"Common"|"Geometry"|"Compute", text_id)`
+///
+/// Properties:
+/// - `properties()` returns all names, `get_property(name)`, `set_property(name, value_or_array)`
+/// - `add_property_f32(name, value)`, `add_property_f64(name, value)`
+/// - `add_property_vec3_f32(name, [x, y, z])`, `remove_property(name)`
+///
+/// Misc:
+/// - `project_name()`, `set_project_name(name)`
+/// - `validate(start_label)` returns the list of problems
+/// - `check(condition, message)` fails the script when `condition` is false
+#[derive(Debug, Default)]
+pub struct ScriptRunner {
+    output: Vec<String>,
+}
+
+impl ScriptRunner {
+    const MAX_OPERATIONS: u64 = 10_000_000;
+
+    /// The lines printed by all scripts run so far.
+    pub fn output(&self) -> &[String] {
+        &self.output
+    }
+    pub fn clear_output(&mut self) {
+        self.output.clear();
+    }
+
+    pub fn run(&mut self, project: &mut Project, script: &str) -> Result<()> {
+        let shared = Rc::new(RefCell::new(project.clone()));
+        let output = Rc::new(RefCell::new(Vec::default()));
+
+        let engine = Self::create_engine(&shared, &output);
+        let result = engine.run(script);
+
+        self.output.append(&mut output.borrow_mut());
+        result.map_err(|e| eyre!("{e}"))?;
+
+        drop(engine);
+        let scripted = Rc::try_unwrap(shared)
+            .map_err(|_| eyre!("Script still holds the project"))?
+            .into_inner();
+        project.replace(scripted);
+        Ok(())
+    }
+
+    fn create_engine(project: &SharedProject, output: &Rc<RefCell<Vec<String>>>) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(Self::MAX_OPERATIONS);
+
+        let o = output.clone();
+        engine.on_print(move |s| o.borrow_mut().push(s.to_string()));
+        let o = output.clone();
+        engine.on_debug(move |s, _source, pos| o.borrow_mut().push(format!("{pos:?} {s}")));
+
+        engine.register_fn(
+            "check",
+            |condition: bool, message: &str| -> ScriptResult<()> {
+                if condition {
+                    Ok(())
+                } else {
+                    Err(format!("Check failed: {message}").into())
+                }
+            },
+        );
+
+        Self::register_project(&mut engine, project);
+        Self::register_flow(&mut engine, project);
+        Self::register_resources(&mut engine, project);
+        Self::register_properties(&mut engine, project);
+
+        engine
+    }
+
+    fn register_project(engine: &mut Engine, project: &SharedProject) {
+        let p = project.clone();
+        engine.register_fn("project_name", move || p.borrow().name().to_string());
+        let p = project.clone();
+        engine.register_fn("set_project_name", move |name: &str| {
+            *p.borrow_mut().name_mut() = name.to_string();
+        });
+        let p = project.clone();
+        engine.register_fn("validate", move |start_label: &str| -> Array {
+            p.borrow()
+                .validate(start_label)
+                .into_iter()
+                .map(Dynamic::from)
+                .collect()
+        });
+    }
+
+    fn register_flow(engine: &mut Engine, project: &SharedProject) {
+        let p = project.clone();
+        engine.register_fn("steps", move || -> Array {
+            p.borrow()
+                .flow()
+                .steps()
+                .iter()
+                .map(|(s, gp)| {
+                    let mut m = Map::new();
+                    m.insert("x".into(), Dynamic::from(gp.x() as INT));
+                    m.insert("y".into(), Dynamic::from(gp.y() as INT));
+                    m.insert("type".into(), Dynamic::from(String::from(s)));
+                    let values: Array = s
+                        .values()
+                        .into_iter()
+                        .map(|v| Dynamic::from(v.to_string()))
+                        .collect();
+                    m.insert("values".into(), Dynamic::from(values));
+                    Dynamic::from(m)
+                })
+                .collect()
+        });
+        let p = project.clone();
+        engine.register_fn("step_at", move |x: INT, y: INT| -> ScriptResult<Dynamic> {
+            let gp = grid_pos(x, y)?;
+            Ok(p.borrow()
+                .flow()
+                .get_step_at(&gp)
+                .map(|s| Dynamic::from(String::from(s)))
+                .unwrap_or(Dynamic::UNIT))
+        });
+
+        let p = project.clone();
+        engine.register_fn("add_step", move |x: INT, y: INT, step_type: &str| {
+            if !Step::types().contains(&step_type) {
+                return Err(format!("Unknown step type '{step_type}'").into());
+            }
+            add_step(&p, x, y, Step::from(step_type))
+        });
+        let p = project.clone();
+        engine.register_fn("add_label", move |x: INT, y: INT, name: &str| {
+            let step = Step::Label {
+                name: name.to_string(),
+                version: 1,
+            };
+            add_step(&p, x, y, step)
+        });
+        let p = project.clone();
+        engine.register_fn("add_program_step", move |x: INT, y: INT, id: &str| {
+            let step = Step::Program {
+                resource_id: id.to_string(),
+                version: 1,
+            };
+            add_step(&p, x, y, step)
+        });
+        let p = project.clone();
+        engine.register_fn(
+            "add_set_uniform_f32",
+            move |x: INT, y: INT, name: &str, value: Dynamic| {
+                let step = Step::SetUniformF32 {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    version: 1,
+                };
+                add_step(&p, x, y, step)
+            },
+        );
+        let p = project.clone();
+        engine.register_fn(
+            "add_set_uniform_f64",
+            move |x: INT, y: INT, name: &str, value: Dynamic| {
+                let step = Step::SetUniformF64 {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    version: 1,
+                };
+                add_step(&p, x, y, step)
+            },
+        );
+        let p = project.clone();
+        engine.register_fn(
+            "add_set_uniform_vec3_f32",
+            move |x: INT, y: INT, name: &str, values: Array| {
+                let [a, b, c] = <[Dynamic; 3]>::try_from(values)
+                    .map_err(|_| "SetUniformVec3F32 needs three values")?;
+                let step = Step::SetUniformVec3F32 {
+                    name: name.to_string(),
+                    values: [a.to_string(), b.to_string(), c.to_string()],
+                    version: 1,
+                };
+                add_step(&p, x, y, step)
+            },
+        );
+        let p = project.clone();
+        engine.register_fn(
+            "add_draw_mesh_step",
+            move |x: INT, y: INT, id: &str, instances: INT| {
+                let step = Step::DrawMesh {
+                    resource_id: id.to_string(),
+                    instances: instances.max(0) as u32,
+                    version: 1,
+                };
+                add_step(&p, x, y, step)
+            },
+        );
+        let p = project.clone();
+        engine.register_fn(
+            "add_dispatch_step",
+            move |x: INT, y: INT, id: &str, groups: Array| -> ScriptResult<bool> {
+                let groups = dynamic_to_f64s(Dynamic::from(groups))?;
+                let [gx, gy, gz] = match groups[..] {
+                    [gx, gy, gz] => [gx, gy, gz].map(|g| g.max(0.0) as u32),
+                    _ => return Err("Expected [x, y, z] work groups".into()),
+                };
+                let step = Step::Dispatch {
+                    resource_id: id.to_string(),
+                    groups: [gx, gy, gz],
+                    version: 1,
+                };
+                add_step(&p, x, y, step)
+            },
+        );
+        let p = project.clone();
+        engine.register_fn("remove_step", move |x: INT, y: INT| -> ScriptResult<bool> {
+            let gp = grid_pos(x, y)?;
+            let mut removed = false;
+            p.borrow_mut()
+                .with_flow_mut(|flow| removed = flow.remove_step(&gp).is_some());
+            Ok(removed)
+        });
+    }
+
+    fn register_resources(engine: &mut Engine, project: &SharedProject) {
+        let p = project.clone();
+        engine.register_fn("resources", move || -> Array {
+            let mut ids: Vec<String> = p
+                .borrow()
+                .resource_manager()
+                .resources()
+                .keys()
+                .cloned()
+                .collect();
+            ids.sort();
+            ids.into_iter().map(Dynamic::from).collect()
+        });
+        let p = project.clone();
+        engine.register_fn("resource_name", move |id: &str| -> ScriptResult<String> {
+            match p.borrow().resource_manager().get(&id.to_string()) {
+                Some(r) => Ok(r.name().to_string()),
+                None => Err(format!("Unknown resource {id}").into()),
+            }
+        });
+        let p = project.clone();
+        engine.register_fn("resource_type", move |id: &str| -> ScriptResult<String> {
+            let t = match p.borrow().resource_manager().get(&id.to_string()) {
+                Some(Resource::Text(_)) => "Text",
+                Some(Resource::Program(_)) => "Program",
+                Some(Resource::Audio(_)) => "Audio",
+                Some(Resource::Mesh(_)) => "Mesh",
+                Some(Resource::Buffer(_)) => "Buffer",
+                Some(Resource::None) => "None",
+                None => return Err(format!("Unknown resource {id}").into()),
+            };
+            Ok(t.to_string())
+        });
+        let p = project.clone();
+        engine.register_fn("rename_resource", move |id: &str, name: &str| -> bool {
+            let mut p = p.borrow_mut();
+            let name_mut = match p.resource_manager.get_mut(&id.to_string()) {
+                Some(Resource::Text(rt)) => rt.name_mut(),
+                Some(Resource::Program(rp)) => rp.name_mut(),
+                Some(Resource::Audio(ra)) => ra.name_mut(),
+                Some(Resource::Mesh(rm)) => rm.name_mut(),
+                Some(Resource::Buffer(rb)) => rb.name_mut(),
+                _ => return false,
+            };
+            *name_mut = name.to_string();
+            true
+        });
+        let p = project.clone();
+        engine.register_fn("remove_resource", move |id: &str| -> bool {
+            p.borrow_mut()
+                .resource_manager
+                .remove(&id.to_string())
+                .is_some()
+        });
+        let p = project.clone();
+        engine.register_fn("add_text", move |name: &str, file: &str| -> String {
+            let mut rt = ResourceText::default();
+            *rt.name_mut() = name.to_string();
+            rt.set_file(PathBuf::from(file));
+            p.borrow_mut().resource_manager.add(Resource::Text(rt))
+        });
+        let p = project.clone();
+        engine.register_fn("add_program", move |name: &str| -> String {
+            let mut rp = ResourceProgram::default();
+            *rp.name_mut() = name.to_string();
+            p.borrow_mut().resource_manager.add(Resource::Program(rp))
+        });
+        let p = project.clone();
+        engine.register_fn("add_mesh", move |name: &str, file: &str| -> String {
+            let mut rm = ResourceMesh::default();
+            *rm.name_mut() = name.to_string();
+            rm.set_file(PathBuf::from(file));
+            p.borrow_mut().resource_manager.add(Resource::Mesh(rm))
+        });
+        let p = project.clone();
+        engine.register_fn(
+            "add_buffer",
+            move |name: &str, binding: INT, elements: INT, stride: INT| -> String {
+                let mut rb = ResourceBuffer::default();
+                *rb.name_mut() = name.to_string();
+                rb.set_layout(
+                    binding.max(0) as u32,
+                    elements.max(0) as u32,
+                    stride.max(0) as u32,
+                );
+                p.borrow_mut().resource_manager.add(Resource::Buffer(rb))
+            },
+        );
+        let p = project.clone();
+        engine.register_fn(
+            "add_shader",
+            move |program_id: &str, shader_type: &str, text_id: &str| -> ScriptResult<()> {
+                let shader_type = match shader_type {
+                    "Vertex" => ShaderType::Vertex,
+                    "Fragment" => ShaderType::Fragment,
+                    "Common" => ShaderType::Common,
+                    "Geometry" => ShaderType::Geometry,
+                    "Compute" => ShaderType::Compute,
+                    o => return Err(format!("Unknown shader type '{o}'").into()),
+                };
+                match p
+                    .borrow_mut()
+                    .resource_manager
+                    .get_mut(&program_id.to_string())
+                {
+                    Some(Resource::Program(rp)) => {
+                        rp.add_shader(shader_type, text_id.to_string());
+                        Ok(())
+                    }
+                    _ => Err(format!("{program_id} is not a program").into()),
+                }
+            },
+        );
+    }
+
+    fn register_properties(engine: &mut Engine, project: &SharedProject) {
+        let p = project.clone();
+        engine.register_fn("properties", move || -> Array {
+            let mut names = Array::new();
+            p.borrow().with_property_manager(|pm| {
+                names = pm.entries().keys().cloned().map(Dynamic::from).collect();
+            });
+            names
+        });
+        let p = project.clone();
+        engine.register_fn("get_property", move |name: &str| -> Dynamic {
+            let mut value = Dynamic::UNIT;
+            p.borrow().with_property_manager(|pm| {
+                if let Some(property) = pm.entries().get(name) {
+                    value = property_to_dynamic(property.value());
+                }
+            });
+            value
+        });
+        let p = project.clone();
+        engine.register_fn(
+            "set_property",
+            move |name: &str, value: Dynamic| -> ScriptResult<bool> {
+                let values = dynamic_to_f64s(value)?;
+                let mut changed = false;
+                p.borrow_mut().with_property_manager_mut(|pm| {
+                    if let Some(property) = pm.get_mut(name) {
+                        changed = property.value_mut().set_from_f64s(&values);
+                    }
+                });
+                Ok(changed)
+            },
+        );
+        let p = project.clone();
+        engine.register_fn("add_property_f32", move |name: &str, value: FLOAT| {
+            add_property(&p, name, Property::default_f32(value as f32));
+        });
+        let p = project.clone();
+        engine.register_fn("add_property_f64", move |name: &str, value: FLOAT| {
+            add_property(&p, name, Property::default_f64(value));
+        });
+        let p = project.clone();
+        engine.register_fn(
+            "add_property_vec3_f32",
+            move |name: &str, values: Array| -> ScriptResult<()> {
+                let values = dynamic_to_f64s(Dynamic::from(values))?;
+                let [x, y, z] = <[f64; 3]>::try_from(values)
+                    .map_err(|_| "add_property_vec3_f32 needs three values")?;
+                let property = Property::default_vec3_f32(&[x as f32, y as f32, z as f32]);
+                add_property(&p, name, property);
+                Ok(())
+            },
+        );
+        let p = project.clone();
+        engine.register_fn("remove_property", move |name: &str| -> bool {
+            let mut removed = false;
+            p.borrow_mut().with_property_manager_mut(|pm| {
+                removed = pm.delete_entry(name).is_some();
+            });
+            removed
+        });
+    }
+}
+
+fn grid_pos(x: INT, y: INT) -> ScriptResult<GridPos> {
+    match (u16::try_from(x), u16::try_from(y)) {
+        (Ok(x), Ok(y)) => Ok(GridPos::new(x, y)),
+        _ => Err(format!("Invalid grid position {x}, {y}").into()),
+    }
+}
+
+/// Returns `false` if the position is already in use.
+fn add_step(project: &SharedProject, x: INT, y: INT, step: Step) -> ScriptResult<bool> {
+    let gp = grid_pos(x, y)?;
+    let mut p = project.borrow_mut();
+    if p.flow().get_step_at(&gp).is_some() {
+        return Ok(false);
+    }
+    let mut step = Some(step);
+    p.with_flow_mut(|flow| {
+        if let Some(step) = step.take() {
+            flow.add_step(&gp, step);
+        }
+    });
+    Ok(true)
+}
+
+fn add_property(project: &SharedProject, name: &str, property: Property) {
+    let mut property = Some(property);
+    project.borrow_mut().with_property_manager_mut(|pm| {
+        if let Some(property) = property.take() {
+            pm.add_entry(name, property);
+        }
+    });
+}
+
+fn property_to_dynamic(value: &PropertyValue) -> Dynamic {
+    fn array(values: &[f32]) -> Dynamic {
+        let a: Array = values.iter().map(|v| Dynamic::from(*v as FLOAT)).collect();
+        Dynamic::from(a)
+    }
+    match value {
+        PropertyValue::F32 { value } => Dynamic::from(*value as FLOAT),
+        PropertyValue::F64 { value } => Dynamic::from(*value),
+        PropertyValue::Bool { value } => Dynamic::from(*value),
+        PropertyValue::Vec2F32 { values } => array(values),
+        PropertyValue::Vec3F32 { values } => array(values),
+        PropertyValue::Vec3F32Size4 { values } => array(values),
+        PropertyValue::Gradient { gradient } => array(&gradient.bake(8).concat()),
+        PropertyValue::Matrix { matrix } => array(&matrix.values()),
+        PropertyValue::None => Dynamic::UNIT,
+    }
+}
+
+fn dynamic_to_f64s(value: Dynamic) -> ScriptResult<Vec<f64>> {
+    fn number(v: &Dynamic) -> ScriptResult<f64> {
+        if let Ok(f) = v.as_float() {
+            Ok(f)
+        } else if let Ok(i) = v.as_int() {
+            Ok(i as f64)
+        } else if let Ok(b) = v.as_bool() {
+            Ok(if b { 1.0 } else { 0.0 })
+        } else {
+            Err(format!("Expected a number, got {}", v.type_name()).into())
+        }
+    }
+    if value.is_array() {
+        value.cast::<Array>().iter().map(number).collect()
+    } else {
+        Ok(vec![number(&value)?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_changes_project() {
+        let mut project = Project::default();
+        let mut runner = ScriptRunner::default();
+        runner
+            .run(
+                &mut project,
+                r#"
+                let program = add_program("main");
+                add_label(0, 0, "start");
+                add_program_step(0, 1, program);
+                for i in 0..3 {
+                    add_set_uniform_f32(0, 2 + i, "fLayer" + i, i * 0.5);
+                }
+                add_step(0, 5, "FullscreenQuad");
+                check(!add_label(0, 0, "taken"), "position is in use");
+
+                rename_resource(program, "renamed");
+                add_property_f32("fSpeed", 1.0);
+                set_property("fSpeed", 2);
+                print(resource_name(program) + " " + get_property("fSpeed"));
+                print(steps().len());
+                "#,
+            )
+            .unwrap();
+        assert_eq!(&["renamed 2.0", "6"], runner.output());
+        assert_eq!(6, project.flow().steps().len());
+        assert!(matches!(
+            project.flow().get_step_at(&GridPos::new(0, 4)),
+            Some(Step::SetUniformF32 { name, value, .. }) if name == "fLayer2" && value == "1.0"
+        ));
+        assert!(project
+            .validate("start")
+            .iter()
+            .any(|p| p.contains("shader")));
+    }
+
+    #[test]
+    fn compute_steps_validate() {
+        let mut project = Project::default();
+        let mut runner = ScriptRunner::default();
+        runner
+            .run(
+                &mut project,
+                r#"
+                let sim = add_program("sim");
+                add_shader(sim, "Compute", add_text("sim", "sim.comp.glsl"));
+                add_buffer("particles", 0, 1024, 16);
+                add_buffer("forces", 0, 1024, 16);
+                add_label(0, 0, "start");
+                add_dispatch_step(0, 1, sim, [16, 1, 1]);
+                add_program_step(0, 2, sim);
+                "#,
+            )
+            .unwrap();
+        assert!(matches!(
+            project.flow().get_step_at(&GridPos::new(0, 1)),
+            Some(Step::Dispatch {
+                groups: [16, 1, 1],
+                ..
+            })
+        ));
+        let problems = project.validate("start");
+        let count = |s: &str| problems.iter().filter(|p| p.contains(s)).count();
+        assert_eq!(1, count("needs a Dispatch step"), "{problems:?}");
+        assert_eq!(2, count("shares binding 0"), "{problems:?}");
+        assert_eq!(0, count("has no Vertex shader"), "{problems:?}");
+        assert_eq!(0, count("not a compute program"), "{problems:?}");
+
+        assert!(runner
+            .run(&mut project, "add_dispatch_step(0, 3, \"x\", [1, 1]);")
+            .is_err());
+    }
+
+    #[test]
+    fn failed_script_keeps_project() {
+        let mut project = Project::default();
+        let version = project.version();
+        let mut runner = ScriptRunner::default();
+        let result = runner.run(
+            &mut project,
+            r#"
+            add_label(0, 0, "start");
+            print("before");
+            check(validate("start").len() == 1, "expected one problem");
+            "#,
+        );
+        assert!(result.is_err());
+        assert_eq!(&["before"], runner.output());
+        assert!(project.flow().steps().is_empty());
+        assert_eq!(version, project.version());
+
+        assert!(runner
+            .run(&mut project, "add_step(0, 0, \"Bogus\");")
+            .is_err());
+        assert!(runner
+            .run(&mut project, "add_label(-1, 0, \"x\");")
+            .is_err());
+        assert!(runner.run(&mut project, "loop {}").is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::script_runner::ScriptRunner;
+use crate::state::State;
+use crate::window::Window;
+
+#[derive(Debug, Default)]
+pub struct ScriptWindow {
+    is_open: bool,
+    script: String,
+    script_runner: ScriptRunner,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct ScriptWindowSave {
+    #[serde(default)]
+    is_open: bool,
+    #[serde(default)]
+    script: String,
+}
+
+impl From<&ScriptWindow> for ScriptWindowSave {
+    fn from(sw: &ScriptWindow) -> Self {
+        Self {
+            is_open: sw.is_open,
+            script: sw.script.clone(),
+        }
+    }
+}
+
+impl Window for ScriptWindow {
+    fn name(&self) -> &str {
+        "Script"
+    }
+    fn is_open(&self) -> bool {
+        self.is_open
+    }
+    fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    fn update(&mut self, ctx: &egui::Context, state: &mut State) {
+        let mut is_open = self.is_open;
+        egui::Window::new("Script")
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut is_open)
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.script)
+                        .code_editor()
+                        .desired_rows(12)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("add_label(0, 0, \"start\");"),
+                );
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Run")
+                        .on_hover_text("Runs the Rhai script against the project, Ctrl+Enter")
+                        .clicked()
+                        || ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::Enter))
+                    {
+                        self.last_error = self
+                            .script_runner
+                            .run(&mut state.project, &self.script)
+                            .err()
+                            .map(|e| format!("{e}"));
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("Load...").clicked() {
+                        if let Some(file) = rfd::FileDialog::new()
+                            .add_filter("Rhai", &["rhai"])
+                            .pick_file()
+                        {
+                            match std::fs::read_to_string(&file) {
+                                Ok(script) => self.script = script,
+                                Err(e) => self.last_error = Some(format!("{file:?} -> {e}")),
+                            }
+                        }
+                    }
+                    if ui.button("Clear Output").clicked() {
+                        self.script_runner.clear_output();
+                        self.last_error = None;
+                    }
+                });
+                if let Some(last_error) = &self.last_error {
+                    ui.colored_label(egui::Color32::RED, last_error);
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in self.script_runner.output() {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+        self.is_open = is_open;
+    }
+    fn serialize(&self) -> String {
+        let save: ScriptWindowSave = self.into();
+
+        ron::ser::to_string(&save).unwrap_or_default()
+    }
+    fn deserialize(&mut self, data: &str) {
+        let save: ScriptWindowSave = ron::from_str(data).unwrap_or_default();
+
+        self.is_open = save.is_open;
+        self.script = save.script;
+    }
+}
@@ -1,4 +1,5 @@
 use crate::command_queue::COMMAND_QUEUE;
+#[cfg(not(target_arch = "wasm32"))]
 use crate::path_helper::PathHelper;
 use crate::project::Resource;
 use crate::project::ResourceId;
@@ -139,12 +140,9 @@ impl ShadersWindow {
                             egui::ComboBox::from_label("Shader Type")
                                 .selected_text(format!("{:?}", shader_type))
                                 .show_ui(ui, |ui| {
-                                    ui.selectable_value(
-                                        shader_type,
-                                        ShaderType::Fragment,
-                                        "Fragment",
-                                    );
-                                    ui.selectable_value(shader_type, ShaderType::Vertex, "Vertex");
+                                    for st in ShaderType::ALL {
+                                        ui.selectable_value(shader_type, st, st.to_string());
+                                    }
                                 });
                             let resource_id = &mut self.new_shader_resource_id;
                             egui::ComboBox::from_label("Resource Id")
@@ -307,9 +305,9 @@ impl Window for ShadersWindow {
                                             let _ = rt.save( Some( parent ) );
                                         }
 
-                                        let enabled = true;
+                                        #[cfg(not(target_arch = "wasm32"))]
                                         if ui
-                                            .add_enabled(enabled, egui::Button::new("Save as..."))
+                                            .button("Save as...")
                                             .clicked()
                                         {
                                             // let filename = PathHelper::into_string( rt.file().unwrap_or_else(|| Path::new("")) ).unwrap_or_default() ;
@@ -340,9 +338,9 @@ impl Window for ShadersWindow {
                                                 //let parent = None;
                                                 let _ = rt.reload( Some( parent ) );
                                             }
-                                        let enabled = true;
+                                        #[cfg(not(target_arch = "wasm32"))]
                                             if ui
-                                                .add_enabled(enabled, egui::Button::new("Load from..."))
+                                                .button("Load from...")
                                                 .clicked()
                                             {
                                                 //let filename = shader_source.default_file_name();
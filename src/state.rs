@@ -1,5 +1,11 @@
+use crate::midi_input::MidiInput;
+use crate::osc_server::OscServer;
+use crate::project::PresetTransition;
 use crate::project::Project;
+use crate::project::PropertyRandomizer;
 use crate::project::ResourceId;
+use crate::project::ShadertoyImport;
+use crate::rocket_client::RocketClient;
 use crate::time_series::TimeSeries;
 use crate::McGuffinContainer;
 use crate::StepEditorScratch;
@@ -9,6 +15,8 @@ use std::collections::VecDeque;
 use std::path::Path;
 use std::path::PathBuf;
 
+const PROJECT_BUNDLE_KEY: &str = "project_bundle";
+
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
 pub struct State {
     #[serde(skip)]
@@ -35,6 +43,24 @@ pub struct State {
 
     #[serde(skip)]
     pub step_editor_scratch: StepEditorScratch,
+
+    #[serde(skip)]
+    pub rocket: Option<RocketClient>,
+
+    #[serde(skip)]
+    pub midi_input: Option<MidiInput>,
+    /// The property the next MIDI control will be bound to.
+    #[serde(skip)]
+    pub midi_learn: Option<String>,
+
+    #[serde(skip)]
+    pub osc_server: Option<OscServer>,
+
+    #[serde(skip)]
+    pub preset_transition: Option<PresetTransition>,
+
+    #[serde(skip)]
+    pub randomizer: PropertyRandomizer,
 }
 
 impl State {
@@ -74,16 +100,21 @@ impl State {
         self.project_path.as_deref()
     }
     pub fn set_project_path(&mut self, project_path: PathBuf) {
+        self.clear_project_path();
+
+        self.recent_project_paths.retain(|p| *p != project_path);
+
+        self.project_path = Some(project_path);
+    }
+
+    /// Forgets the project path, e.g. after an import, so saving can't overwrite the previous project.
+    pub fn clear_project_path(&mut self) {
         if let Some(old_project_path) = self.project_path.take() {
             self.recent_project_paths.push_back(old_project_path);
             while self.recent_project_paths.len() > 5 {
                 self.recent_project_paths.pop_front();
             }
         }
-
-        self.recent_project_paths.retain(|p| *p != project_path);
-
-        self.project_path = Some(project_path);
     }
 
     pub fn recent_project_paths(&self) -> &VecDeque<PathBuf> {
@@ -123,7 +154,108 @@ impl State {
         }
     }
 
+    pub fn load_project_bundle(&mut self, data: &str) {
+        match Project::try_load_from_bundle(data) {
+            Ok(project) => {
+                self.project = project;
+                self.clear_project_path();
+            }
+            Err(e) => {
+                // :TODO: report
+                eprintln!("Failed loading project bundle {e:#?}")
+            }
+        }
+    }
+
+    /// Replaces the project with one running a shader exported from Shadertoy as JSON.
+    pub fn import_shadertoy(&mut self, data: &str) {
+        match ShadertoyImport::from_json(data).and_then(|i| i.into_project()) {
+            Ok((project, warnings)) => {
+                for w in warnings {
+                    // :TODO: report
+                    eprintln!("Shadertoy import: {w}");
+                }
+                self.project.replace(project);
+                self.clear_project_path();
+            }
+            Err(e) => {
+                // :TODO: report
+                eprintln!("Failed importing Shadertoy shader {e:#?}")
+            }
+        }
+    }
+
+    pub fn save_project_to_storage(&self, storage: &mut dyn eframe::Storage) {
+        match self.project.to_bundle() {
+            Ok(data) => storage.set_string(PROJECT_BUNDLE_KEY, data),
+            Err(e) => {
+                // :TODO: report
+                eprintln!("Failed saving project to storage {e:#?}")
+            }
+        }
+    }
+
+    pub fn load_project_from_storage(&mut self, storage: &dyn eframe::Storage) {
+        if let Some(data) = storage.get_string(PROJECT_BUNDLE_KEY) {
+            self.load_project_bundle(&data);
+        }
+    }
+
+    pub fn start_osc_server(&mut self, port: u16) -> Result<()> {
+        self.osc_server = None;
+        self.osc_server = Some(OscServer::start(&format!("0.0.0.0:{port}"))?);
+        Ok(())
+    }
+
+    pub fn connect_rocket(&mut self, address: &str) -> Result<()> {
+        let mut rocket = RocketClient::connect(address)?;
+        for name in self.project.sync_track_names() {
+            rocket.request_track(&name)?;
+        }
+        self.rocket = Some(rocket);
+        Ok(())
+    }
+    pub fn disconnect_rocket(&mut self) {
+        self.rocket = None;
+    }
+
+    /// Writes the sync tracks as GNU Rocket `.track` files into the `sync` folder of the project.
+    pub fn export_sync_tracks(&self) -> Result<Vec<PathBuf>> {
+        let Some(pp) = &self.project_path else {
+            return Ok(Vec::default());
+        };
+        self.project.sync_tracks().export_rocket(&pp.join("sync"))
+    }
+
     pub fn clear_project(&mut self) {
         self.project = Project::default();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_import_keeps_old_project() {
+        let folder = std::env::temp_dir().join(format!("sfrs_state_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("sfrs.ron"), "old project").unwrap();
+        let mut state = State::default();
+        state.set_project_path(folder.clone());
+
+        state.load_project_bundle(&Project::default().to_bundle().unwrap());
+        assert_eq!(None, state.project_path());
+        assert!(state.recent_project_paths().contains(&folder));
+
+        // what happens on exit
+        state.save_project();
+        state.save_all_resources().unwrap();
+        assert_eq!(
+            "old project",
+            std::fs::read_to_string(folder.join("sfrs.ron")).unwrap()
+        );
+
+        let _ = std::fs::remove_dir_all(folder);
+    }
+}
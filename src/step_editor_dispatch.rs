@@ -0,0 +1,74 @@
+use crate::command_queue::COMMAND_QUEUE;
+use crate::project::GridPos;
+use crate::project::Project;
+use crate::project::Resource;
+use crate::project::Step;
+use crate::Command;
+use crate::StepEditor;
+use crate::StepEditorScratch;
+
+#[derive(Debug, Default)]
+pub struct StepEditorDispatch {}
+
+impl StepEditor for StepEditorDispatch {
+    fn update(
+        &self,
+        ui: &mut egui::Ui,
+        project: &Project,
+        _step_editor_scratch: &mut StepEditorScratch,
+        step: &Step,
+        grid_pos: &GridPos,
+    ) -> bool {
+        match step {
+            Step::Dispatch {
+                resource_id,
+                groups,
+                ..
+            } => {
+                ui.label("DI");
+                let mut selected_resource_id = resource_id.clone();
+                let mut selected_groups = *groups;
+                egui::ComboBox::from_label("Compute Program")
+                    .selected_text(
+                        egui::RichText::new(resource_id.as_str())
+                            .monospace()
+                            .strong(),
+                    )
+                    .width(192.0)
+                    .show_ui(ui, |ui| {
+                        let compute_resources = project
+                            .resource_manager()
+                            .resources()
+                            .iter()
+                            .filter(|(_k, r)| matches!(r, Resource::Program(rp) if rp.is_compute()))
+                            .map(|(k, r)| (k, r.name()));
+                        for (id, name) in compute_resources {
+                            ui.selectable_value(
+                                &mut selected_resource_id,
+                                id.to_string(),
+                                egui::RichText::new(format!("{id} - {name}")).monospace(),
+                            );
+                        }
+                    });
+                for (g, prefix) in selected_groups.iter_mut().zip(["x ", "y ", "z "]) {
+                    ui.add(
+                        egui::DragValue::new(g)
+                            .clamp_range(1..=65535)
+                            .prefix(prefix),
+                    );
+                }
+                ui.label("Groups");
+                if selected_resource_id != *resource_id || selected_groups != *groups {
+                    let _ = COMMAND_QUEUE.send(Command::HackStepDispatchSetResourceIdAndGroups {
+                        grid_pos: *grid_pos,
+                        resource_id: selected_resource_id,
+                        groups: selected_groups,
+                    });
+                }
+
+                true
+            }
+            _ => false,
+        }
+    }
+}
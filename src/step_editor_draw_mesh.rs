@@ -0,0 +1,72 @@
+use crate::command_queue::COMMAND_QUEUE;
+use crate::project::GridPos;
+use crate::project::Project;
+use crate::project::Resource;
+use crate::project::Step;
+use crate::Command;
+use crate::StepEditor;
+use crate::StepEditorScratch;
+
+#[derive(Debug, Default)]
+pub struct StepEditorDrawMesh {}
+
+impl StepEditor for StepEditorDrawMesh {
+    fn update(
+        &self,
+        ui: &mut egui::Ui,
+        project: &Project,
+        _step_editor_scratch: &mut StepEditorScratch,
+        step: &Step,
+        grid_pos: &GridPos,
+    ) -> bool {
+        match step {
+            Step::DrawMesh {
+                resource_id,
+                instances,
+                ..
+            } => {
+                ui.label("DM");
+                let mut selected_resource_id = resource_id.clone();
+                let mut selected_instances = *instances;
+                egui::ComboBox::from_label("Mesh")
+                    .selected_text(
+                        egui::RichText::new(resource_id.as_str())
+                            .monospace()
+                            .strong(),
+                    )
+                    .width(192.0)
+                    .show_ui(ui, |ui| {
+                        let mesh_resources = project
+                            .resource_manager()
+                            .resources()
+                            .iter()
+                            .filter(|(_k, r)| matches!(r, Resource::Mesh(_)))
+                            .map(|(k, r)| (k, r.name()));
+                        for (id, name) in mesh_resources {
+                            ui.selectable_value(
+                                &mut selected_resource_id,
+                                id.to_string(),
+                                egui::RichText::new(format!("{id} - {name}")).monospace(),
+                            );
+                        }
+                    });
+                ui.add(
+                    egui::DragValue::new(&mut selected_instances)
+                        .clamp_range(0..=65536)
+                        .prefix("Instances "),
+                );
+                if selected_resource_id != *resource_id || selected_instances != *instances {
+                    let _ =
+                        COMMAND_QUEUE.send(Command::HackStepDrawMeshSetResourceIdAndInstances {
+                            grid_pos: *grid_pos,
+                            resource_id: selected_resource_id,
+                            instances: selected_instances,
+                        });
+                }
+
+                true
+            }
+            _ => false,
+        }
+    }
+}
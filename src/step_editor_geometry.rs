@@ -0,0 +1,82 @@
+use crate::command_queue::COMMAND_QUEUE;
+use crate::project::GridPos;
+use crate::project::Project;
+use crate::project::Step;
+use crate::Command;
+use crate::StepEditor;
+use crate::StepEditorScratch;
+
+/// Edits the generated geometry steps, `Plane`, `Sphere`, `LineStrip`, and `Particles`.
+#[derive(Debug, Default)]
+pub struct StepEditorGeometry {}
+
+impl StepEditor for StepEditorGeometry {
+    fn update(
+        &self,
+        ui: &mut egui::Ui,
+        _project: &Project,
+        _step_editor_scratch: &mut StepEditorScratch,
+        step: &Step,
+        grid_pos: &GridPos,
+    ) -> bool {
+        let (tag, detail, instances, quads) = match step {
+            Step::Plane {
+                subdivisions,
+                instances,
+                ..
+            } => (
+                "PL",
+                Some(("Subdivisions", *subdivisions)),
+                *instances,
+                None,
+            ),
+            Step::Sphere {
+                subdivisions,
+                instances,
+                ..
+            } => ("SP", Some(("Rings", *subdivisions)), *instances, None),
+            Step::LineStrip {
+                points, instances, ..
+            } => ("LS", Some(("Points", *points)), *instances, None),
+            Step::Particles { count, quads, .. } => ("PA", None, *count, Some(*quads)),
+            _ => return false,
+        };
+        ui.label(tag);
+        let mut new_detail = detail.map(|(_, d)| d).unwrap_or_default();
+        let mut new_instances = instances;
+        let mut new_quads = quads.unwrap_or_default();
+        if let Some((label, _)) = detail {
+            ui.add(
+                egui::DragValue::new(&mut new_detail)
+                    .clamp_range(1..=1024)
+                    .prefix(format!("{label} ")),
+            );
+        }
+        let label = if quads.is_some() {
+            "Count "
+        } else {
+            "Instances "
+        };
+        ui.add(
+            egui::DragValue::new(&mut new_instances)
+                .clamp_range(0..=1 << 20)
+                .prefix(label),
+        );
+        if quads.is_some() {
+            ui.checkbox(&mut new_quads, "Quads")
+                .on_hover_text("Quads from -1..1, or single points sized by gl_PointSize");
+        }
+        if detail.is_some_and(|(_, d)| d != new_detail)
+            || new_instances != instances
+            || quads.is_some_and(|q| q != new_quads)
+        {
+            let _ = COMMAND_QUEUE.send(Command::HackStepGeometrySetParameters {
+                grid_pos: *grid_pos,
+                detail: new_detail,
+                instances: new_instances,
+                quads: new_quads,
+            });
+        }
+        true
+    }
+}
@@ -0,0 +1,102 @@
+use crate::command_queue::COMMAND_QUEUE;
+use crate::project::BlendMode;
+use crate::project::CullMode;
+use crate::project::DepthTest;
+use crate::project::GridPos;
+use crate::project::Project;
+use crate::project::Step;
+use crate::Command;
+use crate::StepEditor;
+use crate::StepEditorScratch;
+
+/// Edits the render state steps, `Clear`, `Blend`, `Depth`, `Cull`, and `Viewport`.
+#[derive(Debug, Default)]
+pub struct StepEditorRenderState {}
+
+impl StepEditorRenderState {
+    fn combo<T: Copy + PartialEq + core::fmt::Display>(
+        ui: &mut egui::Ui,
+        name: &str,
+        grid_pos: &GridPos,
+        value: &mut T,
+        all: &[T],
+    ) {
+        egui::ComboBox::from_id_source((name, grid_pos.x(), grid_pos.y()))
+            .selected_text(value.to_string())
+            .show_ui(ui, |ui| {
+                for v in all {
+                    ui.selectable_value(value, *v, v.to_string());
+                }
+            });
+    }
+}
+
+impl StepEditor for StepEditorRenderState {
+    fn update(
+        &self,
+        ui: &mut egui::Ui,
+        _project: &Project,
+        _step_editor_scratch: &mut StepEditorScratch,
+        step: &Step,
+        grid_pos: &GridPos,
+    ) -> bool {
+        let mut new_step = step.clone();
+        match &mut new_step {
+            Step::Clear { color, depth, .. } => {
+                ui.label("CL");
+                let mut clear_color = color.is_some();
+                ui.checkbox(&mut clear_color, "Color");
+                let mut c = color.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+                if clear_color {
+                    ui.color_edit_button_rgba_unmultiplied(&mut c);
+                }
+                *color = clear_color.then_some(c);
+
+                let mut clear_depth = depth.is_some();
+                ui.checkbox(&mut clear_depth, "Depth")
+                    .on_hover_text("Needs the depth write of the last Depth step enabled");
+                let mut d = depth.unwrap_or(1.0);
+                if clear_depth {
+                    ui.add(
+                        egui::DragValue::new(&mut d)
+                            .speed(0.01)
+                            .clamp_range(0.0..=1.0),
+                    );
+                }
+                *depth = clear_depth.then_some(d);
+            }
+            Step::Blend { mode, .. } => {
+                ui.label("BL");
+                Self::combo(ui, "blend", grid_pos, mode, &BlendMode::ALL);
+            }
+            Step::Depth { test, write, .. } => {
+                ui.label("DE");
+                Self::combo(ui, "depth", grid_pos, test, &DepthTest::ALL);
+                ui.checkbox(write, "Write");
+            }
+            Step::Cull { mode, .. } => {
+                ui.label("CU");
+                Self::combo(ui, "cull", grid_pos, mode, &CullMode::ALL);
+            }
+            Step::Viewport { rect, .. } => {
+                ui.label("VP");
+                for (v, prefix) in rect.iter_mut().zip(["x ", "y ", "w ", "h "]) {
+                    ui.add(
+                        egui::DragValue::new(v)
+                            .speed(0.01)
+                            .clamp_range(0.0..=1.0)
+                            .prefix(prefix),
+                    );
+                }
+            }
+            _ => return false,
+        }
+        if new_step != *step {
+            let _ = COMMAND_QUEUE.send(Command::HackStepRenderStateSet {
+                grid_pos: *grid_pos,
+                step: new_step,
+            });
+        }
+        true
+    }
+}
@@ -2,8 +2,12 @@ use crate::project::GridPos;
 use crate::project::Project;
 use crate::project::Step;
 use crate::state::State;
+use crate::step_editor_dispatch::StepEditorDispatch;
+use crate::step_editor_draw_mesh::StepEditorDrawMesh;
+use crate::step_editor_geometry::StepEditorGeometry;
 use crate::step_editor_label::StepEditorLabel;
 use crate::step_editor_program::StepEditorProgram;
+use crate::step_editor_render_state::StepEditorRenderState;
 use crate::step_editor_set_uniform_f32::StepEditorSetUniformF32;
 use crate::step_editor_set_uniform_f64::StepEditorSetUniformF64;
 use crate::step_editor_set_uniform_vec3_f32::StepEditorSetUniformVec3F32;
@@ -23,6 +27,10 @@ impl Default for StepEditorUi {
         step_editors.push(Box::new(StepEditorSetUniformF64::default()));
         step_editors.push(Box::new(StepEditorSetUniformVec3F32::default()));
         step_editors.push(Box::new(StepEditorLabel::default()));
+        step_editors.push(Box::new(StepEditorDrawMesh::default()));
+        step_editors.push(Box::new(StepEditorGeometry::default()));
+        step_editors.push(Box::new(StepEditorRenderState::default()));
+        step_editors.push(Box::new(StepEditorDispatch::default()));
 
         Self { step_editors }
     }
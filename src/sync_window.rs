@@ -0,0 +1,271 @@
+use crate::project::SyncInterpolation;
+use crate::project::SyncKey;
+use crate::rocket_client::RocketClient;
+use crate::state::State;
+use crate::window::Window;
+
+#[derive(Debug)]
+pub struct SyncWindow {
+    is_open: bool,
+    address: String,
+    new_track_name: String,
+    last_error: Option<String>,
+}
+
+impl Default for SyncWindow {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            address: RocketClient::DEFAULT_ADDRESS.to_string(),
+            new_track_name: String::default(),
+            last_error: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct SyncWindowSave {
+    #[serde(default)]
+    is_open: bool,
+    #[serde(default)]
+    address: String,
+}
+
+impl From<&SyncWindow> for SyncWindowSave {
+    fn from(sw: &SyncWindow) -> Self {
+        Self {
+            is_open: sw.is_open,
+            address: sw.address.clone(),
+        }
+    }
+}
+
+enum SyncEdit {
+    RemoveTrack {
+        name: String,
+    },
+    SetKey {
+        name: String,
+        key: SyncKey,
+    },
+    MoveKey {
+        name: String,
+        from_row: u32,
+        key: SyncKey,
+    },
+    DeleteKey {
+        name: String,
+        row: u32,
+    },
+}
+
+impl Window for SyncWindow {
+    fn name(&self) -> &str {
+        "Sync"
+    }
+    fn is_open(&self) -> bool {
+        self.is_open
+    }
+    fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    fn update(&mut self, ctx: &egui::Context, state: &mut State) {
+        let time = state
+            .mc_guffin()
+            .map(|mgc| mgc.lock().clock().time())
+            .unwrap_or_default();
+        let mut is_open = self.is_open;
+        egui::Window::new("Sync")
+            .resizable(true)
+            .collapsible(false)
+            .open(&mut is_open)
+            .show(ctx, |ui| {
+                self.update_connection(ui, state);
+                ui.separator();
+
+                let sync_tracks = state.project.sync_tracks();
+                let mut bpm = sync_tracks.bpm();
+                let mut rows_per_beat = sync_tracks.rows_per_beat();
+                let row = sync_tracks.time_to_row(time);
+                ui.horizontal(|ui| {
+                    ui.label("BPM");
+                    let c0 = ui.add(egui::DragValue::new(&mut bpm).speed(0.1)).changed();
+                    ui.label("Rows/Beat");
+                    let c1 = ui
+                        .add(egui::DragValue::new(&mut rows_per_beat).clamp_range(1..=64))
+                        .changed();
+                    if c0 || c1 {
+                        state
+                            .project
+                            .with_sync_tracks_mut(|st| st.set_timing(bpm, rows_per_beat));
+                    }
+                    ui.label(format!("Row {:.2}", row));
+                });
+
+                let mut edits = Vec::default();
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for (name, track) in state.project.sync_tracks().tracks().iter() {
+                            let header = format!("{name} = {:.3}", track.value(row));
+                            egui::CollapsingHeader::new(header)
+                                .id_source(name)
+                                .show(ui, |ui| {
+                                    ui.label(format!("Use as `${{SYNC:{name}}}`"));
+                                    for key in track.keys() {
+                                        let mut k = *key;
+                                        ui.horizontal(|ui| {
+                                            if ui.button("[X]").clicked() {
+                                                edits.push(SyncEdit::DeleteKey {
+                                                    name: name.clone(),
+                                                    row: key.row,
+                                                });
+                                            }
+                                            ui.add(egui::DragValue::new(&mut k.row).prefix("row "));
+                                            ui.add(egui::DragValue::new(&mut k.value).speed(0.01));
+                                            egui::ComboBox::from_id_source((name, key.row))
+                                                .selected_text(format!("{}", k.interpolation))
+                                                .show_ui(ui, |ui| {
+                                                    for i in SyncInterpolation::ALL {
+                                                        ui.selectable_value(
+                                                            &mut k.interpolation,
+                                                            i,
+                                                            format!("{i}"),
+                                                        );
+                                                    }
+                                                });
+                                        });
+                                        if k != *key {
+                                            edits.push(SyncEdit::MoveKey {
+                                                name: name.clone(),
+                                                from_row: key.row,
+                                                key: k,
+                                            });
+                                        }
+                                    }
+                                    ui.horizontal(|ui| {
+                                        if ui.button("Add Key at current Row").clicked() {
+                                            edits.push(SyncEdit::SetKey {
+                                                name: name.clone(),
+                                                key: SyncKey {
+                                                    row: row as u32,
+                                                    value: track.value(row),
+                                                    interpolation: SyncInterpolation::Linear,
+                                                },
+                                            });
+                                        }
+                                        if ui.button("Remove Track").clicked() {
+                                            edits
+                                                .push(SyncEdit::RemoveTrack { name: name.clone() });
+                                        }
+                                    });
+                                });
+                        }
+                    });
+                state.project.with_sync_tracks_mut(|st| {
+                    for edit in edits.drain(..) {
+                        match edit {
+                            SyncEdit::RemoveTrack { name } => {
+                                st.remove_track(&name);
+                            }
+                            SyncEdit::SetKey { name, key } => st.set_key(&name, key),
+                            SyncEdit::MoveKey {
+                                name,
+                                from_row,
+                                key,
+                            } => {
+                                st.delete_key(&name, from_row);
+                                st.set_key(&name, key);
+                            }
+                            SyncEdit::DeleteKey { name, row } => {
+                                st.delete_key(&name, row);
+                            }
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_track_name)
+                            .hint_text("group:name"),
+                    );
+                    if ui
+                        .add_enabled(
+                            !self.new_track_name.is_empty(),
+                            egui::Button::new("Add Track"),
+                        )
+                        .clicked()
+                    {
+                        let name = std::mem::take(&mut self.new_track_name);
+                        state.project.with_sync_tracks_mut(|st| st.add_track(&name));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let has_project_path = state.project_path.is_some();
+                    if ui
+                        .add_enabled(has_project_path, egui::Button::new("Export .track files"))
+                        .on_hover_text("Writes GNU Rocket track files into the `sync` folder")
+                        .clicked()
+                    {
+                        self.last_error = state.export_sync_tracks().err().map(|e| format!("{e}"));
+                    }
+                    if ui
+                        .add_enabled(has_project_path, egui::Button::new("Import .track files"))
+                        .clicked()
+                    {
+                        if let Some(pp) = state.project_path.clone() {
+                            let names = state.project.sync_track_names();
+                            let result = state.project.with_sync_tracks_mut(|st| {
+                                st.import_rocket(&pp.join("sync"), &names)
+                            });
+                            self.last_error = result.err().map(|e| format!("{e}"));
+                        }
+                    }
+                });
+            });
+        self.is_open = is_open;
+    }
+    fn serialize(&self) -> String {
+        let save: SyncWindowSave = self.into();
+
+        ron::ser::to_string(&save).unwrap_or_default()
+    }
+    fn deserialize(&mut self, data: &str) {
+        let save: SyncWindowSave = ron::from_str(data).unwrap_or_default();
+
+        self.is_open = save.is_open;
+        if !save.address.is_empty() {
+            self.address = save.address;
+        }
+    }
+}
+
+impl SyncWindow {
+    fn update_connection(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            if let Some(rocket) = &state.rocket {
+                ui.label(format!(
+                    "Connected to {} ({} tracks)",
+                    self.address,
+                    rocket.track_names().len()
+                ));
+                if ui.button("Disconnect").clicked() {
+                    state.disconnect_rocket();
+                }
+            } else {
+                ui.label("Tracker");
+                ui.add(egui::TextEdit::singleline(&mut self.address).desired_width(120.0));
+                if ui.button("Connect").clicked() {
+                    self.last_error = state
+                        .connect_rocket(&self.address)
+                        .err()
+                        .map(|e| format!("{e}"));
+                }
+            }
+        });
+        if let Some(last_error) = &self.last_error {
+            ui.colored_label(egui::Color32::RED, last_error);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::command::Command;
+use crate::command::TransportCommand;
+use crate::command_queue::COMMAND_QUEUE;
+use crate::state::State;
+use crate::window::Window;
+
+#[derive(Debug, Default)]
+pub struct TransportWindow {
+    is_open: bool,
+    seek_time: f64,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct TransportWindowSave {
+    #[serde(default)]
+    is_open: bool,
+}
+
+impl From<&TransportWindow> for TransportWindowSave {
+    fn from(tw: &TransportWindow) -> Self {
+        Self {
+            is_open: tw.is_open,
+        }
+    }
+}
+
+impl Window for TransportWindow {
+    fn name(&self) -> &str {
+        "Transport"
+    }
+    fn is_open(&self) -> bool {
+        self.is_open
+    }
+    fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    fn update(&mut self, ctx: &egui::Context, state: &mut State) {
+        let Some(mgc) = state.mc_guffin() else {
+            return;
+        };
+        let (clock, flow_time) = {
+            let mg = mgc.lock();
+            (*mg.clock(), *mg.flow_time())
+        };
+
+        let send = |transport_command| {
+            let _ = COMMAND_QUEUE.send(Command::Transport { transport_command });
+        };
+
+        egui::Window::new("Transport")
+            .resizable(false)
+            .collapsible(false)
+            .open(&mut self.is_open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Frame {} Time {:.3}s",
+                    flow_time.frame(),
+                    clock.time()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("|<").on_hover_text("Rewind").clicked() {
+                        send(TransportCommand::Seek { time: 0.0 });
+                    }
+                    if ui.button("<").on_hover_text("Step back").clicked() {
+                        send(TransportCommand::StepFrames { frames: -1 });
+                    }
+                    if clock.is_playing() {
+                        if ui.button("Pause").on_hover_text("Alt+Space").clicked() {
+                            send(TransportCommand::Pause);
+                        }
+                    } else if ui.button("Play").on_hover_text("Alt+Space").clicked() {
+                        send(TransportCommand::Play);
+                    }
+                    if ui.button(">").on_hover_text("Step forward").clicked() {
+                        send(TransportCommand::StepFrames { frames: 1 });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Speed");
+                    let mut speed = clock.speed();
+                    if ui
+                        .add(egui::DragValue::new(&mut speed).speed(0.01).suffix("x"))
+                        .changed()
+                    {
+                        send(TransportCommand::SetSpeed { speed });
+                    }
+                    for s in [0.25, 0.5, 1.0, 2.0] {
+                        if ui.button(format!("{s}x")).clicked() {
+                            send(TransportCommand::SetSpeed { speed: s });
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.seek_time)
+                            .speed(0.1)
+                            .clamp_range(0.0..=f64::MAX)
+                            .suffix("s"),
+                    );
+                    if ui.button("Jump").clicked() {
+                        send(TransportCommand::Seek {
+                            time: self.seek_time,
+                        });
+                    }
+                });
+            });
+    }
+    fn serialize(&self) -> String {
+        let save: TransportWindowSave = self.into();
+
+        ron::ser::to_string(&save).unwrap_or_default()
+    }
+    fn deserialize(&mut self, data: &str) {
+        let save: TransportWindowSave = ron::from_str(data).unwrap_or_default();
+
+        self.is_open = save.is_open;
+    }
+}
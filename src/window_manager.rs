@@ -12,6 +12,14 @@ impl WindowManager {
         self.windows.push(window);
     }
 
+    pub fn open(&mut self, name: &str) {
+        if let Some(window) = self.windows.iter_mut().find(|w| w.name() == name) {
+            if !window.is_open() {
+                window.toggle();
+            }
+        }
+    }
+
     pub fn iter_mut(&mut self) -> IterMut<'_, Box<dyn Window>> {
         self.windows.iter_mut()
     }